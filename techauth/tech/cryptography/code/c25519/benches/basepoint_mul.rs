@@ -0,0 +1,60 @@
+// Compares the fixed-base table with the variable-base window and the X25519 ladder.
+// Run with: cargo bench --bench basepoint_mul
+
+// clippy --all-targets checks benches with cfg(test) but without the test harness, so the
+// test modules below are compiled with their #[test] functions stripped.
+#![cfg_attr(test, allow(unused_imports))]
+
+// The crate is a binary, so the modules under test are compiled in here directly.
+#[path = "../src/binary.rs"]
+mod binary;
+#[path = "../src/bits.rs"]
+mod bits;
+#[path = "../src/edwards.rs"]
+mod edwards;
+#[path = "../src/field.rs"]
+mod field;
+#[path = "../src/montgomery.rs"]
+mod montgomery;
+#[path = "../src/prime_field.rs"]
+mod prime_field;
+#[path = "../src/scalar.rs"]
+mod scalar;
+#[path = "../src/subtle.rs"]
+mod subtle;
+#[cfg(test)]
+#[path = "../src/test_util.rs"]
+mod test_util;
+#[path = "../src/weierstrass.rs"]
+mod weierstrass;
+#[path = "../src/weierstrass_curve.rs"]
+mod weierstrass_curve;
+#[path = "../src/x25519.rs"]
+mod x25519;
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use edwards::Point;
+
+fn bench_basepoint_mul(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0xBE9C);
+    let k: [u8; 32] = rng.gen();
+    let b = Point::basepoint();
+    let mut nine: [u8; 32] = [0; 32];
+    nine[0] = 9;
+
+    // the first call builds the table
+    Point::basepoint_mul(&k);
+
+    c.bench_function("basepoint_mul/table", |bench| bench.iter(|| Point::basepoint_mul(black_box(&k))));
+    c.bench_function("scalar_mul/window", |bench| bench.iter(|| Point::scalar_mul(black_box(&k), &b)));
+    c.bench_function("x25519/ladder", |bench| {
+        let mut u: [u8; 32] = [0; 32];
+        bench.iter(|| x25519::scalar_mul(black_box(k), nine, &mut u))
+    });
+}
+
+criterion_group!(benches, bench_basepoint_mul);
+criterion_main!(benches);
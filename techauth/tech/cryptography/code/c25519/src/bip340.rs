@@ -0,0 +1,232 @@
+#![allow(dead_code)]
+
+use sha2::{Digest, Sha256};
+
+use crate::field_secp256k1::Element;
+use crate::scalar_secp256k1::Scalar;
+use crate::secp256k1::Point;
+
+// BIP-340 Schnorr signatures over secp256k1, with the BIP-341 (Taproot) key tweak.
+//
+// Public keys are x-only: the 32-octet x-coordinate of a point whose y is implicitly even, so
+// a private key d whose [d]G has an odd y signs as n - d. A signature is R.x || s, with R
+// also of even y, and
+//     e = int(hash_BIP0340/challenge(R.x || P.x || m)) mod n,  [s]G = R + [e]P.
+// All hashes are tagged: hash_tag(x) = SHA-256(SHA-256(tag) || SHA-256(tag) || x).
+
+pub const SIGNATURE_SIZE: usize = 64;
+
+// tagged_hash returns hash_tag(parts[0] || parts[1] || ...) [BIP-340, "Design"].
+pub fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> [u8; 32] {
+    let tag_hash = Sha256::digest(tag);
+    let mut h = Sha256::new();
+    h.update(tag_hash);
+    h.update(tag_hash);
+    for part in parts {
+        h.update(part);
+    }
+    h.finalize().into()
+}
+
+// challenge returns e = hash_BIP0340/challenge(r || p || m) mod n.
+fn challenge(r: &[u8; 32], p: &[u8; 32], message: &[u8]) -> Scalar {
+    Scalar::from_be_bytes_mod_order(&tagged_hash(b"BIP0340/challenge", &[r, p, message]))
+}
+
+pub struct PrivateKey {
+    // the secret scalar, already negated if needed so that [d]G has an even y
+    d: Scalar,
+    public: PublicKey,
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKey {
+    point: Point,
+    bytes: [u8; 32],
+}
+
+impl PrivateKey {
+    // new accepts a 32-octet big-endian secret key in [1, n - 1].
+    pub fn new(secret: [u8; 32]) -> Result<PrivateKey, ()> {
+        let d = Scalar::from_be_bytes(&secret)?;
+        if d.is_zero() == 1 {
+            return Err(());
+        }
+        Ok(PrivateKey::from_scalar(&d))
+    }
+
+    // from_scalar negates a nonzero d if [d]G has an odd y.
+    fn from_scalar(d: &Scalar) -> PrivateKey {
+        let (x, y) = Point::basepoint_mul(&d.to_be_bytes()).to_affine().unwrap();
+        let mut d_even = Scalar::ZERO.clone();
+        d_even.select(&Scalar::negate(d), d, y.is_odd());
+        let mut y_even = Element::ZERO.clone();
+        y_even.select(&Element::negate(&y), &y, y.is_odd());
+        let bytes = x.to_be_bytes();
+        let point = Point::from_affine(&x, &y_even).unwrap();
+        PrivateKey { d: d_even, public: PublicKey { point, bytes } }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    // sign signs a message of any length with 32 octets of auxiliary randomness, which only
+    // protects against side channels: with a zero aux_rand, signing is deterministic
+    // [BIP-340, "Default Signing"].
+    pub fn sign(&self, message: &[u8], aux_rand: &[u8; 32]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        let p = &self.public.bytes;
+        let aux = tagged_hash(b"BIP0340/aux", &[aux_rand]);
+        let d = self.d.to_be_bytes();
+        let t: [u8; 32] = core::array::from_fn(|i| d[i] ^ aux[i]);
+        let rand = tagged_hash(b"BIP0340/nonce", &[&t, p, message]);
+        let k = Scalar::from_be_bytes_mod_order(&rand);
+        if k.is_zero() == 1 {
+            return Err(());
+        }
+
+        let (rx, ry) = Point::basepoint_mul(&k.to_be_bytes()).to_affine().unwrap();
+        let mut k_even = Scalar::ZERO.clone();
+        k_even.select(&Scalar::negate(&k), &k, ry.is_odd());
+        let r = rx.to_be_bytes();
+        let e = challenge(&r, p, message);
+        let s = Scalar::add(&k_even, &Scalar::multiply(&e, &self.d));
+
+        let mut signature: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+        signature[..32].copy_from_slice(&r);
+        signature[32..].copy_from_slice(&s.to_be_bytes());
+        Ok(signature)
+    }
+
+    // tap_tweak returns the private key of the BIP-341 output key, d + t, with t as in
+    // PublicKey::tap_tweak.
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<PrivateKey, ()> {
+        let t = self.public.tweak(merkle_root)?;
+        let d = Scalar::add(&self.d, &t);
+        if d.is_zero() == 1 {
+            return Err(());
+        }
+        Ok(PrivateKey::from_scalar(&d))
+    }
+}
+
+impl PublicKey {
+    // from_bytes decodes an x-only public key with lift_x: x must be below p and on the curve.
+    pub fn from_bytes(b: &[u8; 32]) -> Result<PublicKey, ()> {
+        let x = Element::from_be_bytes(b)?;
+        Ok(PublicKey { point: Point::lift_x(&x)?, bytes: *b })
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.bytes
+    }
+
+    // verify checks that r < p, s < n, and that R = [s]G - [e]P is not the identity, has an
+    // even y and the x-coordinate r [BIP-340, "Verification"].
+    pub fn verify(&self, message: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let r: [u8; 32] = signature[..32].try_into().unwrap();
+        let r_x = Element::from_be_bytes(&r)?;
+        let s = Scalar::from_be_bytes(&signature[32..].try_into().unwrap())?;
+        let e = challenge(&r, &self.bytes, message);
+
+        let big_r = Point::add(
+            &Point::basepoint_mul(&s.to_be_bytes()),
+            &Point::scalar_mul(&Scalar::negate(&e).to_be_bytes(), &self.point));
+        let (x, y) = big_r.to_affine()?;
+        if y.is_odd() == 1 || !x.equal(&r_x) {
+            return Err(());
+        }
+        Ok(())
+    }
+
+    // tweak returns t = int(hash_TapTweak(P.x || merkle_root)), failing if t >= n; without a
+    // script tree, the merkle root is omitted [BIP-341, "Constructing and spending Taproot
+    // outputs"].
+    fn tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<Scalar, ()> {
+        let root: &[u8] = match merkle_root {
+            Some(root) => root,
+            None => &[],
+        };
+        Scalar::from_be_bytes(&tagged_hash(b"TapTweak", &[&self.bytes, root]))
+    }
+
+    // tap_tweak returns the BIP-341 output key Q = P + [t]G as an x-only key, together with
+    // the parity of its y, which a script-path spend reveals in the control block.
+    pub fn tap_tweak(&self, merkle_root: Option<&[u8; 32]>) -> Result<(PublicKey, u32), ()> {
+        let t = self.tweak(merkle_root)?;
+        let q = Point::add(&self.point, &Point::basepoint_mul(&t.to_be_bytes()));
+        let (x, y) = q.to_affine()?;
+        let parity = y.is_odd();
+        let mut y_even = Element::ZERO.clone();
+        y_even.select(&Element::negate(&y), &y, parity);
+        let point = Point::from_affine(&x, &y_even).unwrap();
+        Ok((PublicKey { point, bytes: x.to_be_bytes() }, parity))
+    }
+}
+
+#[cfg(test)]
+mod bip340_test {
+    use crate::bip340::{PrivateKey, PublicKey};
+    use crate::scalar_secp256k1::Scalar;
+    use crate::test_util::hex_decode;
+
+    // The test vectors of BIP-340, bip-0340/test-vectors.csv, with the columns index, secret
+    // key, public key, aux_rand, message, signature, verification result and comment.
+    #[test]
+    fn test_bip340_vectors() {
+        let csv = include_str!("../testdata/bip340-test-vectors.csv");
+        for line in csv.lines().skip(1) {
+            let fields: Vec<&str> = line.splitn(8, ',').collect();
+            let (index, secret, public, aux_rand, message, signature, result) =
+                (fields[0], fields[1], fields[2], fields[3], fields[4], fields[5], fields[6]);
+            let message = hex_decode(message);
+            let signature: [u8; 64] = hex_decode(signature).try_into().unwrap();
+
+            if !secret.is_empty() {
+                let sk = PrivateKey::new(hex_decode(secret).try_into().unwrap()).unwrap();
+                assert_eq!(sk.public_key().to_bytes().to_vec(), hex_decode(public), "index {}", index);
+                let aux_rand: [u8; 32] = hex_decode(aux_rand).try_into().unwrap();
+                assert_eq!(sk.sign(&message, &aux_rand), Ok(signature), "index {}", index);
+            }
+
+            let valid = match PublicKey::from_bytes(&hex_decode(public).try_into().unwrap()) {
+                Ok(pk) => pk.verify(&message, &signature).is_ok(),
+                Err(()) => false,
+            };
+            assert_eq!(valid, result == "TRUE", "index {}", index);
+        }
+    }
+
+    // The key-path vectors of BIP-341, bip-0341/wallet-test-vectors.json: an output key
+    // without a script tree and one with a single leaf, and a tweaked private key.
+    #[test]
+    fn test_tap_tweak() {
+        fn _test_tweak_(internal: &str, merkle_root: Option<&str>, output: &str, parity: u32) {
+            let pk = PublicKey::from_bytes(&hex_decode(internal).try_into().unwrap()).unwrap();
+            let root: Option<[u8; 32]> = merkle_root.map(|r| hex_decode(r).try_into().unwrap());
+            let (q, q_parity) = pk.tap_tweak(root.as_ref()).unwrap();
+            assert_eq!(q.to_bytes().to_vec(), hex_decode(output));
+            assert_eq!(q_parity, parity);
+        }
+
+        _test_tweak_("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d", None,
+            "53a1f6e454df1aa2776a2814a721372d6258050de330b3c6d10ee8f4e0dda343", 1);
+        _test_tweak_("187791b6f712a8ea41c8ecdd0ee77fab3e85263b37e1ec18a3651926b3a6cf27",
+            Some("5b75adecf53548f3ec6ad7d78383bf84cc57b55a3127c72b9a2481752dd88b21"),
+            "147c9c57132f6e7ecddba9800bb0c4449251c92a1e60371ee77557b6620f3ea3", 1);
+
+        let sk = PrivateKey::new(hex_decode("6b973d88838f27366ed61c9ad6367663045cb456e28335c109e30717ae0c6baa")
+            .try_into().unwrap()).unwrap();
+        assert_eq!(sk.public_key().to_bytes().to_vec(),
+            hex_decode("d6889cb081036e0faefa3a35157ad71086b123b2b144b649798b494c300a961d"));
+        let tweaked = sk.tap_tweak(None).unwrap();
+        // the tweakedPrivkey d + t of the vector; Q has an odd y, so it signs as n - (d + t)
+        assert_eq!(Scalar::negate(&tweaked.d).to_be_bytes().to_vec(),
+            hex_decode("2405b971772ad26915c8dcdf10f238753a9b837e5f8e6a86fd7c0cce5b7296d9"));
+        let (q, _) = sk.public_key().tap_tweak(None).unwrap();
+        assert_eq!(tweaked.public_key().to_bytes(), q.to_bytes());
+        let signature = tweaked.sign(b"key path spend", &[0; 32]).unwrap();
+        assert!(q.verify(b"key path spend", &signature).is_ok());
+        assert!(sk.public_key().verify(b"key path spend", &signature).is_err());
+    }
+}
@@ -0,0 +1,380 @@
+#![allow(dead_code)]
+
+use sha2::{Digest, Sha256};
+
+use crate::hmac_drbg::HmacDrbg;
+use crate::p256::{Point, UNCOMPRESSED_SIZE};
+use crate::scalar_p256::Scalar;
+
+// ECDSA over P-256 with SHA-256 [FIPS 186-5, 6.4], with the deterministic nonces of RFC 6979:
+// k is drawn from an HMAC_DRBG seeded with the private key and the message hash, so signing
+// needs no randomness and never reuses a nonce across messages.
+//
+// Signatures come in two encodings: the fixed-width r || s of 64 octets, and the DER
+// SEQUENCE { INTEGER r, INTEGER s } of X.509 and TLS, which is parsed strictly (minimal
+// lengths and integers, no trailing data), as Wycheproof expects.
+
+pub const SIGNATURE_SIZE: usize = 64;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Signature {
+    r: Scalar,
+    s: Scalar,
+}
+
+pub struct PrivateKey {
+    d: Scalar,
+    public: PublicKey,
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKey {
+    point: Point,
+}
+
+// hash_to_scalar returns bits2int(H(m)) mod n; the hash and the order both have 256 bits, so
+// bits2int keeps all of them [RFC 6979, 2.3.2].
+fn hash_to_scalar(digest: &[u8; 32]) -> Scalar {
+    Scalar::from_be_bytes_mod_order(digest)
+}
+
+impl PrivateKey {
+    // new accepts a big-endian private key d in [1, n - 1].
+    pub fn new(key: [u8; 32]) -> Result<PrivateKey, ()> {
+        let d = Scalar::from_be_bytes(&key)?;
+        if d.is_zero() == 1 {
+            return Err(());
+        }
+        let public = PublicKey { point: Point::basepoint_mul(&key) };
+        Ok(PrivateKey { d, public })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    pub fn sign(&self, message: &[u8]) -> Signature {
+        self.sign_prehashed(&Sha256::digest(message).into())
+    }
+
+    // sign_prehashed signs a SHA-256 digest:
+    //     r = x([k]G) mod n,  s = (e + r*d) / k mod n,
+    // and draws another k in the unlikely case that r or s is zero.
+    pub fn sign_prehashed(&self, digest: &[u8; 32]) -> Signature {
+        let e = hash_to_scalar(digest);
+        let mut drbg = HmacDrbg::new(&[&self.d.to_be_bytes(), &e.to_be_bytes()]);
+        loop {
+            let k = PrivateKey::nonce(&mut drbg);
+            let (x, _) = Point::basepoint_mul(&k.to_be_bytes()).to_affine().unwrap();
+            let r = Scalar::from_be_bytes_mod_order(&x.to_be_bytes());
+            let s = Scalar::multiply(&Scalar::invert(&k), &Scalar::add(&e, &Scalar::multiply(&r, &self.d)));
+            if r.is_zero() == 0 && s.is_zero() == 0 {
+                return Signature { r, s };
+            }
+        }
+    }
+
+    // nonce returns the next candidate of RFC 6979, Section 3.2, steps h.1 to h.3: the DRBG
+    // output until it is in [1, n - 1]. The DRBG is seeded with int2octets(d) and
+    // bits2octets(H(m)), and its update between two outputs is the step h.3 retry.
+    fn nonce(drbg: &mut HmacDrbg) -> Scalar {
+        loop {
+            let mut t: [u8; 32] = [0; 32];
+            drbg.generate(&mut t);
+            if let Ok(k) = Scalar::from_be_bytes(&t) {
+                if k.is_zero() == 0 {
+                    return k;
+                }
+            }
+        }
+    }
+}
+
+impl PublicKey {
+    // from_sec1 decodes and validates a public key, like p256::PublicKey::from_sec1.
+    pub fn from_sec1(b: &[u8]) -> Result<PublicKey, ()> {
+        Ok(PublicKey { point: Point::from_sec1(b)? })
+    }
+
+    pub fn to_uncompressed(&self) -> [u8; UNCOMPRESSED_SIZE] {
+        self.point.to_uncompressed().unwrap()
+    }
+
+    pub fn verify(&self, message: &[u8], signature: &Signature) -> Result<(), ()> {
+        self.verify_prehashed(&Sha256::digest(message).into(), signature)
+    }
+
+    // verify_prehashed checks that x([e/s]G + [r/s]Q) = r (mod n). It accepts both s and
+    // n - s; callers that want a unique signature should require is_low_s.
+    pub fn verify_prehashed(&self, digest: &[u8; 32], signature: &Signature) -> Result<(), ()> {
+        let e = hash_to_scalar(digest);
+        let w = Scalar::invert(&signature.s);
+        let u1 = Scalar::multiply(&e, &w);
+        let u2 = Scalar::multiply(&signature.r, &w);
+        let p = Point::add(
+            &Point::basepoint_mul(&u1.to_be_bytes()),
+            &Point::scalar_mul(&u2.to_be_bytes(), &self.point));
+        let (x, _) = p.to_affine()?;
+        if Scalar::from_be_bytes_mod_order(&x.to_be_bytes()) != signature.r {
+            return Err(());
+        }
+        Ok(())
+    }
+}
+
+// der_integer appends the minimal DER INTEGER encoding of a non-negative a.
+fn der_integer(out: &mut Vec<u8>, a: &Scalar) {
+    let b = a.to_be_bytes();
+    let start = b.iter().position(|&x| x != 0).unwrap_or(31);
+    let pad = (b[start] >> 7) as usize;
+    out.push(0x02);
+    out.push((32 - start + pad) as u8);
+    if pad == 1 {
+        out.push(0x00);
+    }
+    out.extend_from_slice(&b[start..]);
+}
+
+// parse_der_integer parses a DER INTEGER in [1, n - 1] from the front of b, and returns it
+// with the rest of b.
+fn parse_der_integer(b: &[u8]) -> Result<(Scalar, &[u8]), ()> {
+    if b.len() < 2 || b[0] != 0x02 || b[1] >= 0x80 {
+        return Err(());
+    }
+    let len = b[1] as usize;
+    if len == 0 || b.len() < 2 + len {
+        return Err(());
+    }
+    let (v, rest) = b[2..].split_at(len);
+
+    // negative, or a leading zero that is not needed for the sign
+    if v[0] & 0x80 != 0 || (len > 1 && v[0] == 0 && v[1] & 0x80 == 0) {
+        return Err(());
+    }
+    let v = if v[0] == 0 && len > 1 { &v[1..] } else { v };
+    if v.len() > 32 {
+        return Err(());
+    }
+    let mut a: [u8; 32] = [0; 32];
+    a[32 - v.len()..].copy_from_slice(v);
+    let a = Scalar::from_be_bytes(&a)?;
+    if a.is_zero() == 1 {
+        return Err(());
+    }
+    Ok((a, rest))
+}
+
+impl Signature {
+    // from_bytes decodes r || s, and fails unless both are in [1, n - 1].
+    pub fn from_bytes(b: &[u8; SIGNATURE_SIZE]) -> Result<Signature, ()> {
+        let r = Scalar::from_be_bytes(&b[..32].try_into().unwrap())?;
+        let s = Scalar::from_be_bytes(&b[32..].try_into().unwrap())?;
+        if r.is_zero() == 1 || s.is_zero() == 1 {
+            return Err(());
+        }
+        Ok(Signature { r, s })
+    }
+
+    pub fn to_bytes(&self) -> [u8; SIGNATURE_SIZE] {
+        let mut b: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+        b[..32].copy_from_slice(&self.r.to_be_bytes());
+        b[32..].copy_from_slice(&self.s.to_be_bytes());
+        b
+    }
+
+    // from_der decodes SEQUENCE { INTEGER r, INTEGER s }. The encoding is at most 72 octets,
+    // so every length must use the short form.
+    pub fn from_der(b: &[u8]) -> Result<Signature, ()> {
+        if b.len() < 2 || b[0] != 0x30 || b[1] >= 0x80 || b[1] as usize != b.len() - 2 {
+            return Err(());
+        }
+        let (r, rest) = parse_der_integer(&b[2..])?;
+        let (s, rest) = parse_der_integer(rest)?;
+        if !rest.is_empty() {
+            return Err(());
+        }
+        Ok(Signature { r, s })
+    }
+
+    pub fn to_der(&self) -> Vec<u8> {
+        let mut body: Vec<u8> = Vec::with_capacity(70);
+        der_integer(&mut body, &self.r);
+        der_integer(&mut body, &self.s);
+        let mut b = vec![0x30, body.len() as u8];
+        b.extend_from_slice(&body);
+        b
+    }
+
+    pub fn is_low_s(&self) -> bool {
+        self.s.is_high() == 0
+    }
+
+    // normalize_s returns the signature with s replaced by n - s if s > (n - 1)/2. Both verify,
+    // and the low-s form is the unique one that BIP 62 and many verifiers insist on.
+    pub fn normalize_s(&self) -> Signature {
+        let mut s = Scalar::ZERO.clone();
+        s.select(&Scalar::negate(&self.s), &self.s, self.s.is_high());
+        Signature { r: self.r.clone(), s }
+    }
+}
+
+#[cfg(test)]
+mod ecdsa_p256_test {
+    use sha2::{Digest, Sha256};
+
+    use crate::ecdsa_p256::{PrivateKey, Signature, hash_to_scalar};
+    use crate::hmac_drbg::HmacDrbg;
+    use crate::test_util::hex_decode;
+
+    fn rfc6979_key() -> PrivateKey {
+        PrivateKey::new(hex_decode("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+            .try_into().unwrap()).unwrap()
+    }
+
+    // RFC 6979, Appendix A.2.5, ECDSA with P-256 and SHA-256.
+    #[test]
+    fn test_rfc6979() {
+        let sk = rfc6979_key();
+        assert_eq!(sk.public_key().to_uncompressed().to_vec(), hex_decode(
+            "0460fed4ba255a9d31c961eb74c6356d68c049b8923b61fa6ce669622e60f29fb6\
+             7903fe1008b8bc99a41ae9e95628bc64f2f1b20c2d7e9f5177a3c294d4462299"));
+
+        fn _test_sign_(sk: &PrivateKey, message: &[u8], k: &str, r: &str, s: &str) {
+            let e = hash_to_scalar(&Sha256::digest(message).into());
+            let mut drbg = HmacDrbg::new(&[&sk.d.to_be_bytes(), &e.to_be_bytes()]);
+            assert_eq!(PrivateKey::nonce(&mut drbg).to_be_bytes().to_vec(), hex_decode(k));
+
+            let signature = sk.sign(message);
+            assert_eq!(signature.to_bytes().to_vec(), hex_decode(&format!("{}{}", r, s)));
+            assert!(sk.public_key().verify(message, &signature).is_ok());
+            assert!(sk.public_key().verify(b"other message", &signature).is_err());
+        }
+
+        _test_sign_(&sk, b"sample",
+            "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60",
+            "efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716",
+            "f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8");
+        _test_sign_(&sk, b"test",
+            "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0",
+            "f1abb023518351cd71d881567b1ea663ed3efcf6c5132b354f28d3b0b7d38367",
+            "019f4113742a2b14bd25926b49c649155f267e60d3814b4c0cc84250e46f0083");
+    }
+
+    // The "sample" signature has a high s; its low-s form also verifies.
+    #[test]
+    fn test_low_s() {
+        let sk = rfc6979_key();
+        let high = sk.sign(b"sample");
+        assert!(!high.is_low_s());
+        let low = high.normalize_s();
+        assert!(low.is_low_s());
+        assert_eq!(low.to_bytes()[32..].to_vec(),
+            hex_decode("0834e36ad29a83bf2bc9385e491d6099c8fdf9d1ed67aa7ea5f51f93782857a9"));
+        assert!(sk.public_key().verify(b"sample", &low).is_ok());
+        assert_eq!(low.normalize_s(), low);
+
+        let already_low = sk.sign(b"test");
+        assert!(already_low.is_low_s());
+        assert_eq!(already_low.normalize_s(), already_low);
+    }
+
+    #[test]
+    fn test_encodings() {
+        let sk = rfc6979_key();
+        let signature = sk.sign(b"sample");
+        assert_eq!(Signature::from_bytes(&signature.to_bytes()), Ok(signature.clone()));
+
+        // r needs a zero octet for its sign, s does not
+        let der = signature.to_der();
+        assert_eq!(der, hex_decode(
+            "3046022100efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716\
+             022100f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8"));
+        assert_eq!(Signature::from_der(&der), Ok(signature));
+        let signature = sk.sign(b"test");
+        assert_eq!(&signature.to_der()[37..41], &[0x02, 0x20, 0x01, 0x9f]);
+        assert_eq!(Signature::from_der(&signature.to_der()), Ok(signature));
+
+        // a short r, whose leading zero octets are dropped
+        let mut short: [u8; 64] = [0; 64];
+        short[31] = 0x7f;
+        short[63] = 0x80;
+        let short = Signature::from_bytes(&short).unwrap();
+        assert_eq!(short.to_der(), vec![0x30, 0x07, 0x02, 0x01, 0x7f, 0x02, 0x02, 0x00, 0x80]);
+        assert_eq!(Signature::from_der(&short.to_der()), Ok(short));
+
+        // r = 0 and s = n are out of range
+        let n = "ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+        let zero_r: [u8; 64] = hex_decode(&format!("{}{}", "00".repeat(32), "01".repeat(32))).try_into().unwrap();
+        assert!(Signature::from_bytes(&zero_r).is_err());
+        let n_s: [u8; 64] = hex_decode(&format!("{}{}", "01".repeat(32), n)).try_into().unwrap();
+        assert!(Signature::from_bytes(&n_s).is_err());
+    }
+
+    // Malformed DER in the manner of Wycheproof's ecdsa_secp256r1_sha256 test groups: every
+    // variant of the valid signature r = 0x7f, s = 0x80 below must be rejected.
+    #[test]
+    fn test_malformed_der() {
+        let valid = "3007" .to_owned() + "02017f" + "02020080";
+        assert!(Signature::from_der(&hex_decode(&valid)).is_ok());
+
+        let n = "00ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551";
+        let invalid = [
+            // empty and truncated
+            "".to_owned(),
+            "30".to_owned(),
+            "3007".to_owned() + "02017f" + "020200",
+            "3007".to_owned() + "02017f",
+            // wrong tags
+            "3107".to_owned() + "02017f" + "02020080",
+            "3007".to_owned() + "03017f" + "02020080",
+            "3007".to_owned() + "02017f" + "04020080",
+            // sequence length too long, too short, long form and indefinite
+            "3008".to_owned() + "02017f" + "02020080",
+            "3006".to_owned() + "02017f" + "02020080",
+            "308107".to_owned() + "02017f" + "02020080",
+            "3080".to_owned() + "02017f" + "02020080" + "0000",
+            // trailing data, inside and outside the sequence
+            "3009".to_owned() + "02017f" + "02020080" + "0500",
+            "3007".to_owned() + "02017f" + "02020080" + "00",
+            // integer length long form, zero, or past the end
+            "3008".to_owned() + "0281017f" + "02020080",
+            "3006".to_owned() + "0200" + "02020080",
+            "3007".to_owned() + "02027f" + "02020080",
+            // negative r, and s without its sign octet
+            "3007".to_owned() + "0201ff" + "02020080",
+            "3006".to_owned() + "02017f" + "020180",
+            // non-minimal integers
+            "3008".to_owned() + "0202007f" + "02020080",
+            "3009".to_owned() + "02017f" + "0203000080",
+            // a missing, an extra and a zero integer
+            "3003".to_owned() + "02017f",
+            "300a".to_owned() + "02017f" + "02020080" + "020101",
+            "3006".to_owned() + "020100" + "02020080",
+            // r = n, and an integer of 34 octets
+            format!("3027022100{}02020080", &n[2..]),
+            format!("3028022200{}02020080", n),
+        ];
+        for der in invalid {
+            assert!(Signature::from_der(&hex_decode(&der)).is_err(), "{}", der);
+        }
+    }
+
+    #[test]
+    fn test_verify_invalid() {
+        let sk = rfc6979_key();
+        let signature = sk.sign(b"sample");
+
+        // a different key, swapped r and s, and a modified r
+        let other = PrivateKey::new([0x01; 32]).unwrap();
+        assert!(other.public_key().verify(b"sample", &signature).is_err());
+        let b = signature.to_bytes();
+        let swapped: [u8; 64] = [&b[32..], &b[..32]].concat().try_into().unwrap();
+        assert!(sk.public_key().verify(b"sample", &Signature::from_bytes(&swapped).unwrap()).is_err());
+        let mut modified = b;
+        modified[31] ^= 1;
+        assert!(sk.public_key().verify(b"sample", &Signature::from_bytes(&modified).unwrap()).is_err());
+
+        assert!(PrivateKey::new([0; 32]).is_err());
+        assert!(PrivateKey::new([0xff; 32]).is_err());
+    }
+}
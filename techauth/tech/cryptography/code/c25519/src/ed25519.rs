@@ -0,0 +1,839 @@
+#![allow(dead_code)]
+
+use rand::{CryptoRng, RngCore};
+use sha2::{Digest, Sha512};
+
+use crate::edwards::Point;
+use crate::scalar::Scalar;
+use crate::subtle;
+use crate::x25519;
+
+// Ed25519, Ed25519ctx and Ed25519ph as specified in RFC 8032, Section 5.1.
+//
+// The three variants differ only in the dom2 prefix that is hashed in front of
+// the nonce and challenge inputs, and in whether the message is hashed first:
+//     Ed25519     dom2 is empty,                      M' = M
+//     Ed25519ctx  dom2(0, context), 0 < |context|,    M' = M
+//     Ed25519ph   dom2(1, context),                   M' = SHA-512(M)
+// All of them share the same keys.
+
+pub const SIGNATURE_SIZE: usize = 64;
+pub const CONTEXT_MAX_SIZE: usize = 255;
+
+// dom2(phflag, context) = "SigEd25519 no Ed25519 collisions" || octet(phflag) ||
+//                          octet(OLEN(context)) || context
+const DOM2_PREFIX: &[u8] = b"SigEd25519 no Ed25519 collisions";
+
+fn dom2(phflag: u8, context: &[u8]) -> Result<Vec<u8>, ()> {
+    if context.len() > CONTEXT_MAX_SIZE {
+        return Err(());
+    }
+    let mut dom: Vec<u8> = Vec::with_capacity(DOM2_PREFIX.len() + 2 + context.len());
+    dom.extend_from_slice(DOM2_PREFIX);
+    dom.push(phflag);
+    dom.push(context.len() as u8);
+    dom.extend_from_slice(context);
+    Ok(dom)
+}
+
+// Ed25519ctx must not be used with an empty context; RFC 8032 points callers
+// to plain Ed25519 instead.
+fn dom2_ctx(context: &[u8]) -> Result<Vec<u8>, ()> {
+    if context.is_empty() {
+        return Err(());
+    }
+    dom2(0, context)
+}
+
+fn dom2_ph(context: &[u8]) -> Result<Vec<u8>, ()> {
+    dom2(1, context)
+}
+
+// VerificationPolicy selects which Ed25519 signatures are accepted.
+// RFC 8032 leaves enough room that implementations disagree on edge cases: small-order
+// keys and nonces, non-canonical point encodings, and whether the verification equation
+// is multiplied by the cofactor 8 ("Taming the many EdDSAs", Chalkias et al., 2020).
+//
+//                  encodings of A, R   small-order A, R   equation
+//    Strict        canonical           rejected           [S]B = R + [k]A
+//    Cofactorless  canonical           accepted           [S]B = R + [k]A
+//    Cofactored    canonical           accepted           [8][S]B = [8]R + [8][k]A
+//    Zip215        any                 accepted           [8][S]B = [8]R + [8][k]A
+//
+// Every policy requires S < l and hashes the encodings of R and A as received.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum VerificationPolicy {
+    Strict,
+    Cofactorless,
+    Cofactored,
+    Zip215,
+}
+
+impl VerificationPolicy {
+    fn is_cofactored(self) -> bool {
+        matches!(self, VerificationPolicy::Cofactored | VerificationPolicy::Zip215)
+    }
+}
+
+// hash_to_scalar returns SHA-512(parts[0] || parts[1] || ...) mod l.
+pub(crate) fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    let mut h = Sha512::new();
+    for part in parts {
+        h.update(part);
+    }
+    Scalar::from_bytes_mod_order_wide(&h.finalize().into())
+}
+
+// verify_batch checks many pure Ed25519 signatures at once and accepts exactly the
+// signatures that verify_with_policy accepts under the same policy.
+//
+// Each signature i contributes the equation [S_i]B = R_i + [k_i]A_i. For the cofactored
+// policies the batch is accepted if a random linear combination of these equations holds:
+//     [8]([sum(z_i * S_i)]B - sum([z_i]R_i) - sum([z_i * k_i]A_i)) == 0,
+// which is evaluated with a single multiscalar multiplication. The 128-bit
+// coefficients z_i come from rng; a batch containing an invalid signature passes
+// with probability about 2^-128. If the combined check fails, every signature is
+// verified on its own, and the indices of the invalid ones are returned.
+//
+// The cofactorless policies cannot be batched this way: a nonzero small-order residue
+// vanishes in the combination whenever z_i happens to be a multiple of its order, so
+// the outcome would depend on rng. Under Strict and Cofactorless every signature is
+// therefore verified on its own.
+pub fn verify_batch<R: RngCore + CryptoRng>(
+    messages: &[&[u8]],
+    signatures: &[[u8; SIGNATURE_SIZE]],
+    public_keys: &[PublicKey],
+    policy: VerificationPolicy,
+    rng: &mut R,
+) -> Result<(), Vec<usize>> {
+    assert_eq!(messages.len(), signatures.len());
+    assert_eq!(messages.len(), public_keys.len());
+
+    let mut invalid: Vec<usize> = Vec::new();
+    if !policy.is_cofactored() {
+        for i in 0..messages.len() {
+            if public_keys[i].verify_with_policy(messages[i], &signatures[i], policy).is_err() {
+                invalid.push(i);
+            }
+        }
+        return if invalid.is_empty() { Ok(()) } else { Err(invalid) };
+    }
+
+    let mut candidates: Vec<usize> = Vec::with_capacity(messages.len());
+    let mut b_coefficient = Scalar::ZERO.clone();
+    let mut scalars: Vec<Scalar> = Vec::with_capacity(2 * messages.len() + 1);
+    let mut points: Vec<Point> = Vec::with_capacity(2 * messages.len() + 1);
+
+    for i in 0..messages.len() {
+        let Ok((a, r, s)) = public_keys[i].decode_signature(&signatures[i], policy) else {
+            invalid.push(i);
+            continue;
+        };
+        let k = hash_to_scalar(&[&signatures[i][..32], &public_keys[i].public, messages[i]]);
+
+        let mut z_bytes: [u8; 32] = [0; 32];
+        rng.fill_bytes(&mut z_bytes[..16]);
+        let z = Scalar(z_bytes);
+
+        b_coefficient = Scalar::mul_add(&z, &s, &b_coefficient);
+        scalars.push(Scalar::negate(&z));
+        points.push(r);
+        scalars.push(Scalar::negate(&Scalar::multiply(&z, &k)));
+        points.push(a);
+        candidates.push(i);
+    }
+    scalars.push(b_coefficient);
+    points.push(Point::basepoint());
+
+    let check = Point::vartime_multiscalar_mul(&scalars, &points);
+    if !Point::mul_by_cofactor(&check).is_identity() {
+        for i in candidates {
+            if public_keys[i].verify_with_policy(messages[i], &signatures[i], policy).is_err() {
+                invalid.push(i);
+            }
+        }
+        invalid.sort();
+    }
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+pub struct PrivateKey {
+    seed: [u8; 32],
+    // the clamped lower half of SHA-512(seed)
+    pub(crate) scalar: [u8; 32],
+    // the upper half of SHA-512(seed), used to derive the per-message nonce
+    pub(crate) prefix: [u8; 32],
+    public: PublicKey,
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKey {
+    pub(crate) public: [u8; 32],
+}
+
+impl PrivateKey {
+    // new expands a 32-octet seed into a signing key [RFC 8032, 5.1.5].
+    pub fn new(seed: [u8; 32]) -> PrivateKey {
+        let h: [u8; 64] = Sha512::digest(seed).into();
+        let mut scalar: [u8; 32] = h[..32].try_into().unwrap();
+        scalar[0] &= 0xF8;
+        scalar[31] = (scalar[31] & 0x7F) | 0x40;
+        let prefix: [u8; 32] = h[32..].try_into().unwrap();
+
+        let public = PublicKey::new(Point::basepoint_mul(&scalar).to_bytes());
+        PrivateKey { seed, scalar, prefix, public }
+    }
+
+    pub fn seed(&self) -> [u8; 32] {
+        self.seed
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    // to_x25519 returns the X25519 private key with the same secret scalar, the clamped lower
+    // half of SHA-512(seed), as crypto_sign_ed25519_sk_to_curve25519 of libsodium does.
+    // Its public key is the to_x25519 image of this key's public key.
+    pub fn to_x25519(&self) -> x25519::PrivateKey {
+        x25519::PrivateKey::new(self.scalar)
+    }
+
+    // sign returns a pure Ed25519 signature of message.
+    pub fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_SIZE] {
+        self.sign_with_dom(&[], message)
+    }
+
+    // sign_ctx returns an Ed25519ctx signature of message under context,
+    // which must be between 1 and 255 octets long.
+    pub fn sign_ctx(&self, message: &[u8], context: &[u8]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        let dom = dom2_ctx(context)?;
+        Ok(self.sign_with_dom(&dom, message))
+    }
+
+    // sign_ph returns an Ed25519ph signature of message under context (at most 255 octets).
+    pub fn sign_ph(&self, message: &[u8], context: &[u8]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        self.sign_prehashed(Sha512::new_with_prefix(message), context)
+    }
+
+    // sign_prehashed returns an Ed25519ph signature of the message absorbed by prehash.
+    // Large messages can be fed to the SHA-512 state incrementally and signed without
+    // ever being held in memory as a whole.
+    pub fn sign_prehashed(&self, prehash: Sha512, context: &[u8]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        let dom = dom2_ph(context)?;
+        let digest: [u8; 64] = prehash.finalize().into();
+        Ok(self.sign_with_dom(&dom, &digest))
+    }
+
+    // sign_with_dom implements RFC 8032, Section 5.1.6 for the prefix dom and message m.
+    fn sign_with_dom(&self, dom: &[u8], m: &[u8]) -> [u8; SIGNATURE_SIZE] {
+        // r = SHA-512(dom2 || prefix || M') mod l;  R = [r]B
+        let r = hash_to_scalar(&[dom, &self.prefix, m]);
+        let r_bytes = Point::basepoint_mul(&r.to_bytes()).to_bytes();
+
+        // k = SHA-512(dom2 || R || A || M') mod l;  S = (r + k * s) mod l
+        let k = hash_to_scalar(&[dom, &r_bytes, &self.public.public, m]);
+        let s = Scalar::mul_add_bytes(&k.to_bytes(), &self.scalar, &r.to_bytes());
+
+        let mut signature: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+        signature[..32].copy_from_slice(&r_bytes);
+        signature[32..].copy_from_slice(&s.to_bytes());
+        signature
+    }
+}
+
+impl PublicKey {
+    pub fn new(key: [u8; 32]) -> PublicKey {
+        PublicKey {
+            public: key
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.public
+    }
+
+    // to_x25519 returns the X25519 public key u = (1 + y) / (1 - y) of the point A.
+    // Like crypto_sign_ed25519_pk_to_curve25519 of libsodium, it fails unless A decodes to
+    // a point of order l, so that a key with a small-order component cannot be converted.
+    pub fn to_x25519(&self) -> Result<x25519::PublicKey, ()> {
+        let a = Point::from_bytes(&self.public)?;
+        if a.is_small_order() || !a.is_torsion_free() {
+            return Err(());
+        }
+        Ok(x25519::PublicKey::new(a.to_montgomery()))
+    }
+
+    // verify checks a pure Ed25519 signature of message with the cofactorless equation.
+    pub fn verify(&self, message: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        self.verify_with_policy(message, signature, VerificationPolicy::Cofactorless)
+    }
+
+    // verify_ctx checks an Ed25519ctx signature of message under context.
+    pub fn verify_ctx(&self, message: &[u8], context: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let dom = dom2_ctx(context)?;
+        self.verify_with_dom(&dom, message, signature, VerificationPolicy::Cofactorless)
+    }
+
+    // verify_ph checks an Ed25519ph signature of message under context.
+    pub fn verify_ph(&self, message: &[u8], context: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        self.verify_prehashed(Sha512::new_with_prefix(message), context, signature)
+    }
+
+    // verify_prehashed checks an Ed25519ph signature of the message absorbed by prehash.
+    pub fn verify_prehashed(&self, prehash: Sha512, context: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let dom = dom2_ph(context)?;
+        let digest: [u8; 64] = prehash.finalize().into();
+        self.verify_with_dom(&dom, &digest, signature, VerificationPolicy::Cofactorless)
+    }
+
+    // verify_with_policy checks a pure Ed25519 signature of message under the given policy.
+    pub fn verify_with_policy(&self, message: &[u8], signature: &[u8; SIGNATURE_SIZE], policy: VerificationPolicy) -> Result<(), ()> {
+        self.verify_with_dom(&[], message, signature, policy)
+    }
+
+    // decode_signature decodes the public key A and the signature components R and S
+    // according to policy. S must always be reduced modulo l.
+    fn decode_signature(&self, signature: &[u8; SIGNATURE_SIZE], policy: VerificationPolicy) -> Result<(Point, Point, Scalar), ()> {
+        fn decode_point(b: &[u8; 32], policy: VerificationPolicy) -> Result<Point, ()> {
+            let p = Point::from_bytes(b)?;
+            if policy != VerificationPolicy::Zip215 && !subtle::constant_time_compare(&p.to_bytes(), b) {
+                return Err(());
+            }
+            if policy == VerificationPolicy::Strict && p.is_small_order() {
+                return Err(());
+            }
+            Ok(p)
+        }
+
+        let a = decode_point(&self.public, policy)?;
+        let r = decode_point(&signature[..32].try_into().unwrap(), policy)?;
+        let s = Scalar::from_canonical_bytes(signature[32..].try_into().unwrap())?;
+        Ok((a, r, s))
+    }
+
+    // verify_with_dom implements RFC 8032, Section 5.1.7 for the prefix dom and message m.
+    // The challenge k is computed over the encodings of R and A as they were received.
+    fn verify_with_dom(&self, dom: &[u8], m: &[u8], signature: &[u8; SIGNATURE_SIZE], policy: VerificationPolicy) -> Result<(), ()> {
+        let (a, r, s) = self.decode_signature(signature, policy)?;
+        let k = hash_to_scalar(&[dom, &signature[..32], &self.public, m]);
+
+        // [S]B - [k]A - R, computed in variable time since all of its inputs are public.
+        // A is negated rather than k: [l - k]A differs from -[k]A when A has a small-order component.
+        let sb_ka = Point::vartime_double_scalar_base_mul(&k, &Point::negate(&a), &s);
+        let mut check = Point::subtract(&sb_ka, &r);
+        if policy.is_cofactored() {
+            check = Point::mul_by_cofactor(&check);
+        }
+
+        if check.is_identity() {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_ed25519 {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use sha2::{Digest, Sha512};
+
+    use crate::ed25519::{hash_to_scalar, verify_batch, PrivateKey, PublicKey, VerificationPolicy, SIGNATURE_SIZE};
+    use crate::edwards::Point;
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+
+    const POLICIES: [VerificationPolicy; 4] = [
+        VerificationPolicy::Strict,
+        VerificationPolicy::Cofactorless,
+        VerificationPolicy::Cofactored,
+        VerificationPolicy::Zip215,
+    ];
+
+    fn keys(sk_hex: &str, pk_hex: &str) -> (PrivateKey, PublicKey) {
+        let sk = PrivateKey::new(hex_decode(sk_hex).try_into().unwrap());
+        let pk = PublicKey::new(hex_decode(pk_hex).try_into().unwrap());
+        assert_eq!(sk.public_key().to_bytes(), pk.to_bytes());
+        (sk, pk)
+    }
+
+    fn signature(sig_hex: &str) -> [u8; SIGNATURE_SIZE] {
+        hex_decode(sig_hex).try_into().unwrap()
+    }
+
+    // Ed25519 test vectors from RFC 8032, Section 7.1 (TEST 1, 2 and 3).
+    #[test]
+    fn test_ed25519_rfc8032() {
+        fn _test_ed25519_(sk_hex: &str, pk_hex: &str, msg_hex: &str, sig_hex: &str) {
+            let (sk, pk) = keys(sk_hex, pk_hex);
+            let msg = hex_decode(msg_hex);
+            let sig = signature(sig_hex);
+            assert_eq!(sk.sign(&msg), sig);
+            assert!(pk.verify(&msg, &sig).is_ok());
+        }
+
+        _test_ed25519_(
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b");
+        _test_ed25519_(
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00");
+        _test_ed25519_(
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a");
+    }
+
+    // Ed25519ctx test vectors from RFC 8032, Section 7.2.
+    #[test]
+    fn test_ed25519ctx_rfc8032() {
+        fn _test_ed25519ctx_(sk_hex: &str, pk_hex: &str, msg_hex: &str, ctx_hex: &str, sig_hex: &str) {
+            let (sk, pk) = keys(sk_hex, pk_hex);
+            let msg = hex_decode(msg_hex);
+            let ctx = hex_decode(ctx_hex);
+            let sig = signature(sig_hex);
+            assert!(matches!(sk.sign_ctx(&msg, &ctx), Ok(s) if s == sig));
+            assert!(pk.verify_ctx(&msg, &ctx, &sig).is_ok());
+            // The context is bound to the signature, and so is the variant.
+            assert!(pk.verify_ctx(&msg, b"baz", &sig).is_err());
+            assert!(pk.verify(&msg, &sig).is_err());
+            assert!(pk.verify_ph(&msg, &ctx, &sig).is_err());
+        }
+
+        _test_ed25519ctx_(
+            "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+            "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+            "f726936d19c800494e3fdaff20b276a8",
+            "666f6f",
+            "55a4cc2f70a54e04288c5f4cd1e45a7bb520b36292911876cada7323198dd87a8b36950b95130022907a7fb7c4e9b2d5f6cca685a587b4b21f4b888e4e7edb0d");
+        _test_ed25519ctx_(
+            "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+            "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+            "f726936d19c800494e3fdaff20b276a8",
+            "626172",
+            "fc60d5872fc46b3aa69f8b5b4351d5808f92bcc044606db097abab6dbcb1aee3216c48e8b3b66431b5b186d1d28f8ee15a5ca2df6668346291c2043d4eb3e90d");
+        _test_ed25519ctx_(
+            "0305334e381af78f141cb666f6199f57bc3495335a256a95bd2a55bf546663f6",
+            "dfc9425e4f968f7f0c29f0259cf5f9aed6851c2bb4ad8bfb860cfee0ab248292",
+            "508e9e6882b979fea900f62adceaca35",
+            "666f6f",
+            "8b70c1cc8310e1de20ac53ce28ae6e7207f33c3295e03bb5c0732a1d20dc64908922a8b052cf99b7c4fe107a5abb5b2c4085ae75890d02df26269d8945f84b0b");
+        _test_ed25519ctx_(
+            "ab9c2853ce297ddab85c993b3ae14bcad39b2c682beabc27d6d4eb20711d6560",
+            "0f1d1274943b91415889152e893d80e93275a1fc0b65fd71b4b0dda10ad7d772",
+            "f726936d19c800494e3fdaff20b276a8",
+            "666f6f",
+            "21655b5f1aa965996b3f97b3c849eafba922a0a62992f73b3d1b73106a84ad85e9b86a7b6005ea868337ff2d20a7f5fbd4cd10b0be49a68da2b2e0dc0ad8960f");
+    }
+
+    // Ed25519ph test vector from RFC 8032, Section 7.3.
+    #[test]
+    fn test_ed25519ph_rfc8032() {
+        let (sk, pk) = keys(
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42",
+            "ec172b93ad5e563bf4932c70e1245034c35467ef2efd4d64ebf819683467e2bf");
+        let msg = hex_decode("616263");
+        let sig = signature(
+            "98a70222f0b8121aa9d30f813d683f809e462b469c7ff87639499bb94e6dae4131f85042463c2a355a2003d062adf5aaa10b8c61e636062aaad11c2a26083406");
+
+        assert!(matches!(sk.sign_ph(&msg, &[]), Ok(s) if s == sig));
+        assert!(pk.verify_ph(&msg, &[], &sig).is_ok());
+        assert!(pk.verify(&msg, &sig).is_err());
+
+        // Feeding the message to SHA-512 one octet at a time gives the same signature.
+        let mut prehash = Sha512::new();
+        for b in &msg {
+            prehash.update([*b]);
+        }
+        assert!(matches!(sk.sign_prehashed(prehash.clone(), &[]), Ok(s) if s == sig));
+        assert!(pk.verify_prehashed(prehash, &[], &sig).is_ok());
+    }
+
+    #[test]
+    fn test_context_length() {
+        let sk = PrivateKey::new([7; 32]);
+        let pk = sk.public_key();
+        let msg = b"settlement batch";
+
+        assert!(sk.sign_ctx(msg, &[]).is_err());
+        assert!(sk.sign_ctx(msg, &[0xAB; 256]).is_err());
+        assert!(sk.sign_ph(msg, &[0xAB; 256]).is_err());
+
+        let sig = sk.sign_ctx(msg, &[0xAB; 255]).unwrap();
+        assert!(pk.verify_ctx(msg, &[0xAB; 255], &sig).is_ok());
+        assert!(pk.verify_ctx(msg, &[0xAB; 256], &sig).is_err());
+
+        let sig = sk.sign_ph(msg, &[0xAB; 255]).unwrap();
+        assert!(pk.verify_ph(msg, &[0xAB; 255], &sig).is_ok());
+        assert!(pk.verify_ph(msg, &[0xAB; 254], &sig).is_err());
+    }
+
+    #[test]
+    fn test_verify_failure() {
+        let sk = PrivateKey::new([42; 32]);
+        let pk = sk.public_key();
+        let msg = b"pay 100 to merchant 7";
+        let sig = sk.sign(msg);
+        assert!(pk.verify(msg, &sig).is_ok());
+        assert!(pk.verify(b"pay 900 to merchant 7", &sig).is_err());
+
+        let mut bad_r = sig;
+        bad_r[0] ^= 1;
+        assert!(pk.verify(msg, &bad_r).is_err());
+
+        // S + l is congruent to S but is not a canonical scalar.
+        let mut s_plus_l = sig;
+        let mut carry: u16 = 0;
+        for (i, l) in Scalar::ORDER.iter().enumerate() {
+            let v = s_plus_l[32 + i] as u16 + *l as u16 + carry;
+            s_plus_l[32 + i] = v as u8;
+            carry = v >> 8;
+        }
+        assert!(pk.verify(msg, &s_plus_l).is_err());
+    }
+
+    // The output of test/default/ed25519_convert.c in libsodium.
+    #[test]
+    fn test_to_x25519_libsodium() {
+        let seed: [u8; 32] = hex_decode("421151a459faeade3d247115f94aedae42318124095afabe4d1451a559faedee")
+            .try_into().unwrap();
+        let sk = PrivateKey::new(seed);
+        let x_pk = sk.public_key().to_x25519().unwrap();
+        assert_eq!(x_pk.public.to_vec(), hex_decode("f1814f0e8ff1043d8a44d25babff3cedcae6c22c3edaa48f857ae70de2baae50"));
+        assert_eq!(sk.to_x25519().public_key().public, x_pk.public);
+        assert_eq!(sk.scalar.to_vec(), hex_decode("8052030376d47112be7f73ed7a019293dd12ad910b654455798b4667d73de166"));
+
+        let mut rng = StdRng::seed_from_u64(0xC0DE);
+        for _ in 0..32 {
+            let sk = PrivateKey::new(rng.gen());
+            assert_eq!(sk.to_x25519().public_key().public, sk.public_key().to_x25519().unwrap().public);
+        }
+
+        // not on the curve, or of small order
+        for pk in ["0000000000000000000000000000000000000000000000000000000000000000",
+                   "0200000000000000000000000000000000000000000000000000000000000000",
+                   "0500000000000000000000000000000000000000000000000000000000000000",
+                   "0100000000000000000000000000000000000000000000000000000000000000"] {
+            assert!(PublicKey::new(hex_decode(pk).try_into().unwrap()).to_x25519().is_err());
+        }
+        // a point of order 8l
+        let t8 = Point::from_bytes(&hex_decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a")
+            .try_into().unwrap()).unwrap();
+        let a = Point::from_bytes(&sk.public_key().to_bytes()).unwrap();
+        assert!(PublicKey::new(Point::add(&a, &t8).to_bytes()).to_x25519().is_err());
+    }
+
+    #[test]
+    fn test_verify_batch_valid() {
+        let mut rng = StdRng::seed_from_u64(0x5E771E);
+        let keys: Vec<PrivateKey> = (0..16).map(|_| PrivateKey::new(rng.gen())).collect();
+        let messages: Vec<Vec<u8>> = (0..16).map(|i| format!("transfer #{i}").into_bytes()).collect();
+        let message_refs: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+        let signatures: Vec<[u8; SIGNATURE_SIZE]> = keys.iter().zip(messages.iter())
+            .map(|(k, m)| k.sign(m)).collect();
+        let public_keys: Vec<PublicKey> = keys.iter().map(|k| k.public_key()).collect();
+
+        for policy in POLICIES {
+            assert!(verify_batch(&message_refs, &signatures, &public_keys, policy, &mut rng).is_ok());
+            assert!(verify_batch(&[], &[], &[], policy, &mut rng).is_ok());
+        }
+    }
+
+    // The indices reported by verify_batch must be exactly those rejected by verify_with_policy.
+    #[test]
+    fn test_verify_batch_agrees_with_verify() {
+        let mut rng = StdRng::seed_from_u64(0xBA7C4);
+        let n = 24;
+        let keys: Vec<PrivateKey> = (0..n).map(|_| PrivateKey::new(rng.gen())).collect();
+        let messages: Vec<Vec<u8>> = (0..n).map(|i| format!("settlement {i}").into_bytes()).collect();
+        let mut signatures: Vec<[u8; SIGNATURE_SIZE]> = keys.iter().zip(messages.iter())
+            .map(|(k, m)| k.sign(m)).collect();
+        let mut public_keys: Vec<PublicKey> = keys.iter().map(|k| k.public_key()).collect();
+
+        // signed by another key
+        signatures[2] = keys[3].sign(&messages[2]);
+        // a bit flipped in S, which stays below l
+        signatures[5][32] ^= 0x04;
+        // S replaced by l, which is not canonical
+        signatures[7][32..].copy_from_slice(&Scalar::ORDER);
+        // R is not the encoding of a curve point
+        signatures[11][..32].copy_from_slice(&[2; 32]);
+        // the public key does not decode
+        public_keys[13] = PublicKey::new([2; 32]);
+        // valid signature of a different message
+        signatures[17] = keys[17].sign(b"another message");
+        // R replaced by a valid but unrelated point
+        signatures[20][..32].copy_from_slice(&keys[0].public_key().to_bytes());
+
+        let message_refs: Vec<&[u8]> = messages.iter().map(|m| m.as_slice()).collect();
+        for policy in POLICIES {
+            let expected: Vec<usize> = (0..n)
+                .filter(|&i| public_keys[i].verify_with_policy(&messages[i], &signatures[i], policy).is_err())
+                .collect();
+            assert_eq!(expected, vec![2, 5, 7, 11, 13, 17, 20]);
+
+            let res = verify_batch(&message_refs, &signatures, &public_keys, policy, &mut rng);
+            assert!(matches!(res, Err(v) if v == expected));
+
+            // A single bad signature is found in an otherwise valid batch.
+            let res = verify_batch(&message_refs[..3], &signatures[..3], &public_keys[..3], policy, &mut rng);
+            assert!(matches!(res, Err(v) if v == vec![2]));
+        }
+    }
+
+    // EdgeCase builds a signature (A, R, S) with
+    //     A = [a]B + T_A,  R = [r]B + T_R,  S = r + k * a mod l,
+    // where T_A and T_R are small-order points, so that [S]B - R - [k]A = -(T_R + [k]T_A)
+    // always satisfies the cofactored equation. Messages are tried until this residue is
+    // zero (the cofactorless equation holds too) exactly when residue_zero is set.
+    // A and R are sent as a_enc and r_enc when given. The signer computes k over those
+    // encodings, or over the canonical ones when hash_canonical is set.
+    struct EdgeCase {
+        a: Scalar,
+        t_a: Point,
+        a_enc: Option<[u8; 32]>,
+        r: Scalar,
+        t_r: Point,
+        r_enc: Option<[u8; 32]>,
+        hash_canonical: bool,
+        residue_zero: bool,
+    }
+
+    impl EdgeCase {
+        fn sign(&self) -> (PublicKey, Vec<u8>, [u8; SIGNATURE_SIZE]) {
+            let a_point = Point::add(&Point::basepoint_mul(&self.a.to_bytes()), &self.t_a);
+            let r_point = Point::add(&Point::basepoint_mul(&self.r.to_bytes()), &self.t_r);
+            let a_bytes = self.a_enc.unwrap_or(a_point.to_bytes());
+            let r_bytes = self.r_enc.unwrap_or(r_point.to_bytes());
+            let (hash_a, hash_r) = if self.hash_canonical {
+                (a_point.to_bytes(), r_point.to_bytes())
+            } else {
+                (a_bytes, r_bytes)
+            };
+
+            for i in 0.. {
+                let m = format!("edge case {i}").into_bytes();
+                let k = hash_to_scalar(&[&hash_r, &hash_a, &m]);
+                let residue = Point::add(&self.t_r, &Point::scalar_mul(&k.to_bytes(), &self.t_a));
+                if residue.is_identity() == self.residue_zero {
+                    let s = Scalar::mul_add(&k, &self.a, &self.r);
+                    let mut sig: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+                    sig[..32].copy_from_slice(&r_bytes);
+                    sig[32..].copy_from_slice(&s.to_bytes());
+                    return (PublicKey::new(a_bytes), m, sig);
+                }
+            }
+            unreachable!()
+        }
+    }
+
+    // adds m * l to the S half of a signature, producing a congruent but non-canonical S.
+    fn add_multiple_of_order(sig: &mut [u8; SIGNATURE_SIZE], m: u16) {
+        let mut carry: u16 = 0;
+        for (i, l) in Scalar::ORDER.iter().enumerate() {
+            let v = sig[32 + i] as u16 + m * (*l as u16) + carry;
+            sig[32 + i] = v as u8;
+            carry = v >> 8;
+        }
+        assert_eq!(carry, 0);
+    }
+
+    // check_policy_matrix checks every case under every policy against the expected results,
+    // given as Strict, Cofactorless, Cofactored and Zip215, both on its own and in batches.
+    fn check_policy_matrix(cases: &[(PublicKey, Vec<u8>, [u8; SIGNATURE_SIZE])], expected: &[[bool; 4]]) {
+        let public_keys: Vec<PublicKey> = cases.iter().map(|c| c.0.clone()).collect();
+        let messages: Vec<&[u8]> = cases.iter().map(|c| c.1.as_slice()).collect();
+        let signatures: Vec<[u8; SIGNATURE_SIZE]> = cases.iter().map(|c| c.2).collect();
+        let mut rng = StdRng::seed_from_u64(0x7A31);
+
+        for (j, policy) in POLICIES.iter().enumerate() {
+            let mut rejected: Vec<usize> = Vec::new();
+            for (i, (pk, m, sig)) in cases.iter().enumerate() {
+                let accepted = pk.verify_with_policy(m, sig, *policy).is_ok();
+                assert_eq!(accepted, expected[i][j], "case {i} under {policy:?}");
+                if !accepted {
+                    rejected.push(i);
+                }
+            }
+
+            let res = verify_batch(&messages, &signatures, &public_keys, *policy, &mut rng);
+            assert!(matches!(res, Err(v) if v == rejected), "batch under {policy:?}");
+
+            // Batches made only of the signatures accepted on their own are accepted too.
+            let accepted: Vec<usize> = (0..cases.len()).filter(|i| !rejected.contains(i)).collect();
+            let res = verify_batch(
+                &accepted.iter().map(|&i| messages[i]).collect::<Vec<&[u8]>>(),
+                &accepted.iter().map(|&i| signatures[i]).collect::<Vec<[u8; SIGNATURE_SIZE]>>(),
+                &accepted.iter().map(|&i| public_keys[i].clone()).collect::<Vec<PublicKey>>(),
+                *policy, &mut rng);
+            assert!(res.is_ok(), "accepted subset under {policy:?}");
+        }
+    }
+
+    // The twelve test cases of "Taming the many EdDSAs" (Chalkias, Garillot, Nikolaenko, 2020),
+    // as published in the ed25519-speccheck repository (cases.json): message, public key and
+    // signature.
+    const SPECCHECK_CASES: [(&str, &str, &str); 12] = [
+        ("8c93255d71dcab10e8f379c26200f3c7bd5f09d9bc3068d3ef4edeb4853022b6",
+         "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+         "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a\
+          0000000000000000000000000000000000000000000000000000000000000000"),
+        ("9bd9f44f4dcc75bd531b56b2cd280b0bb38fc1cd6d1230e14861d861de092e79",
+         "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+         "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43\
+          a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04"),
+        ("aebf3f2601a0c8c5d39cc7d8911642f740b78168218da8471772b35f9d35b9ab",
+         "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+         "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa\
+          8c4bd45aecaca5b24fb97bc10ac27ac8751a7dfe1baff8b953ec9f5833ca260e"),
+        ("9bd9f44f4dcc75bd531b56b2cd280b0bb38fc1cd6d1230e14861d861de092e79",
+         "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+         "9046a64750444938de19f227bb80485e92b83fdb4b6506c160484c016cc1852f\
+          87909e14428a7a1d62e9f22f3d3ad7802db02eb2e688b6c52fcd6648a98bd009"),
+        ("e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec4011eaccd55b53f56c",
+         "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+         "160a1cb0dc9c0258cd0a7d23e94d8fa878bcb1925f2c64246b2dee1796bed512\
+          5ec6bc982a269b723e0668e540911a9a6a58921d6925e434ab10aa7940551a09"),
+        ("e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec4011eaccd55b53f56c",
+         "cdb267ce40c5cd45306fa5d2f29731459387dbf9eb933b7bd5aed9a765b88d4d",
+         "21122a84e0b5fca4052f5b1235c80a537878b38f3142356b2c2384ebad4668b7\
+          e40bc836dac0f71076f9abe3a53f9c03c1ceeeddb658d0030494ace586687405"),
+        ("85e241a07d148b41e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec40",
+         "442aad9f089ad9e14647b1ef9099a1ff4798d78589e66f28eca69c11f582a623",
+         "e96f66be976d82e60150baecff9906684aebb1ef181f67a7189ac78ea23b6c0e\
+          547f7690a0e2ddcd04d87dbc3490dc19b3b3052f7ff0538cb68afb369ba3a514"),
+        ("85e241a07d148b41e47d62c63f830dc7a6851a0b1f33ae4bb2f507fb6cffec40",
+         "442aad9f089ad9e14647b1ef9099a1ff4798d78589e66f28eca69c11f582a623",
+         "8ce5b96c8f26d0ab6c47958c9e68b937104cd36e13c33566acd2fe8d38aa1942\
+          7e71f98a473474f2f13f06f97c20d58cc3f54b8bd0d272f42b695dd7e89a8c22"),
+        ("9bedc267423725d473888631ebf45988bad3db83851ee85c85e241a07d148b41",
+         "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+         "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+          03be9678ac102edcd92b0210bb34d7428d12ffc5df5f37e359941266a4e35f0f"),
+        ("9bedc267423725d473888631ebf45988bad3db83851ee85c85e241a07d148b41",
+         "f7badec5b8abeaf699583992219b7b223f1df3fbbea919844e3f7c554a43dd43",
+         "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff\
+          ca8c5b64cd208982aa38d4936621a4775aa233aa0505711d8fdcfdaa943d4908"),
+        ("e96b7021eb39c1a163b6da4e3093dcd3f21387da4cc4572be588fafae23c155b",
+         "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+         "a9d55260f765261eb9b84e106f665e00b867287a761990d7135963ee0a7d59dc\
+          a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04"),
+        ("39a591f5321bbe07fd5a23dc2f39d025d74526615746727ceefd6e82ae65c06f",
+         "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+         "a9d55260f765261eb9b84e106f665e00b867287a761990d7135963ee0a7d59dc\
+          a5bb704786be79fc476f91d3f3f89b03984d8068dcf1bb7dfc6637b45450ac04"),
+    ];
+
+    // The published speccheck cases, under each policy on their own and in batches. Cases 0-5
+    // mix small-order and mixed-order A and R, 6 and 7 have S >= l, 8 and 9 a non-canonical R
+    // hashed after re-encoding and as received, and 10 and 11 a non-canonical A likewise.
+    #[test]
+    fn test_verification_policies_speccheck() {
+        let cases: Vec<(PublicKey, Vec<u8>, [u8; SIGNATURE_SIZE])> = SPECCHECK_CASES.iter()
+            .map(|(m, pk, sig)| (PublicKey::new(hex_decode(pk).try_into().unwrap()), hex_decode(m), signature(sig)))
+            .collect();
+
+        //                    Strict Cofactorless Cofactored Zip215
+        let expected: [[bool; 4]; 12] = [
+            /*  0 */ [false, true, true, true],
+            /*  1 */ [false, true, true, true],
+            /*  2 */ [false, true, true, true],
+            /*  3 */ [true, true, true, true],
+            /*  4 */ [false, false, true, true],
+            /*  5 */ [false, false, true, true],
+            /*  6 */ [false, false, false, false],
+            /*  7 */ [false, false, false, false],
+            /*  8 */ [false, false, false, false],
+            /*  9 */ [false, false, false, true],
+            /* 10 */ [false, false, false, true],
+            /* 11 */ [false, false, false, true],
+        ];
+        check_policy_matrix(&cases, &expected);
+    }
+
+    // Extra coverage beyond the published cases: the same kinds of edge case, built here with
+    // fresh keys and messages, including non-canonical encodings of order 4 and of the identity.
+    #[test]
+    fn test_verification_policies_constructed_cases() {
+        let decode = |enc: &str| Point::from_bytes(&hex_decode(enc).try_into().unwrap()).unwrap();
+        let enc = |enc: &str| -> Option<[u8; 32]> { Some(hex_decode(enc).try_into().unwrap()) };
+        // a point of order 8
+        let t8 = decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a");
+        // (sqrt(-1), 0), of order 4, encoded with y = p
+        let order4_non_canonical = "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f";
+        // (0, 1), the identity, encoded with y = p + 1
+        let identity_non_canonical = "eeffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f";
+        let id = Point::identity();
+        let zero = Scalar::ZERO.clone();
+        let a = Scalar::from_bytes_mod_order([0x21; 32]);
+        let r = Scalar::from_bytes_mod_order([0x7e; 32]);
+
+        let case = |a: &Scalar, t_a: &Point, a_enc, r: &Scalar, t_r: &Point, r_enc, hash_canonical, residue_zero| {
+            EdgeCase { a: a.clone(), t_a: t_a.clone(), a_enc, r: r.clone(), t_r: t_r.clone(), r_enc, hash_canonical, residue_zero }.sign()
+        };
+
+        let honest = PrivateKey::new([0x5C; 32]);
+        let honest_msg = b"edge case 6".to_vec();
+        let mut s_plus_l = honest.sign(&honest_msg);
+        add_multiple_of_order(&mut s_plus_l, 1);
+        let mut s_plus_8l = honest.sign(&honest_msg);
+        add_multiple_of_order(&mut s_plus_8l, 8);
+
+        let cases = [
+            // 0: S = 0, small-order A, small-order R
+            case(&zero, &t8, None, &zero, &t8, None, false, true),
+            // 1: small-order A, mixed-order R
+            case(&zero, &t8, None, &r, &t8, None, false, true),
+            // 2: mixed-order A, small-order R
+            case(&a, &t8, None, &zero, &t8, None, false, true),
+            // 3: mixed-order A and R, both equations hold
+            case(&a, &t8, None, &r, &t8, None, false, true),
+            // 4: mixed-order A and R, only the cofactored equation holds
+            case(&a, &t8, None, &r, &t8, None, false, false),
+            // 5: mixed-order A, prime-order R, only the cofactored equation holds
+            case(&a, &t8, None, &r, &id, None, false, false),
+            // 6: S = S + l
+            (honest.public_key(), honest_msg.clone(), s_plus_l),
+            // 7: S = S + 8l, with bit 255 set
+            (honest.public_key(), honest_msg.clone(), s_plus_8l),
+            // 8: non-canonical small-order R, hashed as received
+            case(&a, &t8, None, &zero, &decode(order4_non_canonical), enc(order4_non_canonical), false, true),
+            // 9: non-canonical small-order R, hashed after re-encoding
+            case(&a, &t8, None, &zero, &decode(order4_non_canonical), enc(order4_non_canonical), true, true),
+            // 10: non-canonical small-order A, hashed as received
+            case(&zero, &decode(order4_non_canonical), enc(order4_non_canonical), &r, &id, None, false, true),
+            // 11: non-canonical small-order A, hashed after re-encoding
+            case(&zero, &decode(identity_non_canonical), enc(identity_non_canonical), &r, &id, None, true, true),
+        ];
+
+        //                    Strict Cofactorless Cofactored Zip215
+        let expected: [[bool; 4]; 12] = [
+            /*  0 */ [false, true, true, true],
+            /*  1 */ [false, true, true, true],
+            /*  2 */ [false, true, true, true],
+            /*  3 */ [true, true, true, true],
+            /*  4 */ [false, false, true, true],
+            /*  5 */ [false, false, true, true],
+            /*  6 */ [false, false, false, false],
+            /*  7 */ [false, false, false, false],
+            /*  8 */ [false, false, false, true],
+            /*  9 */ [false, false, false, false],
+            /* 10 */ [false, false, false, true],
+            /* 11 */ [false, false, false, true],
+        ];
+
+        check_policy_matrix(&cases, &expected);
+    }
+}
@@ -0,0 +1,316 @@
+#![allow(dead_code)]
+
+use crate::edwards448::Point;
+use crate::keccak::{shake256, Shake256};
+use crate::scalar448::Scalar;
+
+// Ed448 and Ed448ph as specified in RFC 8032, Section 5.2.
+//
+// Both variants hash with SHAKE256 and put the dom4 prefix in front of every hash input.
+// Unlike Ed25519, Ed448 always takes a context, which may be empty:
+//     Ed448    dom4(0, context),  M' = M
+//     Ed448ph  dom4(1, context),  M' = SHAKE256(M, 64)
+// Both share the same keys: 57-octet seeds, public keys and scalars, and 114-octet signatures.
+
+pub const KEY_SIZE: usize = 57;
+pub const SIGNATURE_SIZE: usize = 114;
+pub const CONTEXT_MAX_SIZE: usize = 255;
+
+// dom4(phflag, context) = "SigEd448" || octet(phflag) || octet(OLEN(context)) || context
+const DOM4_PREFIX: &[u8] = b"SigEd448";
+
+fn dom4(phflag: u8, context: &[u8]) -> Result<Vec<u8>, ()> {
+    if context.len() > CONTEXT_MAX_SIZE {
+        return Err(());
+    }
+    let mut dom: Vec<u8> = Vec::with_capacity(DOM4_PREFIX.len() + 2 + context.len());
+    dom.extend_from_slice(DOM4_PREFIX);
+    dom.push(phflag);
+    dom.push(context.len() as u8);
+    dom.extend_from_slice(context);
+    Ok(dom)
+}
+
+// hash_to_scalar returns SHAKE256(parts[0] || parts[1] || ..., 114) mod L.
+fn hash_to_scalar(parts: &[&[u8]]) -> Scalar {
+    Scalar::from_bytes_mod_order_wide(&shake256(parts))
+}
+
+pub struct PrivateKey {
+    seed: [u8; KEY_SIZE],
+    // the clamped lower half of SHAKE256(seed, 114)
+    scalar: [u8; KEY_SIZE],
+    // the upper half of SHAKE256(seed, 114), used to derive the per-message nonce
+    prefix: [u8; KEY_SIZE],
+    public: PublicKey,
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKey {
+    public: [u8; KEY_SIZE],
+}
+
+impl PrivateKey {
+    // new expands a 57-octet seed into a signing key [RFC 8032, 5.2.5]. The two lowest bits
+    // of the scalar are cleared, so that it is a multiple of the cofactor 4, the whole last
+    // octet is cleared, and the highest bit of the second to last octet is set.
+    pub fn new(seed: [u8; KEY_SIZE]) -> PrivateKey {
+        let h: [u8; 2 * KEY_SIZE] = shake256(&[&seed]);
+        let mut scalar: [u8; KEY_SIZE] = h[..KEY_SIZE].try_into().unwrap();
+        scalar[0] &= 0xFC;
+        scalar[56] = 0;
+        scalar[55] |= 0x80;
+        let prefix: [u8; KEY_SIZE] = h[KEY_SIZE..].try_into().unwrap();
+
+        let public = PublicKey::new(Point::basepoint_mul(&scalar).to_bytes());
+        PrivateKey { seed, scalar, prefix, public }
+    }
+
+    pub fn seed(&self) -> [u8; KEY_SIZE] {
+        self.seed
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        self.public.clone()
+    }
+
+    // sign returns an Ed448 signature of message with an empty context.
+    pub fn sign(&self, message: &[u8]) -> [u8; SIGNATURE_SIZE] {
+        self.sign_with_dom(&dom4(0, &[]).unwrap(), message)
+    }
+
+    // sign_ctx returns an Ed448 signature of message under context (at most 255 octets).
+    pub fn sign_ctx(&self, message: &[u8], context: &[u8]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        let dom = dom4(0, context)?;
+        Ok(self.sign_with_dom(&dom, message))
+    }
+
+    // sign_ph returns an Ed448ph signature of message under context (at most 255 octets).
+    pub fn sign_ph(&self, message: &[u8], context: &[u8]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        let mut prehash = Shake256::new();
+        prehash.update(message);
+        self.sign_prehashed(prehash, context)
+    }
+
+    // sign_prehashed returns an Ed448ph signature of the message absorbed by prehash.
+    pub fn sign_prehashed(&self, prehash: Shake256, context: &[u8]) -> Result<[u8; SIGNATURE_SIZE], ()> {
+        let dom = dom4(1, context)?;
+        let mut digest: [u8; 64] = [0; 64];
+        prehash.finalize(&mut digest);
+        Ok(self.sign_with_dom(&dom, &digest))
+    }
+
+    // sign_with_dom implements RFC 8032, Section 5.2.6 for the prefix dom and message m.
+    fn sign_with_dom(&self, dom: &[u8], m: &[u8]) -> [u8; SIGNATURE_SIZE] {
+        // r = SHAKE256(dom4 || prefix || M', 114) mod L;  R = [r]B
+        let r = hash_to_scalar(&[dom, &self.prefix, m]);
+        let r_bytes = Point::basepoint_mul(&r.to_bytes()).to_bytes();
+
+        // k = SHAKE256(dom4 || R || A || M', 114) mod L;  S = (r + k * s) mod L
+        let k = hash_to_scalar(&[dom, &r_bytes, &self.public.public, m]);
+        let s = Scalar::mul_add_bytes(&k.to_bytes(), &self.scalar, &r.to_bytes());
+
+        let mut signature: [u8; SIGNATURE_SIZE] = [0; SIGNATURE_SIZE];
+        signature[..KEY_SIZE].copy_from_slice(&r_bytes);
+        signature[KEY_SIZE..].copy_from_slice(&s.to_bytes());
+        signature
+    }
+}
+
+impl PublicKey {
+    pub fn new(key: [u8; KEY_SIZE]) -> PublicKey {
+        PublicKey {
+            public: key
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; KEY_SIZE] {
+        self.public
+    }
+
+    // verify checks an Ed448 signature of message with an empty context.
+    pub fn verify(&self, message: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        self.verify_with_dom(&dom4(0, &[]).unwrap(), message, signature)
+    }
+
+    // verify_ctx checks an Ed448 signature of message under context.
+    pub fn verify_ctx(&self, message: &[u8], context: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let dom = dom4(0, context)?;
+        self.verify_with_dom(&dom, message, signature)
+    }
+
+    // verify_ph checks an Ed448ph signature of message under context.
+    pub fn verify_ph(&self, message: &[u8], context: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let mut prehash = Shake256::new();
+        prehash.update(message);
+        self.verify_prehashed(prehash, context, signature)
+    }
+
+    // verify_prehashed checks an Ed448ph signature of the message absorbed by prehash.
+    pub fn verify_prehashed(&self, prehash: Shake256, context: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let dom = dom4(1, context)?;
+        let mut digest: [u8; 64] = [0; 64];
+        prehash.finalize(&mut digest);
+        self.verify_with_dom(&dom, &digest, signature)
+    }
+
+    // verify_with_dom implements RFC 8032, Section 5.2.7 for the prefix dom and message m:
+    // A and R must decode, S must be below L, and [4][S]B = [4]R + [4][k]A must hold.
+    fn verify_with_dom(&self, dom: &[u8], m: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), ()> {
+        let a = Point::from_bytes(&self.public)?;
+        let r = Point::from_bytes(&signature[..KEY_SIZE].try_into().unwrap())?;
+        let s = Scalar::from_canonical_bytes(&signature[KEY_SIZE..].try_into().unwrap())?;
+        let k = hash_to_scalar(&[dom, &signature[..KEY_SIZE], &self.public, m]);
+
+        let sb = Point::basepoint_mul(&s.to_bytes());
+        let ka = Point::scalar_mul(&k.to_bytes(), &a);
+        let check = Point::mul_by_cofactor(&Point::subtract(&Point::subtract(&sb, &r), &ka));
+        if check.is_identity() {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_ed448 {
+    use crate::ed448::{PrivateKey, PublicKey, KEY_SIZE, SIGNATURE_SIZE};
+    use crate::keccak::Shake256;
+    use crate::test_util::hex_decode;
+
+    fn keys(sk_hex: &str, pk_hex: &str) -> (PrivateKey, PublicKey) {
+        let sk = PrivateKey::new(hex_decode(sk_hex).try_into().unwrap());
+        let pk = PublicKey::new(hex_decode(pk_hex).try_into().unwrap());
+        assert_eq!(sk.public_key().to_bytes(), pk.to_bytes());
+        (sk, pk)
+    }
+
+    fn signature(sig_hex: &str) -> [u8; SIGNATURE_SIZE] {
+        hex_decode(sig_hex).try_into().unwrap()
+    }
+
+    // Ed448 test vectors from RFC 8032, Section 7.4 (blank, 1 octet, 1 octet with context,
+    // 11, 12 and 13 octets).
+    #[test]
+    fn test_ed448_rfc8032() {
+        fn _test_ed448_(sk_hex: &str, pk_hex: &str, msg_hex: &str, ctx_hex: &str, sig_hex: &str) {
+            let (sk, pk) = keys(sk_hex, pk_hex);
+            let msg = hex_decode(msg_hex);
+            let ctx = hex_decode(ctx_hex);
+            let sig = signature(sig_hex);
+            assert_eq!(sk.sign_ctx(&msg, &ctx), Ok(sig));
+            assert!(pk.verify_ctx(&msg, &ctx, &sig).is_ok());
+            if ctx.is_empty() {
+                assert_eq!(sk.sign(&msg), sig);
+                assert!(pk.verify(&msg, &sig).is_ok());
+            }
+            // The context is bound to the signature, and so is the variant.
+            assert!(pk.verify_ctx(&msg, b"bar", &sig).is_err());
+            assert!(pk.verify_ph(&msg, &ctx, &sig).is_err());
+        }
+
+        _test_ed448_(
+            "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+            "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+            "",
+            "",
+            "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4dbb61149f05a7363268c71d95808ff2e652600");
+        _test_ed448_(
+            "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+            "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+            "03",
+            "",
+            "26b8f91727bd62897af15e41eb43c377efb9c610d48f2335cb0bd0087810f4352541b143c4b981b7e18f62de8ccdf633fc1bf037ab7cd779805e0dbcc0aae1cbcee1afb2e027df36bc04dcecbf154336c19f0af7e0a6472905e799f1953d2a0ff3348ab21aa4adafd1d234441cf807c03a00");
+        _test_ed448_(
+            "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+            "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+            "03",
+            "666f6f",
+            "d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbcd001b32a89f7d2151f7647f11d8ca2ae279fb842d607217fce6e042f6815ea000c85741de5c8da1144a6a1aba7f96de42505d7a7298524fda538fccbbb754f578c1cad10d54d0d5428407e85dcbc98a49155c13764e66c3c00");
+        _test_ed448_(
+            "cd23d24f714274e744343237b93290f511f6425f98e64459ff203e8985083ffdf60500553abc0e05cd02184bdb89c4ccd67e187951267eb328",
+            "dcea9e78f35a1bf3499a831b10b86c90aac01cd84b67a0109b55a36e9328b1e365fce161d71ce7131a543ea4cb5f7e9f1d8b00696447001400",
+            "0c3e544074ec63b0265e0c",
+            "",
+            "1f0a8888ce25e8d458a21130879b840a9089d999aaba039eaf3e3afa090a09d389dba82c4ff2ae8ac5cdfb7c55e94d5d961a29fe0109941e00b8dbdeea6d3b051068df7254c0cdc129cbe62db2dc957dbb47b51fd3f213fb8698f064774250a5028961c9bf8ffd973fe5d5c206492b140e00");
+        _test_ed448_(
+            "258cdd4ada32ed9c9ff54e63756ae582fb8fab2ac721f2c8e676a72768513d939f63dddb55609133f29adf86ec9929dccb52c1c5fd2ff7e21b",
+            "3ba16da0c6f2cc1f30187740756f5e798d6bc5fc015d7c63cc9510ee3fd44adc24d8e968b6e46e6f94d19b945361726bd75e149ef09817f580",
+            "64a65f3cdedcdd66811e2915",
+            "",
+            "7eeeab7c4e50fb799b418ee5e3197ff6bf15d43a14c34389b59dd1a7b1b85b4ae90438aca634bea45e3a2695f1270f07fdcdf7c62b8efeaf00b45c2c96ba457eb1a8bf075a3db28e5c24f6b923ed4ad747c3c9e03c7079efb87cb110d3a99861e72003cbae6d6b8b827e4e6c143064ff3c00");
+        _test_ed448_(
+            "7ef4e84544236752fbb56b8f31a23a10e42814f5f55ca037cdcc11c64c9a3b2949c1bb60700314611732a6c2fea98eebc0266a11a93970100e",
+            "b3da079b0aa493a5772029f0467baebee5a8112d9d3a22532361da294f7bb3815c5dc59e176b4d9f381ca0938e13c6c07b174be65dfa578e80",
+            "64a65f3cdedcdd66811e2915e7",
+            "",
+            "6a12066f55331b6c22acd5d5bfc5d71228fbda80ae8dec26bdd306743c5027cb4890810c162c027468675ecf645a83176c0d7323a2ccde2d80efe5a1268e8aca1d6fbc194d3f77c44986eb4ab4177919ad8bec33eb47bbb5fc6e28196fd1caf56b4e7e0ba5519234d047155ac727a1053100");
+    }
+
+    // Ed448ph test vectors from RFC 8032, Section 7.5 ("abc", without and with context).
+    #[test]
+    fn test_ed448ph_rfc8032() {
+        fn _test_ed448ph_(sk_hex: &str, pk_hex: &str, msg_hex: &str, ctx_hex: &str, sig_hex: &str) {
+            let (sk, pk) = keys(sk_hex, pk_hex);
+            let msg = hex_decode(msg_hex);
+            let ctx = hex_decode(ctx_hex);
+            let sig = signature(sig_hex);
+            assert_eq!(sk.sign_ph(&msg, &ctx), Ok(sig));
+            assert!(pk.verify_ph(&msg, &ctx, &sig).is_ok());
+
+            // Incremental prehashing gives the same signature.
+            let mut prehash = Shake256::new();
+            for b in &msg {
+                prehash.update(&[*b]);
+            }
+            assert_eq!(sk.sign_prehashed(prehash.clone(), &ctx), Ok(sig));
+            assert!(pk.verify_prehashed(prehash, &ctx, &sig).is_ok());
+            assert!(pk.verify_ctx(&msg, &ctx, &sig).is_err());
+        }
+
+        _test_ed448ph_(
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42ef7822e0d5104127dc05d6dbefde69e3ab2cec7c867c6e2c49",
+            "259b71c19f83ef77a7abd26524cbdb3161b590a48f7d17de3ee0ba9c52beb743c09428a131d6b1b57303d90d8132c276d5ed3d5d01c0f53880",
+            "616263",
+            "",
+            "822f6901f7480f3d5f562c592994d9693602875614483256505600bbc281ae381f54d6bce2ea911574932f52a4e6cadd78769375ec3ffd1b801a0d9b3f4030cd433964b6457ea39476511214f97469b57dd32dbc560a9a94d00bff07620464a3ad203df7dc7ce360c3cd3696d9d9fab90f00");
+        _test_ed448ph_(
+            "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42ef7822e0d5104127dc05d6dbefde69e3ab2cec7c867c6e2c49",
+            "259b71c19f83ef77a7abd26524cbdb3161b590a48f7d17de3ee0ba9c52beb743c09428a131d6b1b57303d90d8132c276d5ed3d5d01c0f53880",
+            "616263",
+            "666f6f",
+            "c32299d46ec8ff02b54540982814dce9a05812f81962b649d528095916a2aa481065b1580423ef927ecf0af5888f90da0f6a9a85ad5dc3f280d91224ba9911a3653d00e484e2ce232521481c8658df304bb7745a73514cdb9bf3e15784ab71284f8d0704a608c54a6b62d97beb511d132100");
+    }
+
+    // A tampered message, signature or key fails, and so do S >= L and over-long contexts.
+    #[test]
+    fn test_ed448_invalid() {
+        let sk = PrivateKey::new([0x44; KEY_SIZE]);
+        let pk = sk.public_key();
+        let sig = sk.sign(b"message");
+        assert!(pk.verify(b"message", &sig).is_ok());
+        assert!(pk.verify(b"massage", &sig).is_err());
+        for i in [0, 56, 57, 113] {
+            let mut tampered = sig;
+            tampered[i] ^= 0x01;
+            assert!(pk.verify(b"message", &tampered).is_err());
+        }
+        assert!(PrivateKey::new([0x45; KEY_SIZE]).public_key().verify(b"message", &sig).is_err());
+
+        // S + L encodes the same residue, but is not canonical.
+        let l = hex_decode("f34458ab92c27823558fc58d72c26c219036d6ae49db4ec4e923ca7cffffffffffffffffffffffffffffffffffffffffffffffffffffff3f00");
+        let mut s_plus_l = sig;
+        let mut carry: u16 = 0;
+        for i in 0..KEY_SIZE {
+            let t = sig[KEY_SIZE + i] as u16 + l[i] as u16 + carry;
+            s_plus_l[KEY_SIZE + i] = t as u8;
+            carry = t >> 8;
+        }
+        assert!(pk.verify(b"message", &s_plus_l).is_err());
+
+        assert!(sk.sign_ctx(b"message", &[0; 256]).is_err());
+        assert!(sk.sign_ph(b"message", &[0; 256]).is_err());
+        assert!(pk.verify_ctx(b"message", &[0; 256], &sig).is_err());
+    }
+}
@@ -0,0 +1,600 @@
+#![allow(dead_code)]
+
+use std::sync::OnceLock;
+
+use crate::field::Element;
+use crate::scalar::Scalar;
+use crate::subtle;
+
+// Point represents a point on the twisted Edwards curve edwards25519
+//     -x^2 + y^2 = 1 + d*x^2*y^2,  d = -121665/121666
+// in extended homogeneous coordinates (X:Y:Z:T), where x = X/Z, y = Y/Z and x*y = T/Z.
+// The addition and doubling formulas are those of RFC 8032, Section 5.1.4, taken from
+// "Twisted Edwards Curves Revisited" (see notes/ext-proj.txt).
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub(crate) x: Element,
+    pub(crate) y: Element,
+    pub(crate) z: Element,
+    pub(crate) t: Element,
+}
+
+impl Point {
+    // d = -121665/121666
+    pub const D: &'static Element = &Element(
+        929955233495203, 466365720129213, 1662059464998953, 2033849074728123, 1442794654840575);
+    // 2*d
+    pub const D2: &'static Element = &Element(
+        1859910466990425, 932731440258426, 1072319116312658, 1815898335770999, 633789495995903);
+
+    // The base point B = (x, 4/5) with positive x [RFC 8032, 5.1].
+    const BASE_X: &'static Element = &Element(
+        1738742601995546, 1146398526822698, 2070867633025821, 562264141797630, 587772402128613);
+    const BASE_Y: &'static Element = &Element(
+        1801439850948184, 1351079888211148, 450359962737049, 900719925474099, 1801439850948198);
+    const BASE_T: &'static Element = &Element(
+        1841354044333475, 16398895984059, 755974180946558, 900171276175154, 1821297809914039);
+
+    // the neutral element (0, 1)
+    pub fn identity() -> Point {
+        Point {
+            x: Element::ZERO.clone(),
+            y: Element::ONE.clone(),
+            z: Element::ONE.clone(),
+            t: Element::ZERO.clone(),
+        }
+    }
+
+    pub fn basepoint() -> Point {
+        Point {
+            x: Point::BASE_X.clone(),
+            y: Point::BASE_Y.clone(),
+            z: Element::ONE.clone(),
+            t: Point::BASE_T.clone(),
+        }
+    }
+
+    // from_bytes decodes a point as described in RFC 8032, Section 5.1.3.
+    // Like Element::from_le_bytes, it follows the lax decoding rules of most implementations:
+    // a non-canonical y (2^255-19 through 2^255-1) is accepted and so is the "negative zero"
+    // x-coordinate. Strict callers must check the encoding separately.
+    pub fn from_bytes(b: &[u8; 32]) -> Result<Point, ()> {
+        let y = Element::from_le_bytes(*b);
+
+        // -x^2 + y^2 = 1 + d*x^2*y^2
+        // x^2 = (y^2 - 1) / (d*y^2 + 1)
+        let y2 = Element::square(&y);
+        let u = Element::subtract(&y2, Element::ONE);
+        let v = Element::add(&Element::multiply(&y2, Point::D), Element::ONE);
+        let (x, was_square) = Element::sqrt_ratio(&u, &v);
+        if was_square == 0 {
+            return Err(());
+        }
+
+        // sqrt_ratio returns the non-negative root; pick the one whose sign matches bit 255.
+        let mut x_signed = Element::ZERO.clone();
+        x_signed.select(&Element::negate(&x), &x, (b[31] >> 7) as u32);
+
+        let t = Element::multiply(&x_signed, &y);
+        Ok(Point { x: x_signed, y, z: Element::ONE.clone(), t })
+    }
+
+    // to_bytes encodes the point as the 255-bit y-coordinate with the sign of x
+    // in the most significant bit [RFC 8032, 5.1.2].
+    pub fn to_bytes(&self) -> [u8; 32] {
+        let z_inv = Element::invert(&self.z);
+        let x = Element::multiply(&self.x, &z_inv);
+        let y = Element::multiply(&self.y, &z_inv);
+        let mut b = y.to_le_bytes();
+        b[31] |= (x.is_negative() << 7) as u8;
+        b
+    }
+
+    // to_montgomery returns the u-coordinate of the curve25519 point that corresponds to p
+    // under the birational map of RFC 7748, Section 4.1:
+    //     u = (1 + y) / (1 - y) = (Z + Y) / (Z - Y)
+    // The identity and the point (0, -1) of order 2 both map to u = 0, the image of the latter.
+    pub fn to_montgomery(&self) -> [u8; 32] {
+        let u = Element::multiply(
+            &Element::add(&self.z, &self.y),
+            &Element::invert(&Element::subtract(&self.z, &self.y)));
+        u.to_le_bytes()
+    }
+
+    // from_montgomery returns the point with y = (u - 1) / (u + 1) whose x-coordinate has the
+    // given sign, 0 or 1. The u-coordinate does not determine the sign of x: the choice of sign
+    // is that of the v-coordinate. It fails if u = -1, which has no image, and if u lies on
+    // the twist of curve25519, where no such x exists.
+    pub fn from_montgomery(u: &[u8; 32], sign: u8) -> Result<Point, ()> {
+        let u = Element::from_le_bytes(*u);
+        let u_plus_one = Element::add(&u, Element::ONE);
+        if u_plus_one.equal(Element::ZERO) {
+            return Err(());
+        }
+        let y = Element::multiply(&Element::subtract(&u, Element::ONE), &Element::invert(&u_plus_one));
+        let mut b = y.to_le_bytes();
+        b[31] |= (sign & 1) << 7;
+        Point::from_bytes(&b)
+    }
+
+    // returns true if p and q represent the same point: X1*Z2 == X2*Z1 and Y1*Z2 == Y2*Z1.
+    pub fn equal(&self, q: &Point) -> bool {
+        let x1z2 = Element::multiply(&self.x, &q.z);
+        let x2z1 = Element::multiply(&q.x, &self.z);
+        let y1z2 = Element::multiply(&self.y, &q.z);
+        let y2z1 = Element::multiply(&q.y, &self.z);
+        (x1z2.ct_equal(&x2z1) & y1z2.ct_equal(&y2z1)) == 1
+    }
+
+    // calculate p + q.
+    pub fn add(p: &Point, q: &Point) -> Point {
+        let a = Element::multiply(&Element::subtract(&p.y, &p.x), &Element::subtract(&q.y, &q.x));
+        let b = Element::multiply(&Element::add(&p.y, &p.x), &Element::add(&q.y, &q.x));
+        let c = Element::multiply(&Element::multiply(&p.t, Point::D2), &q.t);
+        let d = Element::multiply(&Element::add(&p.z, &p.z), &q.z);
+        let e = Element::subtract(&b, &a);
+        let f = Element::subtract(&d, &c);
+        let g = Element::add(&d, &c);
+        let h = Element::add(&b, &a);
+        Point {
+            x: Element::multiply(&e, &f),
+            y: Element::multiply(&g, &h),
+            z: Element::multiply(&f, &g),
+            t: Element::multiply(&e, &h),
+        }
+    }
+
+    // calculate p - q.
+    pub fn subtract(p: &Point, q: &Point) -> Point {
+        Point::add(p, &Point::negate(q))
+    }
+
+    // calculate 2 * p. This is the dedicated doubling formula for a = -1.
+    pub fn double(p: &Point) -> Point {
+        let a = Element::square(&p.x);
+        let b = Element::square(&p.y);
+        let zz = Element::square(&p.z);
+        let c = Element::add(&zz, &zz);
+        let h = Element::add(&a, &b);
+        let e = Element::subtract(&h, &Element::square(&Element::add(&p.x, &p.y)));
+        let g = Element::subtract(&a, &b);
+        let f = Element::add(&c, &g);
+        Point {
+            x: Element::multiply(&e, &f),
+            y: Element::multiply(&g, &h),
+            z: Element::multiply(&f, &g),
+            t: Element::multiply(&e, &h),
+        }
+    }
+
+    // calculate [8]p, clearing the small-order component of p.
+    pub fn mul_by_cofactor(p: &Point) -> Point {
+        Point::double(&Point::double(&Point::double(p)))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.equal(&Point::identity())
+    }
+
+    // returns true if p belongs to the torsion subgroup of order 8, that is, if [8]p is the identity.
+    pub fn is_small_order(&self) -> bool {
+        Point::mul_by_cofactor(self).is_identity()
+    }
+
+    // returns true if p has no small-order component, that is, if p lies in the subgroup of
+    // order l generated by B and [l]p is the identity.
+    pub fn is_torsion_free(&self) -> bool {
+        Point::scalar_mul(&Scalar::ORDER, self).is_identity()
+    }
+
+    // calculate -p = (-x, y).
+    pub fn negate(p: &Point) -> Point {
+        Point {
+            x: Element::negate(&p.x),
+            y: p.y.clone(),
+            z: p.z.clone(),
+            t: Element::negate(&p.t),
+        }
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Point, b: &Point, cond: u32) {
+        self.x.select(&a.x, &b.x, cond);
+        self.y.select(&a.y, &b.y, cond);
+        self.z.select(&a.z, &b.z, cond);
+        self.t.select(&a.t, &b.t, cond);
+    }
+
+    // scalar_mul returns [k]P, where k is a little-endian 256-bit integer.
+    // The scalar need not be reduced, so the clamped secret scalars of RFC 8032 and
+    // RFC 7748 can be used directly. It uses a fixed 4-bit window; every table entry is
+    // read for every window, so the running time does not depend on the scalar.
+    pub fn scalar_mul(k: &[u8; 32], p: &Point) -> Point {
+        let mut table: Vec<Point> = Vec::with_capacity(16);
+        table.push(Point::identity());
+        for i in 1..16 {
+            let next = Point::add(&table[i - 1], p);
+            table.push(next);
+        }
+
+        let mut q = Point::identity();
+        for pos in (0..64).rev() {
+            for _ in 0..4 {
+                q = Point::double(&q);
+            }
+            let nibble: u8 = (k[pos / 2] >> (4 * (pos & 1))) & 0x0f;
+            let mut entry = Point::identity();
+            for (i, t) in table.iter().enumerate() {
+                let e = entry.clone();
+                entry.select(t, &e, subtle::constant_time_byte_eq(i as u8, nibble));
+            }
+            q = Point::add(&q, &entry);
+        }
+        q
+    }
+
+    // basepoint_mul returns [k]B for the edwards25519 base point B.
+    // Since B has order l, k is first reduced modulo l, and any 256-bit integer is accepted.
+    //
+    // The reduced scalar is written in signed radix 16, k = sum(k_i * 16^i) with k_i in [-8, 8],
+    // and split by the parity of i:
+    //     [k]B = [16] sum([k_(2j+1)] 256^j B) + sum([k_(2j)] 256^j B).
+    // Row j of the basepoint table holds [1]256^j B through [8]256^j B, so each sum costs
+    // 32 table lookups and mixed additions, and the whole product only four doublings.
+    pub fn basepoint_mul(k: &[u8; 32]) -> Point {
+        let digits = Scalar::from_bytes_mod_order(*k).signed_radix16();
+        let table = basepoint_table();
+
+        let mut q = Point::identity();
+        for i in (1..64).step_by(2) {
+            q = Point::add_affine(&q, &table[i / 2].select(digits[i]));
+        }
+        for _ in 0..4 {
+            q = Point::double(&q);
+        }
+        for i in (0..64).step_by(2) {
+            q = Point::add_affine(&q, &table[i / 2].select(digits[i]));
+        }
+        q
+    }
+
+    // vartime_double_scalar_base_mul returns [a]A + [b]B, where B is the edwards25519 base point.
+    // This is the shape of signature verification, [S]B - [k]A = [k](-A) + [S]B. Both scalars are
+    // recoded in width-w NAF and share one chain of doublings (interleaved wNAF): A with w = 5
+    // and eight odd multiples computed on the fly, B with w = 8 and 64 precomputed odd multiples.
+    // Its running time depends on the scalars: use it only on public data.
+    pub fn vartime_double_scalar_base_mul(a: &Scalar, p: &Point, b: &Scalar) -> Point {
+        let a_naf = a.non_adjacent_form(5);
+        let b_naf = b.non_adjacent_form(8);
+        let a_table = Point::odd_multiples(p, 8);
+        let b_table = basepoint_naf_table();
+
+        // Skip the leading zero digits, which would only double the identity.
+        let top = (0..256).rev().find(|&i| a_naf[i] != 0 || b_naf[i] != 0);
+        let mut q = Point::identity();
+        for i in (0..=top.unwrap_or(0)).rev() {
+            q = Point::double(&q);
+            match a_naf[i] {
+                d if d > 0 => q = Point::add(&q, &a_table[d as usize / 2]),
+                d if d < 0 => q = Point::subtract(&q, &a_table[-d as usize / 2]),
+                _ => {}
+            }
+            match b_naf[i] {
+                d if d > 0 => q = Point::add_affine(&q, &b_table[d as usize / 2]),
+                d if d < 0 => q = Point::add_affine(&q, &AffineCached::negate(&b_table[-d as usize / 2])),
+                _ => {}
+            }
+        }
+        q
+    }
+
+    // odd_multiples returns [1]P, [3]P, ..., [2n-1]P.
+    pub(crate) fn odd_multiples(p: &Point, n: usize) -> Vec<Point> {
+        let p2 = Point::double(p);
+        let mut table: Vec<Point> = Vec::with_capacity(n);
+        table.push(p.clone());
+        for i in 1..n {
+            let next = Point::add(&table[i - 1], &p2);
+            table.push(next);
+        }
+        table
+    }
+
+    // calculate p + q for a precomputed affine q. With Z2 = 1 the addition needs
+    // one multiplication less than add (madd-2008-hwcd-3).
+    fn add_affine(p: &Point, q: &AffineCached) -> Point {
+        let a = Element::multiply(&Element::subtract(&p.y, &p.x), &q.y_minus_x);
+        let b = Element::multiply(&Element::add(&p.y, &p.x), &q.y_plus_x);
+        let c = Element::multiply(&p.t, &q.t2d);
+        let d = Element::add(&p.z, &p.z);
+        let e = Element::subtract(&b, &a);
+        let f = Element::subtract(&d, &c);
+        let g = Element::add(&d, &c);
+        let h = Element::add(&b, &a);
+        Point {
+            x: Element::multiply(&e, &f),
+            y: Element::multiply(&g, &h),
+            z: Element::multiply(&f, &g),
+            t: Element::multiply(&e, &h),
+        }
+    }
+}
+
+// AffineCached holds an affine point (x, y) as (y + x, y - x, 2*d*x*y), the form in which
+// add_affine consumes it. The identity is (1, 1, 0), and negation swaps the first two
+// coordinates and negates the third.
+#[derive(Clone, Debug)]
+struct AffineCached {
+    y_plus_x: Element,
+    y_minus_x: Element,
+    t2d: Element,
+}
+
+impl AffineCached {
+    fn identity() -> AffineCached {
+        AffineCached {
+            y_plus_x: Element::ONE.clone(),
+            y_minus_x: Element::ONE.clone(),
+            t2d: Element::ZERO.clone(),
+        }
+    }
+
+    fn from_point(p: &Point) -> AffineCached {
+        let z_inv = Element::invert(&p.z);
+        let x = Element::multiply(&p.x, &z_inv);
+        let y = Element::multiply(&p.y, &z_inv);
+        AffineCached {
+            y_plus_x: Element::add(&y, &x),
+            y_minus_x: Element::subtract(&y, &x),
+            t2d: Element::multiply(&Element::multiply(&x, &y), Point::D2),
+        }
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    fn select(&mut self, a: &AffineCached, b: &AffineCached, cond: u32) {
+        self.y_plus_x.select(&a.y_plus_x, &b.y_plus_x, cond);
+        self.y_minus_x.select(&a.y_minus_x, &b.y_minus_x, cond);
+        self.t2d.select(&a.t2d, &b.t2d, cond);
+    }
+
+    fn negate(a: &AffineCached) -> AffineCached {
+        AffineCached {
+            y_plus_x: a.y_minus_x.clone(),
+            y_minus_x: a.y_plus_x.clone(),
+            t2d: Element::negate(&a.t2d),
+        }
+    }
+
+    // negates v if cond == 1, and leaves it unchanged if cond == 0.
+    fn cond_negate(&mut self, cond: u32) {
+        Element::swap(&mut self.y_plus_x, &mut self.y_minus_x, cond);
+        let t2d = self.t2d.clone();
+        self.t2d.select(&Element::negate(&t2d), &t2d, cond);
+    }
+}
+
+// AffineLookupTable holds [1]P, [2]P, ..., [8]P for a fixed point P.
+#[derive(Clone, Debug)]
+struct AffineLookupTable([AffineCached; 8]);
+
+impl AffineLookupTable {
+    fn new(p: &Point) -> AffineLookupTable {
+        let mut q = p.clone();
+        AffineLookupTable(core::array::from_fn(|_| {
+            let entry = AffineCached::from_point(&q);
+            q = Point::add(&q, p);
+            entry
+        }))
+    }
+
+    // select returns [x]P for x in [-8, 8]. Every entry is read, and the sign is applied
+    // with a conditional negation, so the memory access pattern does not depend on x.
+    fn select(&self, x: i8) -> AffineCached {
+        // |x|, computed without branches
+        let x_mask = x >> 7;
+        let x_abs = ((x + x_mask) ^ x_mask) as u8;
+
+        let mut v = AffineCached::identity();
+        for (j, entry) in self.0.iter().enumerate() {
+            let w = v.clone();
+            v.select(entry, &w, subtle::constant_time_byte_eq(x_abs, j as u8 + 1));
+        }
+        v.cond_negate((x_mask & 1) as u32);
+        v
+    }
+}
+
+// basepoint_table returns the 32 rows used by Point::basepoint_mul; row j holds the
+// multiples of 256^j B. The table is about 30 KB and is built on first use.
+fn basepoint_table() -> &'static [AffineLookupTable; 32] {
+    static BASEPOINT_TABLE: OnceLock<[AffineLookupTable; 32]> = OnceLock::new();
+    BASEPOINT_TABLE.get_or_init(|| {
+        let mut p = Point::basepoint();
+        core::array::from_fn(|_| {
+            let row = AffineLookupTable::new(&p);
+            for _ in 0..8 {
+                p = Point::double(&p);
+            }
+            row
+        })
+    })
+}
+
+// basepoint_naf_table returns [1]B, [3]B, ..., [127]B, the odd multiples needed for the
+// width-8 NAF digits of Point::vartime_double_scalar_base_mul. It is built on first use.
+fn basepoint_naf_table() -> &'static [AffineCached] {
+    static BASEPOINT_NAF_TABLE: OnceLock<Vec<AffineCached>> = OnceLock::new();
+    BASEPOINT_NAF_TABLE.get_or_init(|| {
+        Point::odd_multiples(&Point::basepoint(), 64).iter().map(AffineCached::from_point).collect()
+    })
+}
+
+#[cfg(test)]
+mod edwards_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::edwards::Point;
+    use crate::field::Element;
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+
+    #[test]
+    fn test_basepoint_encoding() {
+        // The canonical encoding of B is the little-endian y = 4/5 with a clear sign bit.
+        let b_bytes = hex_decode("5866666666666666666666666666666666666666666666666666666666666666");
+        assert_eq!(Point::basepoint().to_bytes().to_vec(), b_bytes);
+
+        let b = Point::from_bytes(&b_bytes.try_into().unwrap()).unwrap();
+        assert!(b.equal(&Point::basepoint()));
+
+        // x^2 * y^2 = T^2 / Z^2 must hold for the precomputed coordinates.
+        let b = Point::basepoint();
+        assert_eq!(Element::multiply(&b.x, &b.y), b.t);
+    }
+
+    #[test]
+    fn test_group_law() {
+        let b = Point::basepoint();
+        let b2 = Point::double(&b);
+        assert!(b2.equal(&Point::add(&b, &b)));
+        let b3 = Point::add(&b2, &b);
+        assert!(Point::subtract(&b3, &b).equal(&b2));
+        assert!(Point::add(&b, &Point::negate(&b)).equal(&Point::identity()));
+        assert!(Point::add(&b, &Point::identity()).equal(&b));
+
+        let mut three: [u8; 32] = [0; 32];
+        three[0] = 3;
+        assert!(Point::basepoint_mul(&three).equal(&b3));
+    }
+
+    #[test]
+    fn test_basepoint_order() {
+        // [l]B is the identity.
+        let l: [u8; 32] = crate::scalar::Scalar::ORDER;
+        assert!(Point::basepoint_mul(&l).equal(&Point::identity()));
+    }
+
+    #[test]
+    fn test_small_order() {
+        // the eight points of the torsion subgroup
+        let torsion = [
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a",
+            "0000000000000000000000000000000000000000000000000000000000000080",
+            "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05",
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc85",
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac03fa",
+        ];
+        let t8 = Point::from_bytes(&hex_decode(torsion[1]).try_into().unwrap()).unwrap();
+        let mut t = Point::identity();
+        for enc in torsion {
+            assert_eq!(t.to_bytes().to_vec(), hex_decode(enc));
+            assert!(t.is_small_order());
+            t = Point::add(&t, &t8);
+        }
+        assert!(t.is_identity());
+
+        let b = Point::basepoint();
+        assert!(!b.is_small_order());
+        assert!(!Point::add(&b, &t8).is_small_order());
+        assert!(Point::mul_by_cofactor(&Point::add(&b, &t8)).equal(&Point::mul_by_cofactor(&b)));
+    }
+
+    #[test]
+    fn test_torsion_free() {
+        let t8 = Point::from_bytes(&hex_decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a")
+            .try_into().unwrap()).unwrap();
+        let p = Point::scalar_mul(&[0x5a; 32], &Point::basepoint());
+        assert!(Point::identity().is_torsion_free());
+        assert!(Point::basepoint().is_torsion_free());
+        assert!(p.is_torsion_free());
+        assert!(!t8.is_torsion_free());
+        assert!(!Point::add(&p, &t8).is_torsion_free());
+        assert!(Point::mul_by_cofactor(&Point::add(&p, &t8)).is_torsion_free());
+    }
+
+    #[test]
+    fn test_basepoint_mul() {
+        let b = Point::basepoint();
+        let mut rng = StdRng::seed_from_u64(0x7AB1E);
+        let mut scalars: Vec<[u8; 32]> = vec![[0; 32], [0xff; 32], Scalar::ORDER, Scalar::MINUS_ONE.to_bytes()];
+        // every signed digit from -8 through 8 occurs in these
+        scalars.push([0x88; 32]);
+        scalars.push([0x78; 32]);
+        for _ in 0..16 {
+            scalars.push(rng.gen());
+        }
+        for k in scalars {
+            assert!(Point::basepoint_mul(&k).equal(&Point::scalar_mul(&k, &b)));
+        }
+    }
+
+    #[test]
+    fn test_vartime_double_scalar_base_mul() {
+        let mut rng = StdRng::seed_from_u64(0xD0B1E);
+        let p = Point::scalar_mul(&[0x3c; 32], &Point::basepoint());
+        let mut pairs: Vec<(Scalar, Scalar)> = vec![
+            (Scalar::ZERO.clone(), Scalar::ZERO.clone()),
+            (Scalar::ZERO.clone(), Scalar::MINUS_ONE.clone()),
+            (Scalar::MINUS_ONE.clone(), Scalar::ONE.clone()),
+        ];
+        for _ in 0..16 {
+            pairs.push((Scalar::from_bytes_mod_order(rng.gen()), Scalar::from_bytes_mod_order(rng.gen())));
+        }
+        for (a, b) in pairs {
+            let expected = Point::add(&Point::scalar_mul(&a.to_bytes(), &p), &Point::basepoint_mul(&b.to_bytes()));
+            assert!(Point::vartime_double_scalar_base_mul(&a, &p, &b).equal(&expected));
+        }
+
+        // a point with a small-order component keeps it
+        let t8 = Point::from_bytes(&hex_decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a")
+            .try_into().unwrap()).unwrap();
+        let a = Scalar::from_bytes_mod_order([0x15; 32]);
+        let b = Scalar::from_bytes_mod_order([0x51; 32]);
+        let pt = Point::add(&p, &t8);
+        let expected = Point::add(&Point::scalar_mul(&a.to_bytes(), &pt), &Point::basepoint_mul(&b.to_bytes()));
+        assert!(Point::vartime_double_scalar_base_mul(&a, &pt, &b).equal(&expected));
+    }
+
+    #[test]
+    fn test_montgomery_map() {
+        // B corresponds to u = 9, and its x-coordinate is non-negative.
+        let mut nine: [u8; 32] = [0; 32];
+        nine[0] = 9;
+        let b = Point::basepoint();
+        assert_eq!(b.to_montgomery(), nine);
+        assert!(Point::from_montgomery(&nine, 0).unwrap().equal(&b));
+        assert!(Point::from_montgomery(&nine, 1).unwrap().equal(&Point::negate(&b)));
+
+        let mut rng = StdRng::seed_from_u64(0x3047);
+        for _ in 0..16 {
+            let p = Point::scalar_mul(&rng.gen(), &b);
+            let sign = p.to_bytes()[31] >> 7;
+            assert!(Point::from_montgomery(&p.to_montgomery(), sign).unwrap().equal(&p));
+        }
+
+        // (0, -1) maps to u = 0, and u = -1 has no image.
+        let t2 = Point::from_bytes(&hex_decode("ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
+            .try_into().unwrap()).unwrap();
+        assert_eq!(t2.to_montgomery(), [0; 32]);
+        assert!(Point::from_montgomery(&[0; 32], 0).unwrap().equal(&t2));
+        let minus_one: [u8; 32] = hex_decode("ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
+            .try_into().unwrap();
+        assert!(Point::from_montgomery(&minus_one, 0).is_err());
+        // u = 2 is on the twist
+        let mut two: [u8; 32] = [0; 32];
+        two[0] = 2;
+        assert!(Point::from_montgomery(&two, 0).is_err());
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        // y = 2 is not the y-coordinate of any point on the curve.
+        let mut b: [u8; 32] = [0; 32];
+        b[0] = 2;
+        assert!(Point::from_bytes(&b).is_err());
+    }
+}
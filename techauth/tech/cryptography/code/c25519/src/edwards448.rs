@@ -0,0 +1,228 @@
+#![allow(dead_code)]
+
+use crate::field448::Element;
+use crate::subtle;
+
+// Point is a point of Edwards448, the untwisted Edwards curve
+//     x^2 + y^2 = 1 + d*x^2*y^2,  d = -39081,
+// over GF(2^448 - 2^224 - 1), in projective coordinates (X:Y:Z) with x = X/Z and y = Y/Z
+// [RFC 8032, 5.2]. The group has order 4*L, and the base point has order L.
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub(crate) x: Element,
+    pub(crate) y: Element,
+    pub(crate) z: Element,
+}
+
+impl Point {
+    // -d
+    const MINUS_D: u32 = 39081;
+
+    // the base point B of RFC 8032, Section 5.2, with y = 2989...2660 and an even x
+    const BX: &'static Element = &Element([
+        0x26a82bc70cc05e, 0x80e18b00938e26, 0xf72ab66511433b, 0xa3d3a46412ae1a,
+        0x0f1767ea6de324, 0x36da9e14657047, 0xed221d15a622bf, 0x4f1970c66bed0d,
+    ]);
+    const BY: &'static Element = &Element([
+        0x08795bf230fa14, 0x132c4ed7c8ad98, 0x1ce67c39c4fdbd, 0x05a0c2d73ad3ff,
+        0xa3984087789c1e, 0xc7624bea73736c, 0x248876203756c9, 0x693f46716eb6bc,
+    ]);
+
+    pub fn identity() -> Point {
+        Point { x: Element::ZERO.clone(), y: Element::ONE.clone(), z: Element::ONE.clone() }
+    }
+
+    pub fn basepoint() -> Point {
+        Point { x: Point::BX.clone(), y: Point::BY.clone(), z: Element::ONE.clone() }
+    }
+
+    // from_bytes decodes a point as described in RFC 8032, Section 5.2.3. Unlike the
+    // edwards25519 decoding, it is strict: y must be canonical, bits 448 to 454 must be zero,
+    // and x = 0 must not come with the sign bit set.
+    pub fn from_bytes(b: &[u8; 57]) -> Result<Point, ()> {
+        let y_bytes: [u8; 56] = b[..56].try_into().unwrap();
+        let y = Element::from_le_bytes(&y_bytes);
+        if b[56] & 0x7F != 0 || !subtle::constant_time_compare(&y.to_le_bytes(), &y_bytes) {
+            return Err(());
+        }
+
+        // x^2 = (y^2 - 1) / (d*y^2 - 1)
+        let y2 = Element::square(&y);
+        let u = Element::subtract(&y2, Element::ONE);
+        let v = Element::negate(&Element::add(&Element::mul32(&y2, Point::MINUS_D), Element::ONE));
+        let (x, was_square) = Element::sqrt_ratio(&u, &v);
+        let sign = (b[56] >> 7) as u32;
+        if was_square == 0 || (sign == 1 && x.equal(Element::ZERO)) {
+            return Err(());
+        }
+
+        let mut x_signed = Element::ZERO.clone();
+        x_signed.select(&Element::negate(&x), &x, sign);
+        Ok(Point { x: x_signed, y, z: Element::ONE.clone() })
+    }
+
+    // to_bytes encodes the point as the 448-bit y-coordinate, followed by an octet that holds
+    // the sign of x in its most significant bit [RFC 8032, 5.2.2].
+    pub fn to_bytes(&self) -> [u8; 57] {
+        let z_inv = Element::invert(&self.z);
+        let x = Element::multiply(&self.x, &z_inv);
+        let y = Element::multiply(&self.y, &z_inv);
+        let mut b: [u8; 57] = [0; 57];
+        b[..56].copy_from_slice(&y.to_le_bytes());
+        b[56] = (x.is_negative() << 7) as u8;
+        b
+    }
+
+    // Checks whether two points are equal: X1*Z2 == X2*Z1 and Y1*Z2 == Y2*Z1.
+    pub fn equal(&self, q: &Point) -> bool {
+        Element::multiply(&self.x, &q.z).equal(&Element::multiply(&q.x, &self.z))
+            && Element::multiply(&self.y, &q.z).equal(&Element::multiply(&q.y, &self.z))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.equal(&Point::identity())
+    }
+
+    // add returns p + q with the complete formulas of RFC 8032, Section 5.2.4, which also
+    // double and handle the identity:
+    //     A = Z1*Z2,  B = A^2,  C = X1*X2,  D = Y1*Y2,  E = d*C*D,  F = B - E,  G = B + E,
+    //     H = (X1 + Y1)*(X2 + Y2),  X3 = A*F*(H - C - D),  Y3 = A*G*(D - C),  Z3 = F*G
+    pub fn add(p: &Point, q: &Point) -> Point {
+        let a = Element::multiply(&p.z, &q.z);
+        let b = Element::square(&a);
+        let c = Element::multiply(&p.x, &q.x);
+        let d = Element::multiply(&p.y, &q.y);
+        let e = Element::negate(&Element::mul32(&Element::multiply(&c, &d), Point::MINUS_D));
+        let f = Element::subtract(&b, &e);
+        let g = Element::add(&b, &e);
+        let h = Element::multiply(&Element::add(&p.x, &p.y), &Element::add(&q.x, &q.y));
+        Point {
+            x: Element::multiply(&Element::multiply(&a, &f), &Element::subtract(&Element::subtract(&h, &c), &d)),
+            y: Element::multiply(&Element::multiply(&a, &g), &Element::subtract(&d, &c)),
+            z: Element::multiply(&f, &g),
+        }
+    }
+
+    pub fn subtract(p: &Point, q: &Point) -> Point {
+        Point::add(p, &Point::negate(q))
+    }
+
+    // double returns 2p [RFC 8032, 5.2.4]:
+    //     B = (X1 + Y1)^2,  C = X1^2,  D = Y1^2,  E = C + D,  H = Z1^2,  J = E - 2*H,
+    //     X3 = (B - E)*J,  Y3 = E*(C - D),  Z3 = E*J
+    pub fn double(p: &Point) -> Point {
+        let b = Element::square(&Element::add(&p.x, &p.y));
+        let c = Element::square(&p.x);
+        let d = Element::square(&p.y);
+        let e = Element::add(&c, &d);
+        let h = Element::square(&p.z);
+        let j = Element::subtract(&e, &Element::add(&h, &h));
+        Point {
+            x: Element::multiply(&Element::subtract(&b, &e), &j),
+            y: Element::multiply(&e, &Element::subtract(&c, &d)),
+            z: Element::multiply(&e, &j),
+        }
+    }
+
+    // mul_by_cofactor returns [4]P.
+    pub fn mul_by_cofactor(p: &Point) -> Point {
+        Point::double(&Point::double(p))
+    }
+
+    pub fn negate(p: &Point) -> Point {
+        Point { x: Element::negate(&p.x), y: p.y.clone(), z: p.z.clone() }
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Point, b: &Point, cond: u32) {
+        self.x.select(&a.x, &b.x, cond);
+        self.y.select(&a.y, &b.y, cond);
+        self.z.select(&a.z, &b.z, cond);
+    }
+
+    // scalar_mul returns [k]P for a little-endian k of 57 octets. It doubles and adds for
+    // every bit, and keeps the sum only if the bit is set, so its running time does not
+    // depend on k.
+    pub fn scalar_mul(k: &[u8; 57], p: &Point) -> Point {
+        let mut q = Point::identity();
+        for pos in (0..8 * 57).rev() {
+            q = Point::double(&q);
+            let sum = Point::add(&q, p);
+            let bit = ((k[pos / 8] >> (pos & 7)) & 1) as u32;
+            let prev = q.clone();
+            q.select(&sum, &prev, bit);
+        }
+        q
+    }
+
+    // basepoint_mul returns [k]B.
+    pub fn basepoint_mul(k: &[u8; 57]) -> Point {
+        Point::scalar_mul(k, &Point::basepoint())
+    }
+}
+
+#[cfg(test)]
+mod edwards448_test {
+    use crate::edwards448::Point;
+    use crate::field448::Element;
+    use crate::test_util::hex_decode;
+
+    const ORDER: &str = "f34458ab92c27823558fc58d72c26c219036d6ae49db4ec4e923ca7cffffffffffffffffffffffffffffffffffffffffffffffffffffff3f00";
+
+    // The base point is on the curve, has order L and encodes as in RFC 8032.
+    #[test]
+    fn test_basepoint() {
+        let b = Point::basepoint();
+        let x2 = Element::square(&b.x);
+        let y2 = Element::square(&b.y);
+        let lhs = Element::add(&x2, &y2);
+        let dx2y2 = Element::negate(&Element::mul32(&Element::multiply(&x2, &y2), 39081));
+        assert!(lhs.equal(&Element::add(Element::ONE, &dx2y2)));
+
+        let encoded = b.to_bytes();
+        assert_eq!(encoded.to_vec(), hex_decode(
+            "14fa30f25b790898adc8d74e2c13bdfdc4397ce61cffd33ad7c2a0051e9c78874098a36c7373ea4b62c7c9563720768824bcb66e71463f6900"));
+        assert!(Point::from_bytes(&encoded).unwrap().equal(&b));
+
+        let l: [u8; 57] = hex_decode(ORDER).try_into().unwrap();
+        assert!(Point::basepoint_mul(&l).is_identity());
+        assert!(!Point::double(&b).is_identity());
+    }
+
+    #[test]
+    fn test_group_law() {
+        let b = Point::basepoint();
+        let b2 = Point::double(&b);
+        assert!(Point::add(&b, &b).equal(&b2));
+        assert!(Point::add(&b, &Point::identity()).equal(&b));
+        assert!(Point::subtract(&b2, &b).equal(&b));
+        assert!(Point::add(&b, &Point::negate(&b)).is_identity());
+
+        let mut three: [u8; 57] = [0; 57];
+        three[0] = 3;
+        assert!(Point::basepoint_mul(&three).equal(&Point::add(&b2, &b)));
+        assert!(Point::from_bytes(&b2.to_bytes()).unwrap().equal(&b2));
+    }
+
+    // Non-canonical y, nonzero bits 448 to 454 and a negative zero x are rejected.
+    #[test]
+    fn test_from_bytes_strict() {
+        let mut identity: [u8; 57] = [0; 57];
+        identity[0] = 1;
+        assert!(Point::from_bytes(&identity).unwrap().is_identity());
+
+        let mut negative_zero = identity;
+        negative_zero[56] = 0x80;
+        assert!(Point::from_bytes(&negative_zero).is_err());
+
+        let mut high_bits = Point::basepoint().to_bytes();
+        high_bits[56] |= 0x01;
+        assert!(Point::from_bytes(&high_bits).is_err());
+
+        // p + 1, a non-canonical encoding of y = 1
+        let mut non_canonical: [u8; 57] = [0xFF; 57];
+        non_canonical[..28].fill(0);
+        non_canonical[56] = 0;
+        assert!(Point::from_bytes(&non_canonical).is_err());
+    }
+}
@@ -0,0 +1,241 @@
+#![allow(dead_code)]
+
+use rand::{CryptoRng, RngCore};
+
+use crate::edwards::Point;
+use crate::field::Element;
+use crate::hash_to_curve;
+use crate::x25519::{self, PrivateKey, PublicKey};
+
+// Elligator 2 representatives of X25519 public keys ("Elligator: Elliptic-curve points
+// indistinguishable from uniform random strings", Bernstein, Hamburg, Krasnova and Lange).
+//
+// A public key is a u-coordinate, and not every 255-bit string is one: anyone can tell
+// a public key from random bytes by checking that u^3 + A*u^2 + u is a square. The Elligator 2
+// map sends a field element r to a u-coordinate, and is injective on the non-negative r,
+// here 0 <= r <= (p-1)/2 < 2^254. About half of the u-coordinates are in its image; for those,
+// the inverse map yields a representative r whose 254 bits, topped up with two random bits,
+// are uniformly distributed.
+//
+// The forward map is the map_to_curve of the hash-to-curve suites, so a representative r
+// stands for the point elligator2(r) of RFC 9380, Section 6.7.1:
+//     u1 = -A / (1 + 2*r^2),  u2 = -u1 - A,  u = u1 if u1^3 + A*u1^2 + u1 is square, else u2
+// Solving for r gives the two branches of the inverse map,
+//     r^2 = -(u + A) / (2*u)     (u = u1)
+//     r^2 = -u / (2*(u + A))     (u = u2)
+// whose product is 1/4, so for u other than 0 and -A both have a root or neither does. The
+// branch is picked by the caller; it corresponds to the choice of v in (u, v), which X25519
+// drops.
+//
+// The public keys of PrivateKey::public_key lie in the subgroup of order l, while the image
+// of the map covers the whole curve. A careful observer who computes [l]P for many
+// representatives can still tell them apart. Hiding that requires a public key with a random
+// low-order component, which X25519 ignores in the shared secret: generate_representable
+// adds one.
+
+// A point of order 8 on edwards25519. Its multiples [i]T, 0 <= i < 8, are the eight points
+// of the torsion subgroup.
+const TORSION_GENERATOR: [u8; 32] = [
+    0xc7, 0x17, 0x6a, 0x70, 0x3d, 0x4d, 0xd8, 0x4f, 0xba, 0x3c, 0x0b, 0x76, 0x0d, 0x10, 0x67, 0x0f,
+    0x2a, 0x20, 0x53, 0xfa, 0x2c, 0x39, 0xcc, 0xc6, 0x4e, 0xc7, 0xfd, 0x77, 0x92, 0xac, 0x03, 0x7a,
+];
+
+impl PublicKey {
+    // from_representative returns the public key that the representative stands for.
+    // The top two bits of the representative are random padding and are ignored.
+    pub fn from_representative(representative: &[u8; 32]) -> PublicKey {
+        let mut b = *representative;
+        b[31] &= 0x3F;
+        let (u, _) = hash_to_curve::elligator2(&Element::from_le_bytes(b));
+        PublicKey::new(u.to_le_bytes())
+    }
+
+    // to_representative returns the representative of the public key, or an error if it has
+    // none. The low bit of tweak selects the branch of the inverse map, and its top two bits
+    // become the top two bits of the representative, so tweak must be random.
+    // Only the u-coordinates of points on curve25519 have representatives.
+    pub fn to_representative(&self, tweak: u8) -> Result<[u8; 32], ()> {
+        let a = &Element(hash_to_curve::A as u64, 0, 0, 0, 0);
+        let u = Element::from_le_bytes(self.public);
+        let u_plus_a = Element::add(&u, a);
+
+        let branch = (tweak & 1) as u32;
+        let mut num = Element::ZERO.clone();
+        let mut den = Element::ZERO.clone();
+        num.select(&Element::negate(&u), &Element::negate(&u_plus_a), branch);
+        den.select(&Element::add(&u_plus_a, &u_plus_a), &Element::add(&u, &u), branch);
+        // den is zero for u = 0 on branch 0 and for u = -A on branch 1, where num is not, so
+        // sqrt_ratio finds no root. u = 0, the point of order 2, thus has a single
+        // representative, 0, on branch 1.
+        let (mut r, was_square) = Element::sqrt_ratio(&num, &den);
+
+        // The inverse map also has roots for some u on the twist, but the forward map sends
+        // them to -u - A: u must be on the curve, with u^3 + A*u^2 + u a square. This rejects
+        // u = -A too, which lies on the twist and has the root 0 on branch 0.
+        let gu = Element::multiply(&u, &Element::add(&Element::multiply(&u, &u_plus_a), Element::ONE));
+        let (_, on_curve) = Element::sqrt_ratio(&gu, Element::ONE);
+        let representable = was_square & on_curve;
+
+        // sqrt_ratio returns the even root. Take the one at most (p-1)/2 instead, which is the
+        // root r with 2*r < p, that is, with 2*r even.
+        let r_neg = Element::negate(&r);
+        let r_pos = r.clone();
+        r.select(&r_neg, &r_pos, Element::add(&r_pos, &r_pos).is_negative());
+
+        let mut representative = r.to_le_bytes();
+        representative[31] |= tweak & 0xC0;
+        if representable == 1 {
+            Ok(representative)
+        } else {
+            Err(())
+        }
+    }
+}
+
+impl PrivateKey {
+    // generate_representable returns a fresh private key, the public key to publish for it
+    // and the representative of that public key. Half of the keys qualify, so it takes two
+    // tries on average.
+    //
+    // The returned public key is not that of public_key but [k]B + [i]T, for the clamped
+    // scalar k and a random i in 0..8, so that it lies in any of the eight cosets of the
+    // subgroup of order l. The peer's clamped scalar is a multiple of 8 too and clears [i]T,
+    // so the shared secret is that of public_key. The peer recovers the returned public key
+    // with from_representative.
+    pub fn generate_representable<R: RngCore + CryptoRng>(rng: &mut R) -> (PrivateKey, PublicKey, [u8; 32]) {
+        let t = Point::from_bytes(&TORSION_GENERATOR).unwrap();
+        loop {
+            let mut key: [u8; 32] = [0; 32];
+            rng.fill_bytes(&mut key);
+            let mut tweak: [u8; 1] = [0];
+            rng.fill_bytes(&mut tweak);
+            let mut i: [u8; 32] = [0; 32];
+            i[0] = (rng.next_u32() & 7) as u8;
+
+            let private_key = PrivateKey::new(key);
+            let p = Point::add(&Point::basepoint_mul(&x25519::scalar_clamp(private_key.private)),
+                               &Point::scalar_mul(&i, &t));
+            let public_key = PublicKey::new(p.to_montgomery());
+            if let Ok(representative) = public_key.to_representative(tweak[0]) {
+                return (private_key, public_key, representative);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod elligator_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::edwards::Point;
+    use crate::test_util::hex_decode;
+    use crate::x25519::{self, PrivateKey, PublicKey};
+
+    #[test]
+    fn test_from_representative() {
+        let representative: [u8; 32] = core::array::from_fn(|i| i as u8);
+        assert_eq!(PublicKey::from_representative(&representative).public,
+                   hex_decode("5f3520001c6c9936a31206afe7c7ac224e8861619bf98872444915899d95f46e").as_slice());
+        // the padding bits do not matter
+        let mut padded = representative;
+        padded[31] |= 0xC0;
+        assert_eq!(PublicKey::from_representative(&padded).public,
+                   PublicKey::from_representative(&representative).public);
+
+        let representative: [u8; 32] = [0xFF; 32];
+        assert_eq!(PublicKey::from_representative(&representative).public,
+                   hex_decode("80e5132b658f7f451b2b658f7f451b2b658f7f451b2b658f7f451b2b658f7f45").as_slice());
+    }
+
+    // Both branches of the inverse map are representatives of the same public key, and the
+    // tweak fills the top two bits.
+    #[test]
+    fn test_representative_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0xE11);
+        let mut representable = 0;
+        for _ in 0..128 {
+            let public_key = PrivateKey::new(rng.gen()).public_key();
+            let tweak: u8 = rng.gen();
+            let r0 = public_key.to_representative(tweak & 0xFE);
+            let r1 = public_key.to_representative(tweak | 1);
+            assert_eq!(r0.is_ok(), r1.is_ok());
+            if let (Ok(r0), Ok(r1)) = (r0, r1) {
+                representable += 1;
+                assert_ne!(r0, r1);
+                assert_eq!(r0[31] & 0xC0, tweak & 0xC0);
+                assert_eq!(r1[31] & 0xC0, tweak & 0xC0);
+                assert_eq!(PublicKey::from_representative(&r0).public, public_key.public);
+                assert_eq!(PublicKey::from_representative(&r1).public, public_key.public);
+            }
+        }
+        // about half of the public keys are representable
+        assert!((40..=88).contains(&representable));
+    }
+
+    // The representative stands for the returned public key, which is public_key plus a point
+    // of small order: that changes neither [8]P nor the shared secret.
+    #[test]
+    fn test_generate_representable() {
+        let mut rng = StdRng::seed_from_u64(0x5EC);
+        let peer = PrivateKey::new(rng.gen());
+        let mut top_bits = 0;
+        for _ in 0..32 {
+            let (private_key, public_key, representative) = PrivateKey::generate_representable(&mut rng);
+            assert_eq!(PublicKey::from_representative(&representative).public, public_key.public);
+            assert_eq!(x25519::mul_by_cofactor(&public_key.public),
+                       x25519::mul_by_cofactor(&private_key.public_key().public));
+            assert_eq!(peer.ecdh(&public_key), private_key.ecdh(&peer.public_key()));
+            top_bits |= representative[31] & 0xC0;
+        }
+        assert_eq!(top_bits, 0xC0);
+    }
+
+    // Only one key in eight lies in the subgroup of order l, as for a random point of the
+    // image, so [l]P does not single out the representatives of generated keys.
+    #[test]
+    fn test_generate_representable_torsion() {
+        let mut rng = StdRng::seed_from_u64(0x7055);
+        let mut torsion_free = 0;
+        for _ in 0..64 {
+            let (_, public_key, _) = PrivateKey::generate_representable(&mut rng);
+            if x25519::is_torsion_free(&public_key.public) {
+                torsion_free += 1;
+            }
+        }
+        assert!((1..=20).contains(&torsion_free));
+    }
+
+    // u = 0 is the image of r = 0; u = -A lies on the twist.
+    #[test]
+    fn test_representative_edge_cases() {
+        let zero = PublicKey::new([0; 32]);
+        assert!(zero.to_representative(0).is_err());
+        assert_eq!(zero.to_representative(1), Ok([0; 32]));
+        assert_eq!(PublicKey::from_representative(&[0; 32]).public, [0; 32]);
+
+        let minus_a = PublicKey::new(hex_decode("e792f8ffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f")
+            .try_into().unwrap());
+        assert!(minus_a.to_representative(0).is_err());
+        assert!(minus_a.to_representative(1).is_err());
+    }
+
+    // A u-coordinate on the twist has no representative, even where the inverse map has a
+    // root: u = 2, for one, has r^2 = -(2 + A) / 4 square on branch 0.
+    #[test]
+    fn test_representative_twist() {
+        for i in 2..64u8 {
+            let mut u: [u8; 32] = [0; 32];
+            u[0] = i;
+            let public_key = PublicKey::new(u);
+            let r0 = public_key.to_representative(0);
+            let r1 = public_key.to_representative(1);
+            if Point::from_montgomery(&u, 0).is_err() {
+                assert!(r0.is_err() && r1.is_err(), "u = {}", i);
+            } else if let (Ok(r0), Ok(r1)) = (r0, r1) {
+                assert_eq!(PublicKey::from_representative(&r0).public, u);
+                assert_eq!(PublicKey::from_representative(&r1).public, u);
+            }
+        }
+    }
+}
@@ -0,0 +1,409 @@
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+
+use crate::field;
+use crate::prime_field::PrimeField;
+
+// Extension fields GF(p^m) = GF(p)[z] / (f(z)) for a monic irreducible f of degree m over a
+// PrimeField [Guide to Elliptic Curve Cryptography, 2.4; see sagemath/extension-field.sage].
+// An element is a polynomial c_0 + c_1*z + ... + c_(m-1)*z^(m-1) of degree below m.
+//
+// Addition, multiplication and the Frobenius map are constant time, as the base field is;
+// vartime_invert runs the extended Euclidean algorithm, whose steps depend on the degrees of
+// the remainders.
+
+// ExtensionField is the choice of base field and modulus f(z) = z^m + f_(m-1)*z^(m-1) + ... + f_0.
+pub trait ExtensionField {
+    type Base: PrimeField;
+    const DEGREE: usize;
+    // the coefficients f_0, ..., f_(m-1) below the leading z^m
+    fn modulus() -> Vec<Self::Base>;
+}
+
+// GF(p^2) = GF(p)[z] / (z^2 - 2) for p = 2^255 - 19: 2 is not a square modulo p = 5 (mod 8), so
+// z^2 - 2 is irreducible and z is a square root of 2.
+#[derive(Clone, Debug)]
+pub struct Fp25519Sqrt2;
+
+impl ExtensionField for Fp25519Sqrt2 {
+    type Base = field::Element;
+    const DEGREE: usize = 2;
+    fn modulus() -> Vec<field::Element> {
+        vec![field::Element::negate(&<field::Element as PrimeField>::from_u64(2)), field::Element::ZERO.clone()]
+    }
+}
+
+// Element is an element of GF(p^m) as its m coefficients, lowest first.
+#[derive(Debug)]
+pub struct Element<E: ExtensionField>(Vec<E::Base>, PhantomData<E>);
+
+// Clone is not derived, as that would require E: Clone.
+impl<E: ExtensionField> Clone for Element<E> {
+    fn clone(&self) -> Element<E> {
+        Element::new(self.0.clone())
+    }
+}
+
+// The Euclidean algorithm works on polynomials of any degree over the base field, lowest
+// coefficient first; trim removes the leading zero coefficients.
+fn trim<F: PrimeField>(a: &mut Vec<F>) {
+    while a.last().is_some_and(|c| F::is_zero(c) == 1) {
+        a.pop();
+    }
+}
+
+// poly_sub_mul returns a - q*b.
+fn poly_sub_mul<F: PrimeField>(a: &[F], q: &[F], b: &[F]) -> Vec<F> {
+    let mut r = a.to_vec();
+    r.resize(a.len().max(q.len() + b.len()), F::zero());
+    for (i, qi) in q.iter().enumerate() {
+        for (j, bj) in b.iter().enumerate() {
+            r[i + j] = F::sub(&r[i + j], &F::mul(qi, bj));
+        }
+    }
+    trim(&mut r);
+    r
+}
+
+// poly_divmod returns (q, r) with a = q*b + r and deg r < deg b, for a nonzero trimmed b.
+fn poly_divmod<F: PrimeField>(a: &[F], b: &[F]) -> (Vec<F>, Vec<F>) {
+    let mut r = a.to_vec();
+    trim(&mut r);
+    if r.len() < b.len() {
+        return (vec![], r);
+    }
+    let lead_inv = F::invert(b.last().unwrap());
+    let mut q = vec![F::zero(); r.len() - b.len() + 1];
+    for k in (0..q.len()).rev() {
+        let c = F::mul(&r[k + b.len() - 1], &lead_inv);
+        for (j, bj) in b.iter().enumerate() {
+            r[k + j] = F::sub(&r[k + j], &F::mul(&c, bj));
+        }
+        q[k] = c;
+    }
+    r.truncate(b.len() - 1);
+    trim(&mut r);
+    (q, r)
+}
+
+impl<E: ExtensionField> Element<E> {
+    fn new(c: Vec<E::Base>) -> Element<E> {
+        Element(c, PhantomData)
+    }
+
+    pub fn zero() -> Element<E> {
+        Element::new(vec![E::Base::zero(); E::DEGREE])
+    }
+
+    pub fn one() -> Element<E> {
+        Element::from_base(&E::Base::one())
+    }
+
+    // from_base embeds an element of the base field as a constant polynomial.
+    pub fn from_base(a: &E::Base) -> Element<E> {
+        let mut c = vec![E::Base::zero(); E::DEGREE];
+        c[0] = a.clone();
+        Element::new(c)
+    }
+
+    // from_coefficients returns c_0 + c_1*z + ..., or an error unless there are exactly m
+    // coefficients.
+    pub fn from_coefficients(c: &[E::Base]) -> Result<Element<E>, ()> {
+        if c.len() != E::DEGREE {
+            return Err(());
+        }
+        Ok(Element::new(c.to_vec()))
+    }
+
+    pub fn coefficients(&self) -> &[E::Base] {
+        &self.0
+    }
+
+    // z, the class of the variable, which generates the extension
+    pub fn generator() -> Element<E> {
+        let mut c = vec![E::Base::zero(); E::DEGREE];
+        if E::DEGREE > 1 {
+            c[1] = E::Base::one();
+        } else {
+            // z = -f_0 when m = 1
+            c[0] = E::Base::neg(&E::modulus()[0]);
+        }
+        Element::new(c)
+    }
+
+    pub fn add(a: &Element<E>, b: &Element<E>) -> Element<E> {
+        Element::new(a.0.iter().zip(&b.0).map(|(x, y)| E::Base::add(x, y)).collect())
+    }
+
+    pub fn subtract(a: &Element<E>, b: &Element<E>) -> Element<E> {
+        Element::new(a.0.iter().zip(&b.0).map(|(x, y)| E::Base::sub(x, y)).collect())
+    }
+
+    pub fn negate(a: &Element<E>) -> Element<E> {
+        Element::new(a.0.iter().map(E::Base::neg).collect())
+    }
+
+    // multiply computes the schoolbook product of degree 2m - 2, then reduces it from the top
+    // with z^m = -(f_(m-1)*z^(m-1) + ... + f_0).
+    pub fn multiply(a: &Element<E>, b: &Element<E>) -> Element<E> {
+        let m = E::DEGREE;
+        let mut r = vec![E::Base::zero(); 2 * m - 1];
+        for (i, ai) in a.0.iter().enumerate() {
+            for (j, bj) in b.0.iter().enumerate() {
+                r[i + j] = E::Base::add(&r[i + j], &E::Base::mul(ai, bj));
+            }
+        }
+        let f = E::modulus();
+        for k in (m..2 * m - 1).rev() {
+            let c = r[k].clone();
+            for (i, fi) in f.iter().enumerate() {
+                r[k - m + i] = E::Base::sub(&r[k - m + i], &E::Base::mul(&c, fi));
+            }
+        }
+        r.truncate(m);
+        Element::new(r)
+    }
+
+    pub fn square(a: &Element<E>) -> Element<E> {
+        Element::multiply(a, a)
+    }
+
+    // pow returns a^e for a big-endian e, with a square and a multiplication for every bit.
+    pub fn pow(a: &Element<E>, e: &[u8]) -> Element<E> {
+        let mut r = Element::one();
+        for byte in e {
+            for i in (0..8).rev() {
+                r = Element::square(&r);
+                let prod = Element::multiply(&r, a);
+                let prev = r.clone();
+                r.select(&prod, &prev, ((byte >> i) & 1) as u32);
+            }
+        }
+        r
+    }
+
+    // vartime_invert returns 1/a, and 0 for a = 0. It runs the extended Euclidean algorithm
+    // on a and f, keeping s with s*a = r (mod f); as f is irreducible the last nonzero r is a
+    // constant, and 1/a = s/r. Its running time depends on a: use it only on public data.
+    pub fn vartime_invert(a: &Element<E>) -> Element<E> {
+        let mut f = E::modulus();
+        f.push(E::Base::one());
+        let (mut r0, mut s0) = (f, vec![]);
+        let mut r1 = a.0.clone();
+        trim(&mut r1);
+        let mut s1 = vec![E::Base::one()];
+        if r1.is_empty() {
+            return Element::zero();
+        }
+        while !r1.is_empty() {
+            let (q, r) = poly_divmod(&r0, &r1);
+            let s = poly_sub_mul(&s0, &q, &s1);
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s);
+        }
+        let c_inv = E::Base::invert(&r0[0]);
+        let mut c = vec![E::Base::zero(); E::DEGREE];
+        for (ci, si) in c.iter_mut().zip(&s0) {
+            *ci = E::Base::mul(si, &c_inv);
+        }
+        Element::new(c)
+    }
+
+    // frobenius returns a^p. Since c^p = c for c in the base field, it is the substitution
+    // z -> z^p, evaluated by Horner's rule.
+    pub fn frobenius(a: &Element<E>) -> Element<E> {
+        Element::frobenius_with(a, &Element::pow(&Element::generator(), E::Base::CHARACTERISTIC))
+    }
+
+    fn frobenius_with(a: &Element<E>, z_p: &Element<E>) -> Element<E> {
+        let mut r = Element::zero();
+        for c in a.0.iter().rev() {
+            r = Element::add(&Element::multiply(&r, z_p), &Element::from_base(c));
+        }
+        r
+    }
+
+    // norm returns N(a) = a * a^p * ... * a^(p^(m-1)), the product of the conjugates of a,
+    // which lies in the base field.
+    pub fn norm(a: &Element<E>) -> E::Base {
+        let z_p = Element::pow(&Element::generator(), E::Base::CHARACTERISTIC);
+        let mut n = a.clone();
+        let mut conjugate = a.clone();
+        for _ in 1..E::DEGREE {
+            conjugate = Element::frobenius_with(&conjugate, &z_p);
+            n = Element::multiply(&n, &conjugate);
+        }
+        n.0[0].clone()
+    }
+
+    // returns 1 if a and b are equal, and 0 otherwise.
+    pub fn ct_equal(a: &Element<E>, b: &Element<E>) -> u32 {
+        a.0.iter().zip(&b.0).fold(1, |eq, (x, y)| eq & E::Base::ct_equal(x, y))
+    }
+
+    pub fn is_zero(&self) -> u32 {
+        Element::ct_equal(self, &Element::zero())
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Element<E>, b: &Element<E>, cond: u32) {
+        for (i, c) in self.0.iter_mut().enumerate() {
+            *c = E::Base::select(&a.0[i], &b.0[i], cond);
+        }
+    }
+}
+
+#[cfg(test)]
+mod extension_field_test {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use crate::extension_field::{Element, ExtensionField, Fp25519Sqrt2};
+    use crate::field;
+    use crate::prime_field::gf251::Gf251;
+    use crate::prime_field::{conformance, PrimeField};
+
+    // GF(251^5) with f(z) = z^5 + z^4 + 12z^3 + 9z^2 + 7, Example 2.4 of the Guide.
+    #[derive(Clone, Debug)]
+    struct Gf251To5;
+
+    impl ExtensionField for Gf251To5 {
+        type Base = Gf251;
+        const DEGREE: usize = 5;
+        fn modulus() -> Vec<Gf251> {
+            [7, 0, 9, 12, 1].into_iter().map(Gf251).collect()
+        }
+    }
+
+    // element takes the coefficients from the highest, as Sage prints them.
+    fn element(c: [u64; 5]) -> Element<Gf251To5> {
+        let c: Vec<Gf251> = c.into_iter().rev().map(Gf251).collect();
+        Element::from_coefficients(&c).unwrap()
+    }
+
+    fn eq<E: ExtensionField>(a: &Element<E>, b: &Element<E>) -> bool {
+        Element::ct_equal(a, b) == 1
+    }
+
+    fn random<E: ExtensionField>(rng: &mut StdRng) -> Element<E> {
+        let c: Vec<E::Base> = (0..E::DEGREE).map(|_| conformance::random(rng)).collect();
+        Element::from_coefficients(&c).unwrap()
+    }
+
+    // The field axioms, and that the Frobenius map is a field automorphism of order m that
+    // fixes the base field and multiplies norms.
+    fn check_extension<E: ExtensionField>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        assert!(Element::<E>::vartime_invert(&Element::zero()).is_zero() == 1);
+        for _ in 0..16 {
+            let (a, b, c): (Element<E>, Element<E>, Element<E>) = (random(&mut rng), random(&mut rng), random(&mut rng));
+            assert!(eq(&Element::multiply(&a, &b), &Element::multiply(&b, &a)));
+            assert!(eq(&Element::multiply(&Element::multiply(&a, &b), &c),
+                       &Element::multiply(&a, &Element::multiply(&b, &c))));
+            assert!(eq(&Element::multiply(&a, &Element::add(&b, &c)),
+                       &Element::add(&Element::multiply(&a, &b), &Element::multiply(&a, &c))));
+            assert!(eq(&Element::subtract(&Element::add(&a, &b), &b), &a));
+            assert!(eq(&Element::add(&a, &Element::negate(&a)), &Element::zero()));
+            assert!(eq(&Element::multiply(&a, &Element::one()), &a));
+            if a.is_zero() == 0 {
+                assert!(eq(&Element::multiply(&a, &Element::vartime_invert(&a)), &Element::one()));
+            }
+
+            let frob = Element::frobenius;
+            assert!(eq(&frob(&Element::multiply(&a, &b)), &Element::multiply(&frob(&a), &frob(&b))));
+            assert!(eq(&frob(&Element::add(&a, &b)), &Element::add(&frob(&a), &frob(&b))));
+            let mut conjugate = a.clone();
+            for _ in 0..E::DEGREE {
+                conjugate = frob(&conjugate);
+            }
+            assert!(eq(&conjugate, &a));
+            let base = Element::from_base(&a.coefficients()[0]);
+            assert!(eq(&frob(&base), &base));
+            assert!(E::Base::ct_equal(&Element::norm(&Element::multiply(&a, &b)),
+                                      &E::Base::mul(&Element::norm(&a), &Element::norm(&b))) == 1);
+        }
+    }
+
+    // Example 2.4 of the Guide, as in sagemath/extension-field.sage.
+    #[test]
+    fn test_guide_example() {
+        let a = element([123, 0, 76, 7, 4]);
+        let b = element([196, 12, 225, 0, 76]);
+        assert!(eq(&Element::add(&a, &b), &element([68, 12, 50, 7, 80])));
+        assert!(eq(&Element::subtract(&a, &b), &element([178, 239, 102, 7, 179])));
+        assert!(eq(&Element::multiply(&a, &b), &element([117, 151, 117, 182, 217])));
+        assert!(eq(&Element::vartime_invert(&a), &element([109, 111, 250, 98, 85])));
+
+        // the field has 251^5 elements, so a^(251^5 - 2) is also 1/a
+        let order_minus_2 = (251u64.pow(5) - 2).to_be_bytes();
+        assert!(eq(&Element::pow(&a, &order_minus_2), &Element::vartime_invert(&a)));
+        assert!(eq(&Element::frobenius(&a), &Element::pow(&a, &[251])));
+        assert!(eq(&Element::frobenius(&a), &element([182, 29, 215, 2, 239])));
+        assert_eq!(Element::norm(&a), Gf251(74));
+        // the norm is a^((p^m - 1) / (p - 1))
+        let norm_exponent = ((251u64.pow(5) - 1) / 250).to_be_bytes();
+        assert!(eq(&Element::pow(&a, &norm_exponent), &element([0, 0, 0, 0, 74])));
+
+        // The worksheet's k2 adjoins a root u of the same polynomial to GF(251^5) itself, a
+        // degree-5 extension of order 251^25. Element is the Guide's GF(251)[z] / (f(z)) instead,
+        // so z plays the part of u: of k2's outputs, the characteristic 251 and the identity
+        // modulus() = minpoly() carry over, and the order 251^25 does not.
+        assert_eq!(<Gf251To5 as ExtensionField>::Base::CHARACTERISTIC, &[251]);
+
+        // z is a root of f
+        let z = Element::<Gf251To5>::generator();
+        let mut f = Element::pow(&z, &[5]);
+        for (i, fi) in Gf251To5::modulus().iter().enumerate() {
+            f = Element::add(&f, &Element::multiply(&Element::from_base(fi), &Element::pow(&z, &[i as u8])));
+        }
+        assert!(f.is_zero() == 1);
+
+        // and f is its minimal polynomial, the product of (x - c) over its five conjugates c
+        let mut minpoly = vec![Element::<Gf251To5>::one()];
+        let mut c = z.clone();
+        for _ in 0..5 {
+            let mut next = vec![Element::zero(); minpoly.len() + 1];
+            for (k, m) in minpoly.iter().enumerate() {
+                next[k + 1] = Element::add(&next[k + 1], m);
+                next[k] = Element::subtract(&next[k], &Element::multiply(&c, m));
+            }
+            minpoly = next;
+            c = Element::frobenius(&c);
+        }
+        assert!(eq(&c, &z));
+        let mut modulus: Vec<Element<Gf251To5>> = Gf251To5::modulus().iter().map(Element::from_base).collect();
+        modulus.push(Element::one());
+        assert_eq!(minpoly.len(), modulus.len());
+        for (m, fk) in minpoly.iter().zip(modulus.iter()) {
+            assert!(eq(m, fk));
+        }
+        assert!(Element::<Gf251To5>::from_coefficients(&[Gf251(1)]).is_err());
+    }
+
+    #[test]
+    fn test_gf251_5() {
+        check_extension::<Gf251To5>(251);
+    }
+
+    // In GF(p^2) = GF(p)(sqrt(2)), the Frobenius map is the conjugation x + y*z -> x - y*z and
+    // the norm is x^2 - 2y^2.
+    #[test]
+    fn test_fp25519_sqrt2() {
+        check_extension::<Fp25519Sqrt2>(0x25519);
+        assert_eq!(field::Element::is_square(&field::Element::from_u64(2)), 0);
+
+        let mut rng = StdRng::seed_from_u64(2);
+        let a: Element<Fp25519Sqrt2> = random(&mut rng);
+        let (x, y) = (&a.coefficients()[0], &a.coefficients()[1]);
+        let conjugate = Element::from_coefficients(&[x.clone(), field::Element::negate(y)]).unwrap();
+        assert!(eq(&Element::frobenius(&a), &conjugate));
+        let norm = field::Element::subtract(&field::Element::square(x),
+            &field::Element::multiply(&field::Element::from_u64(2), &field::Element::square(y)));
+        assert!(Element::norm(&a).equal(&norm));
+
+        // z^2 = 2, and 2 now has the square roots z and -z
+        let z = Element::<Fp25519Sqrt2>::generator();
+        assert!(eq(&Element::square(&z), &Element::from_base(&field::Element::from_u64(2))));
+    }
+}
@@ -1,6 +1,7 @@
 #![allow(dead_code)]
 
 use crate::{binary, bits::{self, Uint128}, subtle};
+use crate::prime_field::PrimeField;
 
 // Element represents an element of the field GF(2^255-19).
 // An element is represented as a radix-2^51 value.
@@ -21,6 +22,9 @@ impl Element {
     pub const MASK_LOW_51BITS: u64 = (1 << 51) - 1;
     pub const ZERO: &'static Element = &Element(0, 0, 0, 0, 0);
     pub const ONE: &'static Element = &Element(1, 0, 0, 0, 0);
+    // sqrt(-1) = 2^((p-1)/4) mod p
+    pub const SQRT_M1: &'static Element = &Element(
+        1718705420411056, 234908883556509, 2233514472574048, 2117202627021982, 765476049583133);
 
     // zeroes the given object
     pub fn zero(&mut self) -> &Element {
@@ -319,7 +323,7 @@ impl Element {
         let x2 = Element::square(x);                    // x^2
         let mut t = Element::square(&x2);               // x^4
         t = Element::square(&t);                        // x^8
-        let x9 = Element::multiply(&t, &x);             // x^9
+        let x9 = Element::multiply(&t, x);              // x^9
         let x11 = Element::multiply(&x9, &x2);          // x^11
         t = Element::square(&x11);                      // x^22
         let x2_5_0 = Element::multiply(&t, &x9);        // x^31 = x^(2^5 - 2^0)
@@ -395,15 +399,15 @@ impl Element {
         self.0 += 19 * c;
 
         self.1 += self.0 >> 51;
-        self.0 = self.0 & Element::MASK_LOW_51BITS;
+        self.0 &= Element::MASK_LOW_51BITS;
         self.2 += self.1 >> 51;
-        self.1 = self.1 & Element::MASK_LOW_51BITS;
+        self.1 &= Element::MASK_LOW_51BITS;
         self.3 += self.2 >> 51;
-        self.2 = self.2 & Element::MASK_LOW_51BITS;
+        self.2 &= Element::MASK_LOW_51BITS;
         self.4 += self.3 >> 51;
-        self.3 = self.3 & Element::MASK_LOW_51BITS;
+        self.3 &= Element::MASK_LOW_51BITS;
         // no additional carry
-        self.4 = self.4 & Element::MASK_LOW_51BITS;
+        self.4 &= Element::MASK_LOW_51BITS;
 
         self
     }
@@ -479,6 +483,20 @@ impl Element {
         self.4 = (binary::LittleEndian::u64(l) >> 12) & Element::MASK_LOW_51BITS;
     }
 
+    // from_wide_bytes reduces a 64-octet little-endian integer modulo 2^255-19.
+    // With 2^255 = 19 and 2^256 = 38 (mod p), the value lo + hi*2^256 becomes
+    //     lo' + 19*lo_255 + 38*(hi' + 19*hi_255),
+    // where lo' and hi' are the low 255 bits of each half and lo_255 and hi_255 their top bits.
+    pub fn from_wide_bytes(b: &[u8; 64]) -> Self {
+        let lo = Element::add(
+            &Element::from_le_bytes(b[..32].try_into().unwrap()),
+            &Element(19 * (b[31] >> 7) as u64, 0, 0, 0, 0));
+        let hi = Element::add(
+            &Element::from_le_bytes(b[32..].try_into().unwrap()),
+            &Element(19 * (b[63] >> 7) as u64, 0, 0, 0, 0));
+        Element::add(&lo, &Element::mul32(&hi, 38))
+    }
+
     pub fn from_bytes(b: [u8; 32]) -> Self {
         let mut el = Self::ZERO.clone();
         el.init_from_le_bytes(b);
@@ -528,18 +546,193 @@ impl Element {
         s.4 ^= t;
         u.4 ^= t;
     }
+
+    // returns 1 if v and u are equal, and 0 otherwise, as a condition for select and swap.
+    pub fn ct_equal(&self, u: &Element) -> u32 {
+        self.equal(u) as u32
+    }
+
+    // returns 1 if the canonical encoding of v is odd, and 0 otherwise.
+    // RFC 8032 calls this bit the sign of the x-coordinate.
+    pub fn is_negative(&self) -> u32 {
+        (self.to_le_bytes()[0] & 1) as u32
+    }
+
+    // returns |a|, that is, a if a is non-negative, and -a otherwise.
+    pub fn absolute(a: &Element) -> Element {
+        let mut v = Element::ZERO.clone();
+        v.select(&Element::negate(a), a, a.is_negative());
+        v
+    }
+
+    // calculate x^((p-5)/8) = x^(2^252-3), the exponent used in square root computations.
+    // The addition chain shares its first half with invert.
+    pub fn pow22523(x: &Element) -> Element {
+        let x2 = Element::square(x);                    // x^2
+        let mut t = Element::square(&x2);               // x^4
+        t = Element::square(&t);                        // x^8
+        let x9 = Element::multiply(&t, x);              // x^9
+        let x11 = Element::multiply(&x9, &x2);          // x^11
+        t = Element::square(&x11);                      // x^22
+        let x2_5_0 = Element::multiply(&t, &x9);        // x^(2^5 - 2^0)
+
+        t = Element::square(&x2_5_0);
+        for _ in 0..4 {
+            t = Element::square(&t);                    // x^(2^10 - 2^5)
+        }
+        let x2_10_0 = Element::multiply(&t, &x2_5_0);   // x^(2^10 - 2^0)
+
+        t = Element::square(&x2_10_0);
+        for _ in 0..9 {
+            t = Element::square(&t);                    // x^(2^20 - 2^10)
+        }
+        let x2_20_0 = Element::multiply(&t, &x2_10_0);  // x^(2^20 - 2^0)
+
+        t = Element::square(&x2_20_0);
+        for _ in 0..19 {
+            t = Element::square(&t);                    // x^(2^40 - 2^20)
+        }
+        t = Element::multiply(&t, &x2_20_0);            // x^(2^40 - 2^0)
+
+        t = Element::square(&t);
+        for _ in 0..9 {
+            t = Element::square(&t);                    // x^(2^50 - 2^10)
+        }
+        let x2_50_0 = Element::multiply(&t, &x2_10_0);  // x^(2^50 - 2^0)
+
+        t = Element::square(&x2_50_0);
+        for _ in 0..49 {
+            t = Element::square(&t);                    // x^(2^100 - 2^50)
+        }
+        let x2_100_0 = Element::multiply(&t, &x2_50_0); // x^(2^100 - 2^0)
+
+        t = Element::square(&x2_100_0);
+        for _ in 0..99 {
+            t = Element::square(&t);                    // x^(2^200 - 2^100)
+        }
+        t = Element::multiply(&t, &x2_100_0);           // x^(2^200 - 2^0)
+
+        t = Element::square(&t);
+        for _ in 0..49 {
+            t = Element::square(&t);                    // x^(2^250 - 2^50)
+        }
+        t = Element::multiply(&t, &x2_50_0);            // x^(2^250 - 2^0)
+
+        t = Element::square(&t);                        // x^(2^251 - 2^1)
+        t = Element::square(&t);                        // x^(2^252 - 2^2)
+
+        Element::multiply(&t, x)                        // x^(2^252 - 3)
+    }
+
+    // sqrt_ratio returns the non-negative square root of u/v and 1 if u/v is square.
+    // If u/v is not square, it returns 0 and the square root of i*u/v, where i = sqrt(-1),
+    // provided that one exists. If v is zero, the returned root is zero, and so is
+    // the returned flag unless u is also zero. This is the SQRT_RATIO_M1 function of RFC 9496.
+    pub fn sqrt_ratio(u: &Element, v: &Element) -> (Element, u32) {
+        let v2 = Element::square(v);
+        let uv3 = Element::multiply(u, &Element::multiply(&v2, v));
+        let uv7 = Element::multiply(&uv3, &Element::square(&v2));
+        let mut r = Element::multiply(&uv3, &Element::pow22523(&uv7)); // (u*v^3) * (u*v^7)^((p-5)/8)
+
+        let check = Element::multiply(v, &Element::square(&r));        // v * r^2
+        let u_neg = Element::negate(u);
+        let correct_sign_sqrt = check.ct_equal(u);
+        let flipped_sign_sqrt = check.ct_equal(&u_neg);
+        let flipped_sign_sqrt_i = check.ct_equal(&Element::multiply(&u_neg, Element::SQRT_M1));
+
+        let r_prime = Element::multiply(&r, Element::SQRT_M1);
+        let rr = r.clone();
+        r.select(&r_prime, &rr, flipped_sign_sqrt | flipped_sign_sqrt_i);
+
+        (Element::absolute(&r), correct_sign_sqrt | flipped_sign_sqrt)
+    }
+}
+
+impl PrimeField for Element {
+    const ENCODED_SIZE: usize = 32;
+    const LITTLE_ENDIAN: bool = true;
+    const CHARACTERISTIC: &'static [u8] = &[
+        0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xed];
+
+    fn zero() -> Element {
+        Element::ZERO.clone()
+    }
+
+    fn one() -> Element {
+        Element::ONE.clone()
+    }
+
+    fn from_u64(k: u64) -> Element {
+        Element(k & Element::MASK_LOW_51BITS, k >> 51, 0, 0, 0)
+    }
+
+    fn add(a: &Element, b: &Element) -> Element {
+        Element::add(a, b)
+    }
+
+    fn sub(a: &Element, b: &Element) -> Element {
+        Element::subtract(a, b)
+    }
+
+    fn neg(a: &Element) -> Element {
+        Element::negate(a)
+    }
+
+    fn mul(a: &Element, b: &Element) -> Element {
+        Element::multiply(a, b)
+    }
+
+    fn square(a: &Element) -> Element {
+        Element::square(a)
+    }
+
+    fn invert(a: &Element) -> Element {
+        Element::invert(a)
+    }
+
+    // sqrt_ratio with v = 1 returns the non-negative root.
+    fn sqrt(a: &Element) -> (Element, u32) {
+        Element::sqrt_ratio(a, Element::ONE)
+    }
+
+    fn to_canonical_bytes(&self) -> Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
+
+    // Unlike from_le_bytes, the decoding is strict: the top bit must be clear and the value
+    // below p, which re-encoding the element detects.
+    fn from_canonical_bytes(b: &[u8]) -> Result<Element, ()> {
+        let b: [u8; 32] = b.try_into().map_err(|_| ())?;
+        let a = Element::from_le_bytes(b);
+        if !subtle::constant_time_compare(&a.to_le_bytes(), &b) {
+            return Err(());
+        }
+        Ok(a)
+    }
+
+    fn ct_equal(a: &Element, b: &Element) -> u32 {
+        a.ct_equal(b)
+    }
+
+    fn select(a: &Element, b: &Element, cond: u32) -> Element {
+        let mut v = Element::ZERO.clone();
+        v.select(a, b, cond);
+        v
+    }
 }
 
 #[cfg(test)]
 mod field_test {
     use crate::field;
+    use crate::prime_field::{self, PrimeField};
     use crate::field::Element;
 
     #[test]
     fn test_mask_low_51bits() {
-        assert_eq!(u64::MAX & Element::MASK_LOW_51BITS, 0x7ffffffffffff);
-        assert_eq!(u64::MAX & Element::MASK_LOW_51BITS, Element::MASK_LOW_51BITS);
-        assert_eq!(Element::MASK_LOW_51BITS + 1 & Element::MASK_LOW_51BITS, 0);
+        assert_eq!(Element::MASK_LOW_51BITS, 0x7ffffffffffff);
+        assert_eq!(u64::MAX >> 13, Element::MASK_LOW_51BITS);
+        assert_eq!((Element::MASK_LOW_51BITS + 1) & Element::MASK_LOW_51BITS, 0);
     }
 
     #[test]
@@ -580,7 +773,7 @@ mod field_test {
         let bytes: [u8; 32] = [74, 209, 69, 197, 70, 70, 161, 222, 56, 226, 229, 19, 112, 60, 25, 92, 187, 74, 222, 56, 50, 153, 51, 233, 40, 74, 57, 6, 160, 185, 213, 31];
         let expect = Element(358744748052810, 1691584618240980, 977650209285361, 1429865912637724, 560044844278676);
         let d: &mut Element = &mut Element::ZERO.clone();
-        let _ = d.init_from_le_bytes(bytes);
+        d.init_from_le_bytes(bytes);
         assert_eq!(d.clone(), expect);
     }
 
@@ -589,7 +782,7 @@ mod field_test {
         let bytes: [u8; 32] = [199, 23, 106, 112, 61, 77, 216, 79, 186, 60, 11, 118, 13, 16, 103, 15, 42, 32, 83, 250, 44, 57, 204, 198, 78, 199, 253, 119, 146, 172, 3, 122];
         let expect = Element(84926274344903, 473620666599931, 365590438845504, 1028470286882429, 2146499180330972);
         let d: &mut Element = &mut Element::ZERO.clone();
-        let _ = d.init_from_le_bytes(bytes);
+        d.init_from_le_bytes(bytes);
         assert_eq!(d.clone(), expect);
     }
 
@@ -619,14 +812,14 @@ mod field_test {
 
     #[test]
     fn test_swap_01() {
-        let mut a = Element(358744748052810, 1691584618240980, 977650209285361, 1429865912637724, 560044844278676);
-        let mut b = Element(84926274344903, 473620666599931, 365590438845504, 1028470286882429, 2146499180330972);
+        let a = Element(358744748052810, 1691584618240980, 977650209285361, 1429865912637724, 560044844278676);
+        let b = Element(84926274344903, 473620666599931, 365590438845504, 1028470286882429, 2146499180330972);
 
         let mut c = Element::ZERO.clone();
         let mut d = Element::ZERO.clone();
 
-        c.select(&mut a, &mut b, 1);
-        d.select(&mut a, &mut b, 0);
+        c.select(&a, &b, 1);
+        d.select(&a, &b, 0);
 
         assert!(c.equal(&a) && d.equal(&b));
         Element::swap(&mut c, &mut d, 0);
@@ -739,4 +932,60 @@ mod field_test {
 
         assert_eq!(neg_d, fe_rfc7748_d);
     }
+
+    #[test]
+    fn test_sqrt_ratio() {
+        // sqrt(-1)^2 == -1
+        let i2 = field::Element::square(Element::SQRT_M1);
+        assert!(i2.equal(&Element::negate(Element::ONE)));
+
+        let four = Element(4, 0, 0, 0, 0);
+        let nine = Element(9, 0, 0, 0, 0);
+        // 4/9 is square, and sqrt_ratio returns the non-negative root 2/3.
+        let (r, was_square) = Element::sqrt_ratio(&four, &nine);
+        assert_eq!(was_square, 1);
+        assert_eq!(r.is_negative(), 0);
+        assert!(Element::multiply(&Element::square(&r), &nine).equal(&four));
+
+        // 2 is not a square modulo 2^255 - 19, but 2*i is.
+        let two = Element(2, 0, 0, 0, 0);
+        let (r, was_square) = Element::sqrt_ratio(&two, Element::ONE);
+        assert_eq!(was_square, 0);
+        assert!(Element::square(&r).equal(&Element::multiply(&two, Element::SQRT_M1)));
+
+        // 0/v is the square 0, u/0 is not a square.
+        let (r, was_square) = Element::sqrt_ratio(Element::ZERO, &nine);
+        assert_eq!((was_square, r.equal(Element::ZERO)), (1, true));
+        let (r, was_square) = Element::sqrt_ratio(&four, Element::ZERO);
+        assert_eq!((was_square, r.equal(Element::ZERO)), (0, true));
+    }
+
+    #[test]
+    fn test_from_wide_bytes() {
+        // (2^512 - 1) mod p
+        let v = Element::from_wide_bytes(&[0xff; 64]);
+        assert_eq!(hex::encode(v.to_le_bytes()), "a305000000000000000000000000000000000000000000000000000000000000");
+
+        let b: [u8; 64] = hex::decode("eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee\
+            7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff").unwrap().try_into().unwrap();
+        let v = Element::from_wide_bytes(&b);
+        assert_eq!(hex::encode(v.to_le_bytes()), "7fe1eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee6e");
+    }
+
+    // The 2^255-19 field passes the generic PrimeField conformance suite.
+    #[test]
+    fn test_prime_field() {
+        prime_field::conformance::check_all::<Element>(0x25519);
+
+        // 2^255 - 19 itself and a set top bit are not canonical
+        let mut p = [0xFF; 32];
+        p[0] = 0xED;
+        p[31] = 0x7F;
+        assert!(<Element as PrimeField>::from_canonical_bytes(&p).is_err());
+        p[0] = 0xEC;
+        assert!(<Element as PrimeField>::from_canonical_bytes(&p).is_ok());
+        let mut high = [0; 32];
+        high[31] = 0x80;
+        assert!(<Element as PrimeField>::from_canonical_bytes(&high).is_err());
+    }
 }
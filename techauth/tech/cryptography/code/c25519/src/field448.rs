@@ -0,0 +1,368 @@
+#![allow(dead_code)]
+
+use crate::subtle;
+
+// Element represents an element of the field GF(2^448 - 2^224 - 1), the field of Curve448
+// and Edwards448 (RFC 7748). Hamburg calls this prime "Goldilocks": it is a trinomial
+//     p = phi^2 - phi - 1,  phi = 2^224,
+// so that 2^448 = 2^224 + 1 (mod p), and a carry out of the top bit is folded back in at
+// bit 0 and at bit 224.
+// An element is represented as a radix-2^56 value in eight limbs. An element t represents
+// the integer
+//     t.0[0] + t.0[1]*2^56 + t.0[2]*2^112 + ... + t.0[7]*2^392
+// The limb boundary at 2^224 falls between t.0[3] and t.0[4], so reducing a product needs
+// only additions of whole limbs. Between operations, all limbs are expected to be lower
+// than 2^57. The zero value is a valid zero element.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Element(pub(crate) [u64; 8]);
+
+impl Element {
+    pub const MASK_LOW_56BITS: u64 = (1 << 56) - 1;
+    pub const ZERO: &'static Element = &Element([0; 8]);
+    pub const ONE: &'static Element = &Element([1, 0, 0, 0, 0, 0, 0, 0]);
+
+    // 4p in radix 2^56, added in subtract to keep the limbs positive.
+    const FOUR_P: [u64; 8] = [
+        0x3FFFFFFFFFFFFFC, 0x3FFFFFFFFFFFFFC, 0x3FFFFFFFFFFFFFC, 0x3FFFFFFFFFFFFFC,
+        0x3FFFFFFFFFFFFF8, 0x3FFFFFFFFFFFFFC, 0x3FFFFFFFFFFFFFC, 0x3FFFFFFFFFFFFFC,
+    ];
+
+    // calculate a + b.
+    pub fn add(a: &Element, b: &Element) -> Element {
+        let mut v = Element(core::array::from_fn(|i| a.0[i] + b.0[i]));
+        v.carry_propagate();
+        v
+    }
+
+    // calculate a - b.
+    pub fn subtract(a: &Element, b: &Element) -> Element {
+        let mut v = Element(core::array::from_fn(|i| (a.0[i] + Element::FOUR_P[i]) - b.0[i]));
+        v.carry_propagate();
+        v
+    }
+
+    // calculate -a.
+    pub fn negate(a: &Element) -> Element {
+        Element::subtract(Element::ZERO, a)
+    }
+
+    // calculate x * y for a small constant y, such as a24 of the ladder.
+    pub fn mul32(x: &Element, y: u32) -> Element {
+        let mut c: [u128; 16] = [0; 16];
+        for (ci, xi) in c.iter_mut().zip(x.0.iter()) {
+            *ci = (*xi as u128) * (y as u128);
+        }
+        Element::reduce_wide(c)
+    }
+
+    // calculate a * b.
+    pub fn multiply(a: &Element, b: &Element) -> Element {
+        // Schoolbook multiplication into 15 column sums. With limbs below 2^57, every
+        // product is below 2^114 and every column below 2^117.
+        let mut c: [u128; 16] = [0; 16];
+        for i in 0..8 {
+            for j in 0..8 {
+                c[i + j] += (a.0[i] as u128) * (b.0[j] as u128);
+            }
+        }
+        Element::reduce_wide(c)
+    }
+
+    // calculate a * a.
+    pub fn square(a: &Element) -> Element {
+        let mut c: [u128; 16] = [0; 16];
+        for i in 0..8 {
+            c[2 * i] += (a.0[i] as u128) * (a.0[i] as u128);
+            for j in i + 1..8 {
+                c[i + j] += 2 * (a.0[i] as u128) * (a.0[j] as u128);
+            }
+        }
+        Element::reduce_wide(c)
+    }
+
+    // reduce_wide reduces the column sums of a product, c[0] + c[1]*2^56 + ... + c[15]*2^840.
+    // The column of weight 2^(56*i), i >= 8, is folded with 2^448 = 2^224 + 1 into the columns
+    // i - 8 and i - 4. Going from the top down, the columns 8 to 11 are folded again after they
+    // have received the upper ones.
+    fn reduce_wide(mut c: [u128; 16]) -> Element {
+        for i in (8..16).rev() {
+            c[i - 8] += c[i];
+            c[i - 4] += c[i];
+        }
+
+        // Two carry chains; the carry out of the top limb is folded the same way.
+        for _ in 0..2 {
+            for i in 0..7 {
+                c[i + 1] += c[i] >> 56;
+                c[i] &= Element::MASK_LOW_56BITS as u128;
+            }
+            let top = c[7] >> 56;
+            c[7] &= Element::MASK_LOW_56BITS as u128;
+            c[0] += top;
+            c[4] += top;
+        }
+        Element(core::array::from_fn(|i| c[i] as u64))
+    }
+
+    // carry_propagate brings every limb below 2^56 plus a small carry, with all the carries
+    // computed from the original limbs.
+    pub fn carry_propagate(&mut self) {
+        let c: [u64; 8] = core::array::from_fn(|i| self.0[i] >> 56);
+        for i in 0..8 {
+            self.0[i] &= Element::MASK_LOW_56BITS;
+        }
+        for i in 1..8 {
+            self.0[i] += c[i - 1];
+        }
+        self.0[0] += c[7];
+        self.0[4] += c[7];
+    }
+
+    // reduce value modulo p
+    pub fn reduce(&mut self) -> &Self {
+        // Two strict carry chains leave every limb below 2^56, that is, v < 2^448 < 2p.
+        for _ in 0..2 {
+            for i in 0..7 {
+                self.0[i + 1] += self.0[i] >> 56;
+                self.0[i] &= Element::MASK_LOW_56BITS;
+            }
+            let top = self.0[7] >> 56;
+            self.0[7] &= Element::MASK_LOW_56BITS;
+            self.0[0] += top;
+            self.0[4] += top;
+        }
+
+        // Subtract p and keep the difference unless it borrowed, that is, unless v < p.
+        // In radix 2^56, p has all limbs 2^56 - 1 except for limb 4, which is 2^56 - 2.
+        let mut d: [u64; 8] = [0; 8];
+        let mut borrow: u64 = 0;
+        for (i, di) in d.iter_mut().enumerate() {
+            let p_i = if i == 4 { Element::MASK_LOW_56BITS - 1 } else { Element::MASK_LOW_56BITS };
+            let t = self.0[i].wrapping_sub(p_i).wrapping_sub(borrow);
+            *di = t & Element::MASK_LOW_56BITS;
+            borrow = t >> 63;
+        }
+        let m = borrow.wrapping_sub(1);
+        for (v, di) in self.0.iter_mut().zip(d.iter()) {
+            *v = (m & di) | (!m & *v);
+        }
+        self
+    }
+
+    // to_le_bytes returns the canonical 56-octet little-endian encoding.
+    pub fn to_le_bytes(&self) -> [u8; 56] {
+        let mut el = self.clone();
+        el.reduce();
+        let mut b: [u8; 56] = [0; 56];
+        for i in 0..8 {
+            b[7 * i..7 * i + 7].copy_from_slice(&el.0[i].to_le_bytes()[..7]);
+        }
+        b
+    }
+
+    // from_le_bytes initializes the eight limbs from 56 octets in little-endian encoding.
+    // As RFC 7748 requires for X448, non-canonical values (p through 2^448 - 1) are accepted.
+    pub fn from_le_bytes(b: &[u8; 56]) -> Element {
+        Element(core::array::from_fn(|i| {
+            let mut l: [u8; 8] = [0; 8];
+            l[..7].copy_from_slice(&b[7 * i..7 * i + 7]);
+            u64::from_le_bytes(l)
+        }))
+    }
+
+    // returns true if v and u are equal.
+    pub fn equal(&self, u: &Element) -> bool {
+        subtle::constant_time_compare(&self.to_le_bytes(), &u.to_le_bytes())
+    }
+
+    // returns 1 if v and u are equal, and 0 otherwise, as a condition for select and swap.
+    pub fn ct_equal(&self, u: &Element) -> u32 {
+        self.equal(u) as u32
+    }
+
+    // returns 0xFFFFFFFFFFFFFFFF if cond is 1, and 0 otherwise.
+    fn mask_64bits(cond: u32) -> u64 {
+        (cond as u64).wrapping_neg()
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Element, b: &Element, cond: u32) {
+        let m = Element::mask_64bits(cond);
+        for i in 0..8 {
+            self.0[i] = (m & a.0[i]) | (!m & b.0[i]);
+        }
+    }
+
+    // Swap swaps s and u if cond == 1, and leaves them alone if cond == 0.
+    pub fn swap(s: &mut Element, u: &mut Element, cond: u32) {
+        let m = Element::mask_64bits(cond);
+        for i in 0..8 {
+            let t = m & (s.0[i] ^ u.0[i]);
+            s.0[i] ^= t;
+            u.0[i] ^= t;
+        }
+    }
+
+    // square_times returns x^(2^n).
+    fn square_times(x: &Element, n: usize) -> Element {
+        let mut v = Element::square(x);
+        for _ in 1..n {
+            v = Element::square(&v);
+        }
+        v
+    }
+
+    // calculate x^(2^222 - 1) and x^(2^223 - 1), the common part of the exponents
+    // p - 2 = (2^223 - 1)*2^225 + (2^222 - 1)*2^2 + 1 and (p - 3)/4 = 2^446 - 2^222 - 1.
+    fn pow_2_222_2_223(x: &Element) -> (Element, Element) {
+        let x2 = Element::multiply(&Element::square(x), x);                      // 2^2 - 1
+        let x3 = Element::multiply(&Element::square(&x2), x);                    // 2^3 - 1
+        let x6 = Element::multiply(&Element::square_times(&x3, 3), &x3);         // 2^6 - 1
+        let x12 = Element::multiply(&Element::square_times(&x6, 6), &x6);        // 2^12 - 1
+        let x24 = Element::multiply(&Element::square_times(&x12, 12), &x12);     // 2^24 - 1
+        let x30 = Element::multiply(&Element::square_times(&x24, 6), &x6);       // 2^30 - 1
+        let x48 = Element::multiply(&Element::square_times(&x24, 24), &x24);     // 2^48 - 1
+        let x96 = Element::multiply(&Element::square_times(&x48, 48), &x48);     // 2^96 - 1
+        let x192 = Element::multiply(&Element::square_times(&x96, 96), &x96);    // 2^192 - 1
+        let x222 = Element::multiply(&Element::square_times(&x192, 30), &x30);   // 2^222 - 1
+        let x223 = Element::multiply(&Element::square(&x222), x);                // 2^223 - 1
+        (x222, x223)
+    }
+
+    // calculate x^(p-2), which is 1/x for x != 0, and 0 for x = 0.
+    pub fn invert(x: &Element) -> Element {
+        let (x222, x223) = Element::pow_2_222_2_223(x);
+        let t = Element::multiply(&Element::square_times(&x223, 225), &Element::square_times(&x222, 2));
+        Element::multiply(&t, x)
+    }
+
+    // calculate x^((p-3)/4) = x^(2^446 - 2^222 - 1) = x^((2^223 - 1)*2^223 + 2^222 - 1).
+    pub fn pow_p34(x: &Element) -> Element {
+        let (x222, x223) = Element::pow_2_222_2_223(x);
+        Element::multiply(&Element::square_times(&x223, 223), &x222)
+    }
+
+    // returns 1 if the canonical encoding of v is odd, and 0 otherwise.
+    // RFC 8032 calls this bit the sign of the x-coordinate.
+    pub fn is_negative(&self) -> u32 {
+        (self.to_le_bytes()[0] & 1) as u32
+    }
+
+    // returns |a|, that is, a if a is non-negative, and -a otherwise.
+    pub fn absolute(a: &Element) -> Element {
+        let mut v = Element::ZERO.clone();
+        v.select(&Element::negate(a), a, a.is_negative());
+        v
+    }
+
+    // sqrt_ratio returns (r, 1) where r is the non-negative square root of u/v, if u/v is
+    // a square, and (r, 0) with an unspecified r otherwise. Since p = 3 (mod 4), the candidate
+    // root is (u/v)^((p+1)/4), computed without an inversion as in RFC 8032, Section 5.2.3:
+    //     r = u^3 * v * (u^5 * v^3)^((p-3)/4)
+    // For u = 0 it returns (0, 1); for v = 0 and u != 0 it returns (0, 0).
+    pub fn sqrt_ratio(u: &Element, v: &Element) -> (Element, u32) {
+        let u2 = Element::square(u);
+        let u3v = Element::multiply(&Element::multiply(&u2, u), v);
+        let u5v3 = Element::multiply(&u3v, &Element::multiply(&u2, &Element::square(v)));
+        let r = Element::multiply(&u3v, &Element::pow_p34(&u5v3));
+        let check = Element::multiply(v, &Element::square(&r));
+        (Element::absolute(&r), check.ct_equal(u))
+    }
+}
+
+#[cfg(test)]
+mod field448_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::field448::Element;
+    use crate::test_util::hex_decode;
+
+    fn random(rng: &mut StdRng) -> Element {
+        let mut b: [u8; 56] = [0; 56];
+        rng.fill(&mut b[..]);
+        Element::from_le_bytes(&b)
+    }
+
+    // p, p + 1 and 2^448 - 1 are the non-canonical encodings of 0, 1 and 2^224.
+    #[test]
+    fn test_non_canonical() {
+        let p: [u8; 56] = hex_decode(concat!(
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "feffffffffffffffffffffffffffffffffffffffffffffffffffffff")).try_into().unwrap();
+        assert_eq!(Element::from_le_bytes(&p).to_le_bytes(), [0; 56]);
+        let mut p1: [u8; 56] = [0xFF; 56];
+        p1[..28].fill(0);
+        assert_eq!(Element::from_le_bytes(&p1).to_le_bytes(), Element::ONE.to_le_bytes());
+
+        let mut phi: [u8; 56] = [0; 56];
+        phi[28] = 1;
+        assert_eq!(Element::from_le_bytes(&[0xFF; 56]).to_le_bytes(), phi);
+        assert!(Element::negate(Element::ONE).equal(&Element::from_le_bytes(&{
+            let mut m1 = p;
+            m1[0] = 0xFE;
+            m1
+        })));
+    }
+
+    #[test]
+    fn test_field_laws() {
+        let mut rng = StdRng::seed_from_u64(0x448);
+        for _ in 0..32 {
+            let a = random(&mut rng);
+            let b = random(&mut rng);
+            let c = random(&mut rng);
+            // a * (b + c) = a*b + a*c
+            assert!(Element::multiply(&a, &Element::add(&b, &c))
+                .equal(&Element::add(&Element::multiply(&a, &b), &Element::multiply(&a, &c))));
+            assert!(Element::square(&a).equal(&Element::multiply(&a, &a)));
+            assert!(Element::add(&Element::subtract(&a, &b), &b).equal(&a));
+            assert!(Element::multiply(&a, &Element::invert(&a)).equal(Element::ONE));
+            assert!(Element::mul32(&a, 39081).equal(&Element::multiply(&a, &Element([39081, 0, 0, 0, 0, 0, 0, 0]))));
+        }
+        assert!(Element::invert(Element::ZERO).equal(Element::ZERO));
+    }
+
+    #[test]
+    fn test_sqrt_ratio() {
+        let mut rng = StdRng::seed_from_u64(0x5427);
+        let mut squares = 0;
+        for _ in 0..32 {
+            let u = random(&mut rng);
+            let v = random(&mut rng);
+            let (r, was_square) = Element::sqrt_ratio(&u, &v);
+            if was_square == 1 {
+                squares += 1;
+                assert!(Element::multiply(&v, &Element::square(&r)).equal(&u));
+                assert_eq!(r.is_negative(), 0);
+            }
+            // the ratio of two squares is a square
+            let (_, was_square) = Element::sqrt_ratio(&Element::square(&u), &Element::square(&v));
+            assert_eq!(was_square, 1);
+        }
+        assert!((4..=28).contains(&squares));
+        let (r, was_square) = Element::sqrt_ratio(Element::ZERO, Element::ONE);
+        assert!(r.equal(Element::ZERO) && was_square == 1);
+        assert_eq!(Element::sqrt_ratio(Element::ONE, Element::ZERO).1, 0);
+        // -1 is not a square, since p = 3 (mod 4)
+        assert_eq!(Element::sqrt_ratio(&Element::negate(Element::ONE), Element::ONE).1, 0);
+    }
+
+    #[test]
+    fn test_select_swap() {
+        let mut rng = StdRng::seed_from_u64(0x5E1);
+        let a = random(&mut rng);
+        let b = random(&mut rng);
+        let mut v = Element::ZERO.clone();
+        v.select(&a, &b, 1);
+        assert_eq!(v, a);
+        v.select(&a, &b, 0);
+        assert_eq!(v, b);
+
+        let (mut s, mut u) = (a.clone(), b.clone());
+        Element::swap(&mut s, &mut u, 0);
+        assert_eq!((&s, &u), (&a, &b));
+        Element::swap(&mut s, &mut u, 1);
+        assert_eq!((&s, &u), (&b, &a));
+    }
+}
@@ -0,0 +1,74 @@
+#![allow(dead_code)]
+
+use crate::fp256::{self, Limbs, Modulus, Ring};
+
+// The base field of the NIST curve P-256, GF(p) with p = 2^256 - 2^224 + 2^192 + 2^96 - 1
+// [SP 800-186, 3.2.1.3]. An element x is held in Montgomery form, x*2^256 mod p, as four
+// little-endian 64-bit limbs, fully reduced. Since p = 3 (mod 4), square roots are single
+// exponentiations.
+pub struct BaseField;
+
+impl Ring for BaseField {
+    const MODULUS: &'static Modulus = &Modulus {
+        m: [0xffffffffffffffff, 0x00000000ffffffff, 0x0000000000000000, 0xffffffff00000001],
+        m_prime: 0x0000000000000001,
+        r2: [0x0000000000000003, 0xfffffffbffffffff, 0xfffffffffffffffe, 0x00000004fffffffd],
+    };
+    // 2^256 mod p
+    const ONE: Limbs = [0x0000000000000001, 0xffffffff00000000, 0xffffffffffffffff, 0x00000000fffffffe];
+}
+
+pub type Element = fp256::Element<BaseField>;
+
+#[cfg(test)]
+mod field_p256_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::field_p256::Element;
+    use crate::test_util::hex_decode;
+
+    fn random(rng: &mut StdRng) -> Element {
+        loop {
+            if let Ok(x) = Element::from_be_bytes(&rng.gen()) {
+                return x;
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoding() {
+        let p: [u8; 32] = hex_decode("ffffffff00000001000000000000000000000000ffffffffffffffffffffffff")
+            .try_into().unwrap();
+        assert!(Element::from_be_bytes(&p).is_err());
+        let mut p_minus_1 = p;
+        p_minus_1[31] = 0xFE;
+        let minus_one = Element::from_be_bytes(&p_minus_1).unwrap();
+        assert!(Element::add(&minus_one, Element::ONE).equal(Element::ZERO));
+        assert_eq!(minus_one.to_be_bytes(), p_minus_1);
+        assert_eq!(Element::ONE.to_be_bytes()[31], 1);
+        assert_eq!(Element::from_u64(7).to_be_bytes()[31], 7);
+    }
+
+    #[test]
+    fn test_field_laws() {
+        let mut rng = StdRng::seed_from_u64(0x9256);
+        for _ in 0..32 {
+            let a = random(&mut rng);
+            let b = random(&mut rng);
+            let c = random(&mut rng);
+            assert!(Element::multiply(&a, &Element::add(&b, &c))
+                .equal(&Element::add(&Element::multiply(&a, &b), &Element::multiply(&a, &c))));
+            assert!(Element::add(&Element::subtract(&a, &b), &b).equal(&a));
+            assert!(Element::multiply(&a, &Element::invert(&a)).equal(Element::ONE));
+            assert_eq!(Element::from_be_bytes(&a.to_be_bytes()), Ok(a.clone()));
+
+            let (r, was_square) = Element::sqrt(&Element::square(&a));
+            assert_eq!(was_square, 1);
+            assert!(Element::square(&r).equal(&Element::square(&a)));
+            // -a^2 is not a square, since p = 3 (mod 4)
+            assert_eq!(Element::sqrt(&Element::negate(&Element::square(&a))).1, 0);
+        }
+        assert!(Element::invert(Element::ZERO).equal(Element::ZERO));
+    }
+}
@@ -0,0 +1,55 @@
+#![allow(dead_code)]
+
+use crate::fp256::{self, Limbs, Modulus, Ring};
+
+// The base field of secp256k1, GF(p) with p = 2^256 - 2^32 - 977 [SEC 2, 2.4.1]. Like
+// field_p256::Element, an element is held in Montgomery form, x*2^256 mod p, as four fully
+// reduced little-endian 64-bit limbs, and p = 3 (mod 4).
+pub struct BaseField;
+
+impl Ring for BaseField {
+    const MODULUS: &'static Modulus = &Modulus {
+        m: [0xfffffffefffffc2f, 0xffffffffffffffff, 0xffffffffffffffff, 0xffffffffffffffff],
+        m_prime: 0xd838091dd2253531,
+        r2: [0x000007a2000e90a1, 0x0000000000000001, 0x0000000000000000, 0x0000000000000000],
+    };
+    // 2^256 mod p = 2^32 + 977
+    const ONE: Limbs = [0x00000001000003d1, 0, 0, 0];
+}
+
+pub type Element = fp256::Element<BaseField>;
+
+#[cfg(test)]
+mod field_secp256k1_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::field_secp256k1::Element;
+    use crate::test_util::hex_decode;
+
+    #[test]
+    fn test_field() {
+        let p: [u8; 32] = hex_decode("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+            .try_into().unwrap();
+        assert!(Element::from_be_bytes(&p).is_err());
+        let mut p_minus_1 = p;
+        p_minus_1[31] -= 1;
+        let minus_one = Element::from_be_bytes(&p_minus_1).unwrap();
+        assert!(Element::add(&minus_one, Element::ONE).equal(Element::ZERO));
+        assert_eq!(minus_one.to_be_bytes(), p_minus_1);
+
+        let mut rng = StdRng::seed_from_u64(0x256b1);
+        for _ in 0..32 {
+            let a = Element::from_be_bytes(&rng.gen()).unwrap_or(Element::ONE.clone());
+            let b = Element::from_be_bytes(&rng.gen()).unwrap_or(Element::ONE.clone());
+            assert!(Element::multiply(&Element::subtract(&a, &b), &Element::add(&a, &b))
+                .equal(&Element::subtract(&Element::square(&a), &Element::square(&b))));
+            assert!(Element::multiply(&a, &Element::invert(&a)).equal(Element::ONE));
+
+            let (r, was_square) = Element::sqrt(&Element::square(&a));
+            assert_eq!(was_square, 1);
+            assert!(Element::square(&r).equal(&Element::square(&a)));
+            assert_eq!(Element::sqrt(&Element::negate(&Element::square(&a))).1, 0);
+        }
+    }
+}
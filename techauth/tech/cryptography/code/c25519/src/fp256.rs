@@ -0,0 +1,309 @@
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+
+// Montgomery arithmetic modulo an odd 256-bit modulus m, shared by the prime fields and
+// scalar rings of the 256-bit short-Weierstrass curves.
+//
+// Values are four little-endian 64-bit limbs, always fully reduced to [0, m). A value x is
+// kept in Montgomery form x*R mod m, R = 2^256, so that the product of two values needs
+// a single Montgomery reduction [Montgomery, "Modular multiplication without trial division",
+// 1985]:
+//     mul(a*R, b*R) = a*R * b*R / R = (a*b)*R  (mod m)
+// Every function takes the same time for all inputs of the same modulus.
+
+pub type Limbs = [u64; 4];
+
+// Modulus holds m with the two constants of the reduction.
+pub struct Modulus {
+    pub m: Limbs,
+    // -m^-1 mod 2^64
+    pub m_prime: u64,
+    // R^2 mod m, to enter Montgomery form
+    pub r2: Limbs,
+}
+
+// returns 0xFFFFFFFFFFFFFFFF if cond is 1, and 0 otherwise.
+fn mask(cond: u64) -> u64 {
+    cond.wrapping_neg()
+}
+
+// sub_borrow returns a - b and the borrow out, 0 or 1.
+fn sub_borrow(a: &Limbs, b: &Limbs) -> (Limbs, u64) {
+    let mut d: Limbs = [0; 4];
+    let mut borrow: u64 = 0;
+    for i in 0..4 {
+        let (t, b1) = a[i].overflowing_sub(b[i]);
+        let (t, b2) = t.overflowing_sub(borrow);
+        d[i] = t;
+        borrow = (b1 | b2) as u64;
+    }
+    (d, borrow)
+}
+
+// add_carry returns a + b and the carry out, 0 or 1.
+fn add_carry(a: &Limbs, b: &Limbs) -> (Limbs, u64) {
+    let mut s: Limbs = [0; 4];
+    let mut carry: u64 = 0;
+    for i in 0..4 {
+        let t = a[i] as u128 + b[i] as u128 + carry as u128;
+        s[i] = t as u64;
+        carry = (t >> 64) as u64;
+    }
+    (s, carry)
+}
+
+// select returns a if cond == 1, and b if cond == 0.
+pub fn select(a: &Limbs, b: &Limbs, cond: u32) -> Limbs {
+    let m = mask(cond as u64);
+    core::array::from_fn(|i| (m & a[i]) | (!m & b[i]))
+}
+
+// reduce_once returns x - m if x + carry*2^256 >= m, and x otherwise. The input must be below 2m.
+fn reduce_once(x: &Limbs, carry: u64, md: &Modulus) -> Limbs {
+    let (d, borrow) = sub_borrow(x, &md.m);
+    // keep d if the subtraction did not borrow, or if it did but x overflowed 2^256
+    select(&d, x, (carry | (1 ^ borrow)) as u32)
+}
+
+// is_less_than returns 1 if a < b, and 0 otherwise, in constant time.
+pub fn is_less_than(a: &Limbs, b: &Limbs) -> u32 {
+    sub_borrow(a, b).1 as u32
+}
+
+pub fn is_zero(a: &Limbs) -> u32 {
+    let acc = a[0] | a[1] | a[2] | a[3];
+    (1 ^ ((acc | acc.wrapping_neg()) >> 63)) as u32
+}
+
+pub fn add(a: &Limbs, b: &Limbs, md: &Modulus) -> Limbs {
+    let (s, carry) = add_carry(a, b);
+    reduce_once(&s, carry, md)
+}
+
+pub fn sub(a: &Limbs, b: &Limbs, md: &Modulus) -> Limbs {
+    let (d, borrow) = sub_borrow(a, b);
+    let m = mask(borrow);
+    let correction: Limbs = core::array::from_fn(|i| m & md.m[i]);
+    add_carry(&d, &correction).0
+}
+
+pub fn neg(a: &Limbs, md: &Modulus) -> Limbs {
+    sub(&[0; 4], a, md)
+}
+
+// mul returns a * b / R mod m, with the coarsely integrated operand scanning (CIOS) method
+// of Koc, Acar and Kaliski, "Analyzing and comparing Montgomery multiplication algorithms"
+// (1996): each round adds a * b[i], then a multiple of m that clears the low limb, and
+// shifts by one limb.
+pub fn mul(a: &Limbs, b: &Limbs, md: &Modulus) -> Limbs {
+    let mut t: [u64; 6] = [0; 6];
+    for bi in b {
+        let mut carry: u128 = 0;
+        for j in 0..4 {
+            let s = t[j] as u128 + (a[j] as u128) * (*bi as u128) + carry;
+            t[j] = s as u64;
+            carry = s >> 64;
+        }
+        let s = t[4] as u128 + carry;
+        t[4] = s as u64;
+        t[5] = (s >> 64) as u64;
+
+        let q = t[0].wrapping_mul(md.m_prime);
+        let s = t[0] as u128 + (q as u128) * (md.m[0] as u128);
+        let mut carry: u128 = s >> 64;
+        for j in 1..4 {
+            let s = t[j] as u128 + (q as u128) * (md.m[j] as u128) + carry;
+            t[j - 1] = s as u64;
+            carry = s >> 64;
+        }
+        let s = t[4] as u128 + carry;
+        t[3] = s as u64;
+        t[4] = t[5] + (s >> 64) as u64;
+    }
+    reduce_once(&[t[0], t[1], t[2], t[3]], t[4], md)
+}
+
+// to_montgomery returns a*R mod m for a < m.
+pub fn to_montgomery(a: &Limbs, md: &Modulus) -> Limbs {
+    mul(a, &md.r2, md)
+}
+
+// from_montgomery returns a/R mod m.
+pub fn from_montgomery(a: &Limbs, md: &Modulus) -> Limbs {
+    mul(a, &[1, 0, 0, 0], md)
+}
+
+// pow returns a^e in Montgomery form, given a and one = R mod m in Montgomery form. The
+// exponent is public, usually m - 2 or (m + 1)/4, so it drives the branches.
+pub fn pow(a: &Limbs, e: &Limbs, one: &Limbs, md: &Modulus) -> Limbs {
+    let mut r = *one;
+    for pos in (0..256).rev() {
+        r = mul(&r, &r, md);
+        if (e[pos / 64] >> (pos % 64)) & 1 == 1 {
+            r = mul(&r, a, md);
+        }
+    }
+    r
+}
+
+// from_be_bytes returns the limbs of a 32-octet big-endian integer, as SEC1 encodes them.
+pub fn from_be_bytes(b: &[u8; 32]) -> Limbs {
+    core::array::from_fn(|i| u64::from_be_bytes(b[24 - 8 * i..32 - 8 * i].try_into().unwrap()))
+}
+
+pub fn to_be_bytes(a: &Limbs) -> [u8; 32] {
+    let mut b: [u8; 32] = [0; 32];
+    for i in 0..4 {
+        b[24 - 8 * i..32 - 8 * i].copy_from_slice(&a[i].to_be_bytes());
+    }
+    b
+}
+
+// shift_right returns a >> k for 0 < k < 64.
+fn shift_right(a: &Limbs, k: u32) -> Limbs {
+    core::array::from_fn(|i| (a[i] >> k) | if i < 3 { a[i + 1] << (64 - k) } else { 0 })
+}
+
+// Ring is the choice of modulus of an Element: the base field or the scalar ring of a curve.
+// Every modulus is a prime above 2^255.
+pub trait Ring: 'static {
+    const MODULUS: &'static Modulus;
+    // R mod m, the Montgomery form of 1
+    const ONE: Limbs;
+}
+
+// Element is an integer modulo R::MODULUS.m, held in Montgomery form and fully reduced, so that
+// the representation is unique. The zero value is a valid zero element. The field and scalar
+// modules of each curve name its instances.
+pub struct Element<R: Ring>(pub(crate) Limbs, PhantomData<R>);
+
+// Clone, Debug and Eq are not derived, as that would require them of R.
+impl<R: Ring> Clone for Element<R> {
+    fn clone(&self) -> Element<R> {
+        Element::new(self.0)
+    }
+}
+
+impl<R: Ring> core::fmt::Debug for Element<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Element({:x?})", self.0)
+    }
+}
+
+impl<R: Ring> PartialEq for Element<R> {
+    fn eq(&self, other: &Element<R>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<R: Ring> Eq for Element<R> {}
+
+impl<R: Ring> Element<R> {
+    pub const ZERO: &'static Element<R> = &Element::new([0; 4]);
+    pub const ONE: &'static Element<R> = &Element::new(R::ONE);
+
+    // new wraps limbs that are already in Montgomery form, such as the constants of a curve.
+    pub(crate) const fn new(limbs: Limbs) -> Element<R> {
+        Element(limbs, PhantomData)
+    }
+
+    pub fn add(a: &Element<R>, b: &Element<R>) -> Element<R> {
+        Element::new(add(&a.0, &b.0, R::MODULUS))
+    }
+
+    pub fn subtract(a: &Element<R>, b: &Element<R>) -> Element<R> {
+        Element::new(sub(&a.0, &b.0, R::MODULUS))
+    }
+
+    pub fn negate(a: &Element<R>) -> Element<R> {
+        Element::new(neg(&a.0, R::MODULUS))
+    }
+
+    pub fn multiply(a: &Element<R>, b: &Element<R>) -> Element<R> {
+        Element::new(mul(&a.0, &b.0, R::MODULUS))
+    }
+
+    pub fn square(a: &Element<R>) -> Element<R> {
+        Element::new(mul(&a.0, &a.0, R::MODULUS))
+    }
+
+    // mul_small returns a * k for a small constant k, such as the 3 of a = -3.
+    pub fn mul_small(a: &Element<R>, k: u64) -> Element<R> {
+        Element::multiply(a, &Element::from_u64(k))
+    }
+
+    // calculate x^(m-2), which is 1/x for x != 0, and 0 for x = 0.
+    pub fn invert(x: &Element<R>) -> Element<R> {
+        let m_minus_2 = sub_borrow(&R::MODULUS.m, &[2, 0, 0, 0]).0;
+        Element::new(pow(&x.0, &m_minus_2, &R::ONE, R::MODULUS))
+    }
+
+    // sqrt returns (r, 1) with r^2 = x if x is a square, and (r, 0) with an unspecified r otherwise.
+    // It computes r = x^((m+1)/4), which is a square root only for m = 3 (mod 4), as for the
+    // base fields; the scalar rings have m = 1 (mod 4).
+    pub fn sqrt(x: &Element<R>) -> (Element<R>, u32) {
+        let m_plus_1_div_4 = add_carry(&shift_right(&R::MODULUS.m, 2), &[1, 0, 0, 0]).0;
+        let r = Element::new(pow(&x.0, &m_plus_1_div_4, &R::ONE, R::MODULUS));
+        let was_square = Element::square(&r).ct_equal(x);
+        (r, was_square)
+    }
+
+    pub fn from_u64(k: u64) -> Element<R> {
+        Element::new(to_montgomery(&[k, 0, 0, 0], R::MODULUS))
+    }
+
+    // from_be_bytes decodes a 32-octet big-endian integer, and fails unless it is below m,
+    // as SEC1 requires of field elements and of the scalars of signatures [SEC1, 2.3.6].
+    pub fn from_be_bytes(b: &[u8; 32]) -> Result<Element<R>, ()> {
+        let a = from_be_bytes(b);
+        if is_less_than(&a, &R::MODULUS.m) == 0 {
+            return Err(());
+        }
+        Ok(Element::new(to_montgomery(&a, R::MODULUS)))
+    }
+
+    // from_be_bytes_mod_order reduces a 32-octet big-endian integer modulo m. The integer is
+    // below 2^256 < 2m, so one conditional subtraction is enough: sub(a, m) is a - m if
+    // a >= m, and a - m + m otherwise.
+    pub fn from_be_bytes_mod_order(b: &[u8; 32]) -> Element<R> {
+        let md = R::MODULUS;
+        let a = sub(&from_be_bytes(b), &md.m, md);
+        Element::new(to_montgomery(&a, md))
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        to_be_bytes(&from_montgomery(&self.0, R::MODULUS))
+    }
+
+    // The representation is unique, so equality compares the limbs.
+    pub fn ct_equal(&self, u: &Element<R>) -> u32 {
+        let d: Limbs = core::array::from_fn(|i| self.0[i] ^ u.0[i]);
+        is_zero(&d)
+    }
+
+    pub fn equal(&self, u: &Element<R>) -> bool {
+        self.ct_equal(u) == 1
+    }
+
+    pub fn is_zero(&self) -> u32 {
+        is_zero(&self.0)
+    }
+
+    // returns 1 if the integer x in [0, m) is odd, the bit that SEC1 compresses y to.
+    pub fn is_odd(&self) -> u32 {
+        (from_montgomery(&self.0, R::MODULUS)[0] & 1) as u32
+    }
+
+    // is_high returns 1 if the integer x in [0, m) is above (m - 1)/2, the largest "low" s
+    // of ECDSA.
+    pub fn is_high(&self) -> u32 {
+        let x = from_montgomery(&self.0, R::MODULUS);
+        is_less_than(&shift_right(&R::MODULUS.m, 1), &x)
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Element<R>, b: &Element<R>, cond: u32) {
+        self.0 = select(&a.0, &b.0, cond);
+    }
+}
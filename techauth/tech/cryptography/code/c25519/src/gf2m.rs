@@ -0,0 +1,373 @@
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+
+// Binary fields GF(2^m) in a polynomial basis [Guide to Elliptic Curve Cryptography, 2.3;
+// see sagemath/binary-field.sage]. An element is a binary polynomial of degree below m,
+// stored as an integer whose bit i is the coefficient of z^i, so that the integer 13 is
+// z^3 + z^2 + 1, as in Sage's from_integer. Arithmetic is modulo an irreducible
+// f(z) = z^m + r(z), and is constant time: it branches only on m and r, never on elements.
+//
+// Elements fit in a u128, so m is at most 128.
+
+// BinaryField is the choice of m and f(z) = z^m + REDUCTION.
+pub trait BinaryField {
+    const M: u32;
+    // r(z) = f(z) - z^m, of degree below m
+    const REDUCTION: u128;
+}
+
+// GF(2^4) with f(z) = z^4 + z + 1, Example 2.2 of the Guide.
+#[derive(Clone, Debug)]
+pub struct Gf16;
+
+impl BinaryField for Gf16 {
+    const M: u32 = 4;
+    const REDUCTION: u128 = 0b11;
+}
+
+// GF(2^8) with the AES polynomial f(z) = z^8 + z^4 + z^3 + z + 1 [FIPS 197, 4.2].
+#[derive(Clone, Debug)]
+pub struct Gf256;
+
+impl BinaryField for Gf256 {
+    const M: u32 = 8;
+    const REDUCTION: u128 = 0x1b;
+}
+
+// GF(2^128) with the GHASH polynomial f(z) = z^128 + z^7 + z^2 + z + 1 [NIST SP 800-38D, 6.3].
+#[derive(Clone, Debug)]
+pub struct Gf2_128;
+
+impl BinaryField for Gf2_128 {
+    const M: u32 = 128;
+    const REDUCTION: u128 = 0x87;
+}
+
+pub struct Element<F: BinaryField>(u128, PhantomData<F>);
+
+// Clone, Debug and Eq are not derived, as that would require them of F.
+impl<F: BinaryField> Clone for Element<F> {
+    fn clone(&self) -> Element<F> {
+        Element::new(self.0)
+    }
+}
+
+impl<F: BinaryField> core::fmt::Debug for Element<F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Element({:#x})", self.0)
+    }
+}
+
+impl<F: BinaryField> PartialEq for Element<F> {
+    fn eq(&self, other: &Element<F>) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<F: BinaryField> Eq for Element<F> {}
+
+// clmul returns the carry-less product of a and b as (high, low) halves.
+fn clmul(a: u128, b: u128) -> (u128, u128) {
+    let (mut hi, mut lo) = (0u128, 0u128);
+    for i in 0..128 {
+        let mask = 0u128.wrapping_sub((b >> i) & 1);
+        lo ^= (a << i) & mask;
+        if i > 0 {
+            hi ^= (a >> (128 - i)) & mask;
+        }
+    }
+    (hi, lo)
+}
+
+// spread inserts a zero after each of the low 64 bits of a, which squares a polynomial.
+fn spread(a: u64) -> u128 {
+    let mut r = 0u128;
+    for i in 0..64 {
+        r |= (((a >> i) & 1) as u128) << (2 * i);
+    }
+    r
+}
+
+impl<F: BinaryField> Element<F> {
+    fn new(a: u128) -> Element<F> {
+        Element(a, PhantomData)
+    }
+
+    // the mask of the m coefficients
+    const MASK: u128 = if F::M == 128 { u128::MAX } else { (1 << F::M) - 1 };
+
+    pub fn zero() -> Element<F> {
+        Element::new(0)
+    }
+
+    pub fn one() -> Element<F> {
+        Element::new(1)
+    }
+
+    // from_integer returns the polynomial whose coefficients are the bits of a, or an error if
+    // its degree is m or more.
+    pub fn from_integer(a: u128) -> Result<Element<F>, ()> {
+        if a & !Element::<F>::MASK != 0 {
+            return Err(());
+        }
+        Ok(Element::new(a))
+    }
+
+    pub fn to_integer(&self) -> u128 {
+        self.0
+    }
+
+    // to_be_bytes returns the integer as ceil(m/8) big-endian octets.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        let size = F::M.div_ceil(8) as usize;
+        self.0.to_be_bytes()[16 - size..].to_vec()
+    }
+
+    // reduce returns hi*z^128 + lo modulo f, for a product of two elements, of degree at most
+    // 2m - 2. It clears the coefficients from the top down, adding r(z)*z^(i-m) for each z^i.
+    fn reduce(hi: u128, lo: u128) -> Element<F> {
+        let (mut hi, mut lo) = (hi, lo);
+        for i in (F::M..2 * F::M - 1).rev() {
+            let bit = if i >= 128 { (hi >> (i - 128)) & 1 } else { (lo >> i) & 1 };
+            let mask = 0u128.wrapping_sub(bit);
+            if i >= 128 {
+                hi &= !(1 << (i - 128));
+            } else {
+                lo &= !(1 << i);
+            }
+            let s = i - F::M;
+            lo ^= (F::REDUCTION << s) & mask;
+            if s > 0 {
+                hi ^= (F::REDUCTION >> (128 - s)) & mask;
+            }
+        }
+        Element::new(lo)
+    }
+
+    pub fn add(a: &Element<F>, b: &Element<F>) -> Element<F> {
+        Element::new(a.0 ^ b.0)
+    }
+
+    pub fn multiply(a: &Element<F>, b: &Element<F>) -> Element<F> {
+        let (hi, lo) = clmul(a.0, b.0);
+        Element::reduce(hi, lo)
+    }
+
+    // square is linear in characteristic two: (sum a_i z^i)^2 = sum a_i z^(2i).
+    pub fn square(a: &Element<F>) -> Element<F> {
+        Element::reduce(spread((a.0 >> 64) as u64), spread(a.0 as u64))
+    }
+
+    // square_n returns a^(2^n).
+    fn square_n(a: &Element<F>, n: u32) -> Element<F> {
+        let mut r = a.clone();
+        for _ in 0..n {
+            r = Element::square(&r);
+        }
+        r
+    }
+
+    // invert returns a^(2^m - 2), which is 1/a for a != 0 and 0 for a = 0, with the
+    // Itoh-Tsujii addition chain: with b_k = a^(2^k - 1), b_(j+k) = b_j^(2^k) * b_k, so
+    // b_(m-1) follows the bits of m - 1 with one multiplication per doubling or increment,
+    // and 1/a = b_(m-1)^2.
+    pub fn invert(a: &Element<F>) -> Element<F> {
+        let e = F::M - 1;
+        let mut b = a.clone();
+        let mut k = 1;
+        for i in (0..31 - e.leading_zeros()).rev() {
+            b = Element::multiply(&Element::square_n(&b, k), &b);
+            k *= 2;
+            if (e >> i) & 1 == 1 {
+                b = Element::multiply(&Element::square(&b), a);
+                k += 1;
+            }
+        }
+        Element::square(&b)
+    }
+
+    // trace returns Tr(a) = a + a^2 + a^4 + ... + a^(2^(m-1)), which is 0 or 1.
+    pub fn trace(a: &Element<F>) -> u32 {
+        let mut t = a.clone();
+        let mut s = a.clone();
+        for _ in 1..F::M {
+            s = Element::square(&s);
+            t = Element::add(&t, &s);
+        }
+        t.0 as u32
+    }
+
+    // half_trace returns H(a) = sum of a^(2^(2i)) for i = 0, ..., (m-1)/2. For odd m and
+    // Tr(a) = 0, z = H(a) solves z^2 + z = a, which decompresses points of binary curves
+    // [Guide, 3.85]. For even m there is no half-trace, and it returns an error.
+    pub fn half_trace(a: &Element<F>) -> Result<Element<F>, ()> {
+        if F::M % 2 == 0 {
+            return Err(());
+        }
+        let mut h = a.clone();
+        let mut s = a.clone();
+        for _ in 0..(F::M - 1) / 2 {
+            s = Element::square_n(&s, 2);
+            h = Element::add(&h, &s);
+        }
+        Ok(h)
+    }
+
+    // returns 1 if a and b are equal, and 0 otherwise.
+    pub fn ct_equal(a: &Element<F>, b: &Element<F>) -> u32 {
+        let d = a.0 ^ b.0;
+        (((d | d.wrapping_neg()) >> 127) ^ 1) as u32
+    }
+
+    pub fn is_zero(&self) -> u32 {
+        Element::ct_equal(self, &Element::zero())
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Element<F>, b: &Element<F>, cond: u32) {
+        let mask = 0u128.wrapping_sub(cond as u128);
+        self.0 = (a.0 & mask) | (b.0 & !mask);
+    }
+}
+
+impl Element<Gf2_128> {
+    // GHASH writes the coefficient of z^0 in the most significant bit of the first octet of a
+    // block, the reverse of the integer order [SP 800-38D, 6.3].
+    pub fn from_ghash_block(b: &[u8; 16]) -> Element<Gf2_128> {
+        Element::new(u128::from_be_bytes(*b).reverse_bits())
+    }
+
+    pub fn to_ghash_block(&self) -> [u8; 16] {
+        self.0.reverse_bits().to_be_bytes()
+    }
+}
+
+#[cfg(test)]
+mod gf2m_test {
+    use crate::gf2m::{BinaryField, Element, Gf16, Gf256, Gf2_128};
+    use crate::test_util::hex_decode;
+
+    // GF(2^7) with f(z) = z^7 + z + 1, for the half-trace, which needs an odd m.
+    #[derive(Clone, Debug)]
+    struct Gf128;
+
+    impl BinaryField for Gf128 {
+        const M: u32 = 7;
+        const REDUCTION: u128 = 0b11;
+    }
+
+    // slow_multiply is shift-and-add with a reduction after every shift.
+    fn slow_multiply<F: BinaryField>(a: u128, b: u128) -> u128 {
+        let (mut a, mut r) = (a, 0);
+        for i in 0..F::M {
+            if (b >> i) & 1 == 1 {
+                r ^= a;
+            }
+            let carry = (a >> (F::M - 1)) & 1 == 1;
+            a = if F::M == 128 { a << 1 } else { (a << 1) & ((1 << F::M) - 1) };
+            if carry {
+                a ^= F::REDUCTION;
+            }
+        }
+        r
+    }
+
+    fn element<F: BinaryField>(a: u128) -> Element<F> {
+        Element::from_integer(a).unwrap()
+    }
+
+    // Example 2.2 of the Guide, as worked in sagemath/binary-field.sage.
+    #[test]
+    fn test_sage_example() {
+        let a: Element<Gf16> = element(13);
+        let b: Element<Gf16> = element(7);
+        // (z^3 + z^2 + 1) + (z^2 + z + 1) = z^3 + z
+        assert_eq!(Element::add(&a, &b).to_integer(), 0b1010);
+        // (z^3 + z^2 + 1) * (z^2 + z + 1) = z^2 + 1
+        assert_eq!(Element::multiply(&a, &b).to_integer(), 0b0101);
+        // 1 / (z^3 + z^2 + 1) = z^2
+        assert_eq!(Element::invert(&a).to_integer(), 0b0100);
+        assert!(Element::<Gf16>::from_integer(16).is_err());
+    }
+
+    // Every product and inverse of GF(2^4) and GF(2^8), against the slow reference.
+    #[test]
+    fn test_exhaustive() {
+        fn _test_field_<F: BinaryField>() {
+            let size = 1u128 << F::M;
+            for a in 0..size {
+                let ea: Element<F> = element(a);
+                assert_eq!(Element::square(&ea).to_integer(), slow_multiply::<F>(a, a));
+                for b in 0..size {
+                    assert_eq!(Element::multiply(&ea, &element(b)).to_integer(), slow_multiply::<F>(a, b));
+                }
+                let inv = Element::invert(&ea);
+                if a == 0 {
+                    assert_eq!(inv.is_zero(), 1);
+                } else {
+                    assert_eq!(Element::multiply(&ea, &inv), Element::one());
+                }
+            }
+        }
+        _test_field_::<Gf16>();
+        _test_field_::<Gf256>();
+        _test_field_::<Gf128>();
+    }
+
+    // {57} * {83} = {c1} [FIPS 197, 4.2], and {53} and {ca} are inverses [FIPS 197, 5.1.1].
+    #[test]
+    fn test_aes() {
+        let a: Element<Gf256> = element(0x57);
+        assert_eq!(Element::multiply(&a, &element(0x83)).to_be_bytes(), vec![0xc1]);
+        assert_eq!(Element::invert(&element::<Gf256>(0x53)).to_integer(), 0xca);
+    }
+
+    // GHASH of one ciphertext block, test case 2 of the GCM specification:
+    //     X1 = C * H, X2 = (X1 + len(A) || len(C)) * H.
+    #[test]
+    fn test_ghash() {
+        let block = |s: &str| Element::from_ghash_block(&hex_decode(s).try_into().unwrap());
+        let h = block("66e94bd4ef8a2c3b884cfa59ca342b2e");
+        let x1 = Element::multiply(&block("0388dace60b6a392f328c2b971b2fe78"), &h);
+        let x2 = Element::multiply(&Element::add(&x1, &block("00000000000000000000000000000080")), &h);
+        assert_eq!(x2.to_ghash_block().to_vec(), hex_decode("f38cbb1ad69223dcc3457ae5b6b0f885"));
+
+        assert_eq!(Element::multiply(&h, &Element::invert(&h)), Element::one());
+        assert_eq!(Element::square(&h), Element::multiply(&h, &h));
+        assert_eq!(Element::<Gf2_128>::from_integer(u128::MAX).unwrap().to_be_bytes(), vec![0xff; 16]);
+    }
+
+    #[test]
+    fn test_trace() {
+        // the trace is linear, and is 1 on exactly half the field
+        let traces: Vec<u32> = (0..16).map(|a| Element::trace(&element::<Gf16>(a))).collect();
+        assert_eq!(traces, [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 1, 1, 1, 1]);
+        assert_eq!(Element::trace(&Element::<Gf256>::one()), 0);
+        assert_eq!(Element::trace(&Element::<Gf2_128>::one()), 0);
+        assert!(Element::half_trace(&Element::<Gf256>::one()).is_err());
+
+        // z^2 + z = c has a solution H(c) exactly when Tr(c) = 0
+        let mut solvable = 0;
+        for c in 0..128 {
+            let c: Element<Gf128> = element(c);
+            let h = Element::half_trace(&c).unwrap();
+            let solved = Element::add(&Element::square(&h), &h) == c;
+            assert_eq!(solved, Element::trace(&c) == 0);
+            solvable += solved as u32;
+        }
+        assert_eq!(solvable, 64);
+    }
+
+    #[test]
+    fn test_select() {
+        let (a, b): (Element<Gf256>, Element<Gf256>) = (element(0x12), element(0x34));
+        let mut v = Element::zero();
+        v.select(&a, &b, 1);
+        assert_eq!(v, a);
+        v.select(&a, &b, 0);
+        assert_eq!(v, b);
+        assert_eq!(Element::ct_equal(&a, &a.clone()), 1);
+        assert_eq!(Element::ct_equal(&a, &b), 0);
+    }
+}
@@ -0,0 +1,409 @@
+#![allow(dead_code)]
+
+use sha2::{Digest, Sha512};
+
+use crate::edwards::Point;
+use crate::field::Element;
+
+// Hashing to curve25519 and edwards25519 as specified in RFC 9380, with the four suites
+//     curve25519_XMD:SHA-512_ELL2_RO_     edwards25519_XMD:SHA-512_ELL2_RO_
+//     curve25519_XMD:SHA-512_ELL2_NU_     edwards25519_XMD:SHA-512_ELL2_NU_
+// The message is hashed to one (NU, nonuniform) or two (RO, random oracle) field elements,
+// every field element is mapped to the Montgomery curve with Elligator 2 and from there to
+// edwards25519, and the cofactor is cleared. Only the random oracle suites are
+// indistinguishable from a random oracle; the nonuniform ones reach about half of the points.
+//
+// The computation of the point runs in constant time, so the message may be secret.
+// The domain separation tag dst is a public parameter chosen by the application.
+
+// sqrt(-486664) with sgn0 = 0, the c1 constant of the rational map to edwards25519 [RFC 9380, D.1]
+const SQRT_MINUS_A_MINUS_2: &Element = &Element(
+    1693982333959686, 608509411481997, 2235573344831311, 947681270984193, 266558006233600);
+
+// the A coefficient of curve25519, v^2 = u^3 + A*u^2 + u
+pub(crate) const A: u32 = 486662;
+
+// SHA-512 input block size (r_in_bytes) and output size (b_in_bytes)
+const R_IN_BYTES: usize = 128;
+const B_IN_BYTES: usize = 64;
+
+// expand_message_xmd expands msg to len_in_bytes uniformly random octets [RFC 9380, 5.3.1].
+// A dst longer than 255 octets is first hashed as described in Section 5.3.3.
+// It fails on an empty dst and when len_in_bytes is longer than 255 SHA-512 outputs.
+pub fn expand_message_xmd(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Result<Vec<u8>, ()> {
+    let ell = len_in_bytes.div_ceil(B_IN_BYTES);
+    if dst.is_empty() || ell > 255 || len_in_bytes > 65535 {
+        return Err(());
+    }
+    let oversize: [u8; 64];
+    let dst = if dst.len() > 255 {
+        oversize = Sha512::new_with_prefix(b"H2C-OVERSIZE-DST-").chain_update(dst).finalize().into();
+        &oversize[..]
+    } else {
+        dst
+    };
+
+    // DST_prime = DST || I2OSP(len(DST), 1)
+    let dst_len = [dst.len() as u8];
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let b0: [u8; 64] = Sha512::new()
+        .chain_update([0u8; R_IN_BYTES])
+        .chain_update(msg)
+        .chain_update((len_in_bytes as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(dst)
+        .chain_update(dst_len)
+        .finalize().into();
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime), and
+    // b_i = H(strxor(b_0, b_(i-1)) || I2OSP(i, 1) || DST_prime)
+    let mut uniform_bytes = Vec::with_capacity(ell * B_IN_BYTES);
+    let mut bi = [0u8; 64];
+    for i in 1..=ell {
+        let mut t = b0;
+        for (t, b) in t.iter_mut().zip(bi.iter()) {
+            *t ^= b;
+        }
+        bi = Sha512::new()
+            .chain_update(t)
+            .chain_update([i as u8])
+            .chain_update(dst)
+            .chain_update(dst_len)
+            .finalize().into();
+        uniform_bytes.extend_from_slice(&bi);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    Ok(uniform_bytes)
+}
+
+// hash_to_field hashes msg to count elements of GF(2^255-19) [RFC 9380, 5.2].
+// Each element is reduced from L = 48 octets, that is, ceil((255 + 128) / 8), which keeps
+// the bias of the reduction below 2^-128.
+pub fn hash_to_field(msg: &[u8], dst: &[u8], count: usize) -> Result<Vec<Element>, ()> {
+    const L: usize = 48;
+    let uniform_bytes = expand_message_xmd(msg, dst, count * L)?;
+    Ok(uniform_bytes.chunks(L).map(|tv| {
+        // OS2IP reads big-endian octets, from_wide_bytes little-endian ones.
+        let mut b = [0u8; 64];
+        for (d, s) in b.iter_mut().zip(tv.iter().rev()) {
+            *d = *s;
+        }
+        Element::from_wide_bytes(&b)
+    }).collect())
+}
+
+// elligator2 maps a field element to a point (u, v) of curve25519 [RFC 9380, 6.7.1], with Z = 2.
+// Let u1 = -A / (1 + 2*r^2) and u2 = -u1 - A. Because 2 is not a square, exactly one of
+// g(u1) and g(u2) = 2*r^2 * g(u1) is a square, and the map returns the corresponding point,
+// with sgn0(v) = 1 for u1 and sgn0(v) = 0 for u2.
+pub(crate) fn elligator2(r: &Element) -> (Element, Element) {
+    let a = &Element(A as u64, 0, 0, 0, 0);
+    let tv1 = Element::mul32(&Element::square(r), 2);
+    // -1/2 is not a square, so 1 + 2*r^2 is never zero and the exceptional case of the RFC,
+    // where inv0 returns zero, does not occur.
+    let u1 = Element::negate(&Element::multiply(a, &Element::invert(&Element::add(Element::ONE, &tv1))));
+    let gu1 = Element::multiply(&u1, &Element::add(&Element::multiply(&u1, &Element::add(&u1, a)), Element::ONE));
+    let u2 = Element::negate(&Element::add(&u1, a));
+    let gu2 = Element::multiply(&tv1, &gu1);
+
+    // sqrt_ratio returns the root with sgn0 = 0.
+    let (v1, was_square) = Element::sqrt_ratio(&gu1, Element::ONE);
+    let (v2, _) = Element::sqrt_ratio(&gu2, Element::ONE);
+    let mut u = Element::ZERO.clone();
+    let mut v = Element::ZERO.clone();
+    u.select(&u1, &u2, was_square);
+    v.select(&Element::negate(&v1), &v2, was_square);
+    (u, v)
+}
+
+// montgomery_to_edwards applies the rational map of RFC 7748, Section 4.1, as given in
+// RFC 9380, Appendix D.1:
+//     x = sqrt(-486664) * u / v,  y = (u - 1) / (u + 1)
+// The exceptional points v = 0 and u = -1 map to the identity.
+fn montgomery_to_edwards(u: &Element, v: &Element) -> Point {
+    let xn = Element::multiply(SQRT_MINUS_A_MINUS_2, u);
+    let xd = v;
+    let yn = Element::subtract(u, Element::ONE);
+    let yd = Element::add(u, Element::ONE);
+
+    let p = Point {
+        x: Element::multiply(&xn, &yd),
+        y: Element::multiply(xd, &yn),
+        z: Element::multiply(xd, &yd),
+        t: Element::multiply(&xn, &yn),
+    };
+    let mut q = Point::identity();
+    q.select(&Point::identity(), &p, p.z.ct_equal(Element::ZERO));
+    q
+}
+
+// edwards_to_montgomery is the inverse of montgomery_to_edwards,
+//     u = (1 + y) / (1 - y),  v = sqrt(-486664) * u / x
+// computed with a single inversion. It fails only on the identity, which has no affine
+// counterpart on curve25519. The suites reach the identity with negligible probability.
+fn edwards_to_montgomery(p: &Point) -> Result<(Element, Element), ()> {
+    let z_plus_y = Element::add(&p.z, &p.y);
+    let inv = Element::invert(&Element::multiply(&Element::subtract(&p.z, &p.y), &p.x));
+    let u = Element::multiply(&Element::multiply(&z_plus_y, &p.x), &inv);
+    let v = Element::multiply(&Element::multiply(SQRT_MINUS_A_MINUS_2, &Element::multiply(&z_plus_y, &p.z)), &inv);
+    if p.is_identity() {
+        return Err(());
+    }
+    Ok((u, v))
+}
+
+// map_to_curve is map_to_curve_elligator2_edwards25519 [RFC 9380, 6.8.2].
+// The curve25519 suites use it too: their group law is carried out on edwards25519, whose
+// complete formulas need no special cases. The rational map is an isomorphism, so the result
+// is the same.
+fn map_to_curve(r: &Element) -> Point {
+    let (u, v) = elligator2(r);
+    montgomery_to_edwards(&u, &v)
+}
+
+// hash_to_curve_edwards25519 implements edwards25519_XMD:SHA-512_ELL2_RO_ [RFC 9380, 8.5].
+pub fn hash_to_curve_edwards25519(msg: &[u8], dst: &[u8]) -> Result<Point, ()> {
+    let r = hash_to_field(msg, dst, 2)?;
+    let q0 = map_to_curve(&r[0]);
+    let q1 = map_to_curve(&r[1]);
+    Ok(Point::mul_by_cofactor(&Point::add(&q0, &q1)))
+}
+
+// encode_to_curve_edwards25519 implements edwards25519_XMD:SHA-512_ELL2_NU_ [RFC 9380, 8.5].
+pub fn encode_to_curve_edwards25519(msg: &[u8], dst: &[u8]) -> Result<Point, ()> {
+    let r = hash_to_field(msg, dst, 1)?;
+    Ok(Point::mul_by_cofactor(&map_to_curve(&r[0])))
+}
+
+// hash_to_curve_curve25519 implements curve25519_XMD:SHA-512_ELL2_RO_ [RFC 9380, 8.5] and
+// returns the affine coordinates (u, v) of the point.
+pub fn hash_to_curve_curve25519(msg: &[u8], dst: &[u8]) -> Result<(Element, Element), ()> {
+    let r = hash_to_field(msg, dst, 2)?;
+    let q0 = map_to_curve(&r[0]);
+    let q1 = map_to_curve(&r[1]);
+    edwards_to_montgomery(&Point::mul_by_cofactor(&Point::add(&q0, &q1)))
+}
+
+// encode_to_curve_curve25519 implements curve25519_XMD:SHA-512_ELL2_NU_ [RFC 9380, 8.5] and
+// returns the affine coordinates (u, v) of the point.
+pub fn encode_to_curve_curve25519(msg: &[u8], dst: &[u8]) -> Result<(Element, Element), ()> {
+    let r = hash_to_field(msg, dst, 1)?;
+    edwards_to_montgomery(&Point::mul_by_cofactor(&map_to_curve(&r[0])))
+}
+
+#[cfg(test)]
+mod hash_to_curve_test {
+    use crate::edwards::Point;
+    use crate::field::Element;
+    use crate::hash_to_curve::{self, expand_message_xmd, hash_to_field};
+    use crate::test_util::hex_decode;
+
+    // the test vectors print field elements as big-endian integers
+    fn to_be_hex(e: &Element) -> String {
+        let mut b = e.to_le_bytes();
+        b.reverse();
+        hex::encode(b)
+    }
+
+    fn affine(p: &Point) -> (Element, Element) {
+        let z_inv = Element::invert(&p.z);
+        (Element::multiply(&p.x, &z_inv), Element::multiply(&p.y, &z_inv))
+    }
+
+    fn messages() -> [Vec<u8>; 5] {
+        [
+            b"".to_vec(),
+            b"abc".to_vec(),
+            b"abcdef0123456789".to_vec(),
+            [b"q128_".to_vec(), vec![b'q'; 128]].concat(),
+            [b"a512_".to_vec(), vec![b'a'; 512]].concat(),
+        ]
+    }
+
+    const EDWARDS_RO_DST: &[u8] = b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_RO_";
+    const EDWARDS_NU_DST: &[u8] = b"QUUX-V01-CS02-with-edwards25519_XMD:SHA-512_ELL2_NU_";
+    const CURVE_RO_DST: &[u8] = b"QUUX-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_RO_";
+    const CURVE_NU_DST: &[u8] = b"QUUX-V01-CS02-with-curve25519_XMD:SHA-512_ELL2_NU_";
+
+    // RFC 9380, Appendix J.5.1: (u[0], u[1], P.x, P.y)
+    const EDWARDS_RO: [[&str; 4]; 5] = [
+        ["03fef4813c8cb5f98c6eef88fae174e6e7d5380de2b007799ac7ee712d203f3a",
+         "780bdddd137290c8f589dc687795aafae35f6b674668d92bf92ae793e6a60c75",
+         "3c3da6925a3c3c268448dcabb47ccde5439559d9599646a8260e47b1e4822fc6",
+         "09a6c8561a0b22bef63124c588ce4c62ea83a3c899763af26d795302e115dc21"],
+        ["5081955c4141e4e7d02ec0e36becffaa1934df4d7a270f70679c78f9bd57c227",
+         "005bdc17a9b378b6272573a31b04361f21c371b256252ae5463119aa0b925b76",
+         "608040b42285cc0d72cbb3985c6b04c935370c7361f4b7fbdb1ae7f8c1a8ecad",
+         "1a8395b88338f22e435bbd301183e7f20a5f9de643f11882fb237f88268a5531"],
+        ["285ebaa3be701b79871bcb6e225ecc9b0b32dff2d60424b4c50642636a78d5b3",
+         "2e253e6a0ef658fedb8e4bd6a62d1544fd6547922acb3598ec6b369760b81b31",
+         "6d7fabf47a2dc03fe7d47f7dddd21082c5fb8f86743cd020f3fb147d57161472",
+         "53060a3d140e7fbcda641ed3cf42c88a75411e648a1add71217f70ea8ec561a6"],
+        ["4fedd25431c41f2a606952e2945ef5e3ac905a42cf64b8b4d4a83c533bf321af",
+         "02f20716a5801b843987097a8276b6d869295b2e11253751ca72c109d37485a9",
+         "5fb0b92acedd16f3bcb0ef83f5c7b7a9466b5f1e0d8d217421878ea3686f8524",
+         "2eca15e355fcfa39d2982f67ddb0eea138e2994f5956ed37b7f72eea5e89d2f7"],
+        ["6e34e04a5106e9bd59f64aba49601bf09d23b27f7b594e56d5de06df4a4ea33b",
+         "1c1c2cb59fc053f44b86c5d5eb8c1954b64976d0302d3729ff66e84068f5fd96",
+         "0efcfde5898a839b00997fbe40d2ebe950bc81181afbd5cd6b9618aa336c1e8c",
+         "6dc2fc04f266c5c27f236a80b14f92ccd051ef1ff027f26a07f8c0f327d8f995"],
+    ];
+
+    // RFC 9380, Appendix J.5.2: (u[0], P.x, P.y)
+    const EDWARDS_NU: [[&str; 3]; 5] = [
+        ["7f3e7fb9428103ad7f52db32f9df32505d7b427d894c5093f7a0f0374a30641d",
+         "1ff2b70ecf862799e11b7ae744e3489aa058ce805dd323a936375a84695e76da",
+         "222e314d04a4d5725e9f2aff9fb2a6b69ef375a1214eb19021ceab2d687f0f9b"],
+        ["09cfa30ad79bd59456594a0f5d3a76f6b71c6787b04de98be5cd201a556e253b",
+         "5f13cc69c891d86927eb37bd4afc6672360007c63f68a33ab423a3aa040fd2a8",
+         "67732d50f9a26f73111dd1ed5dba225614e538599db58ba30aaea1f5c827fa42"],
+        ["475ccff99225ef90d78cc9338e9f6a6bb7b17607c0c4428937de75d33edba941",
+         "1dd2fefce934ecfd7aae6ec998de088d7dd03316aa1847198aecf699ba6613f1",
+         "2f8a6c24dd1adde73909cada6a4a137577b0f179d336685c4a955a0a8e1a86fb"],
+        ["049a1c8bd51bcb2aec339f387d1ff51428b88d0763a91bcdf6929814ac95d03d",
+         "35fbdc5143e8a97afd3096f2b843e07df72e15bfca2eaf6879bf97c5d3362f73",
+         "2af6ff6ef5ebba128b0774f4296cb4c2279a074658b083b8dcca91f57a603450"],
+        ["3cb0178a8137cefa5b79a3a57c858d7eeeaa787b2781be4a362a2f0750d24fa0",
+         "6e5e1f37e99345887fc12111575fc1c3e36df4b289b8759d23af14d774b66bff",
+         "2c90c3d39eb18ff291d33441b35f3262cdd307162cc97c31bfcc7a4245891a37"],
+    ];
+
+    // RFC 9380, Appendix J.4.1: (u[0], u[1], P.x, P.y)
+    const CURVE_RO: [[&str; 4]; 5] = [
+        ["005fe8a7b8fef0a16c105e6cadf5a6740b3365e18692a9c05bfbb4d97f645a6a",
+         "1347edbec6a2b5d8c02e058819819bee177077c9d10a4ce165aab0fd0252261a",
+         "2de3780abb67e861289f5749d16d3e217ffa722192d16bbd9d1bfb9d112b98c0",
+         "3b5dc2a498941a1033d176567d457845637554a2fe7a3507d21abd1c1bd6e878"],
+        ["49bed021c7a3748f09fa8cdfcac044089f7829d3531066ac9e74e0994e05bc7d",
+         "5c36525b663e63389d886105cee7ed712325d5a97e60e140aba7e2ce5ae851b6",
+         "2b4419f1f2d48f5872de692b0aca72cc7b0a60915dd70bde432e826b6abc526d",
+         "1b8235f255a268f0a6fa8763e97eb3d22d149343d495da1160eff9703f2d07dd"],
+        ["6412b7485ba26d3d1b6c290a8e1435b2959f03721874939b21782df17323d160",
+         "24c7b46c1c6d9a21d32f5707be1380ab82db1054fde82865d5c9e3d968f287b2",
+         "68ca1ea5a6acf4e9956daa101709b1eee6c1bb0df1de3b90d4602382a104c036",
+         "2a375b656207123d10766e68b938b1812a4a6625ff83cb8d5e86f58a4be08353"],
+        ["5e123990f11bbb5586613ffabdb58d47f64bb5f2fa115f8ea8df0188e0c9e1b5",
+         "5e8553eb00438a0bb1e7faa59dec6d8087f9c8011e5fb8ed9df31cb6c0d4ac19",
+         "096e9c8bae6c06b554c1ee69383bb0e82267e064236b3a30608d4ed20b73ac5a",
+         "1eb5a62612cafb32b16c3329794645b5b948d9f8ffe501d4e26b073fef6de355"],
+        ["20f481e85da7a3bf60ac0fb11ed1d0558fc6f941b3ac5469aa8b56ec883d6d7d",
+         "017d57fd257e9a78913999a23b52ca988157a81b09c5442501d07fed20869465",
+         "1bc61845a138e912f047b5e70ba9606ba2a447a4dade024c8ef3dd42b7bbc5fe",
+         "623d05e47b70e25f7f1d51dda6d7c23c9a18ce015fe3548df596ea9e38c69bf1"],
+    ];
+
+    // RFC 9380, Appendix J.4.2: (u[0], P.x, P.y)
+    const CURVE_NU: [[&str; 3]; 5] = [
+        ["608d892b641f0328523802a6603427c26e55e6f27e71a91a478148d45b5093cd",
+         "1bb913f0c9daefa0b3375378ffa534bda5526c97391952a7789eb976edfe4d08",
+         "4548368f4f983243e747b62a600840ae7c1dab5c723991f85d3a9768479f3ec4"],
+        ["46f5b22494bfeaa7f232cc8d054be68561af50230234d7d1d63d1d9abeca8da5",
+         "7c22950b7d900fa866334262fcaea47a441a578df43b894b4625c9b450f9a026",
+         "5547bc00e4c09685dcbc6cb6765288b386d8bdcb595fa5a6e3969e08097f0541"],
+        ["235fe40c443766ce7e18111c33862d66c3b33267efa50d50f9e8e5d252a40aaa",
+         "31ad08a8b0deeb2a4d8b0206ca25f567ab4e042746f792f4b7973f3ae2096c52",
+         "405070c28e78b4fa269427c82827261991b9718bd6c6e95d627d701a53c30db1"],
+        ["001e92a544463bda9bd04ddbe3d6eed248f82de32f522669efc5ddce95f46f5b",
+         "027877759d155b1997d0d84683a313eb78bdb493271d935b622900459d52ceaa",
+         "54d691731a53baa30707f4a87121d5169fb5d587d70fb0292b5830dedbec4c18"],
+        ["1a68a1af9f663592291af987203393f707305c7bac9c8d63d6a729bdc553dc19",
+         "5fd892c0958d1a75f54c3182a18d286efab784e774d1e017ba2fb252998b5dc1",
+         "750af3c66101737423a4519ac792fb93337bd74ee751f19da4cf1e94f4d6d0b8"],
+    ];
+
+    // RFC 9380, Appendix K.3: uniform_bytes for each message, with len_in_bytes = 0x20 and 0x80
+    const EXPANDER_SHA512: [[&str; 5]; 2] = [
+        [
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba",
+            "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc",
+            "087e45a86e2939ee8b91100af1583c4938e0f5fc6c9db4b107b83346bc967f58",
+            "7336234ee9983902440f6bc35b348352013becd88938d2afec44311caf8356b3",
+            "57b5f7e766d5be68a6bfe1768e3c2b7f1228b3e4b3134956dd73a59b954c66f4",
+        ],
+        [
+            "41b037d1734a5f8df225dd8c7de38f851efdb45c372887be655212d07251b921b052b62eaed99b46f72f2ef4cc96bfaf254ebbbec091e1a3b9e4fb5e5b619d2e0c5414800a1d882b62bb5cd1778f098b8eb6cb399d5d9d18f5d5842cf5d13d7eb00a7cff859b605da678b318bd0e65ebff70bec88c753b159a805d2c89c55961",
+            "7f1dddd13c08b543f2e2037b14cefb255b44c83cc397c1786d975653e36a6b11bdd7732d8b38adb4a0edc26a0cef4bb45217135456e58fbca1703cd6032cb1347ee720b87972d63fbf232587043ed2901bce7f22610c0419751c065922b488431851041310ad659e4b23520e1772ab29dcdeb2002222a363f0c2b1c972b3efe1",
+            "3f721f208e6199fe903545abc26c837ce59ac6fa45733f1baaf0222f8b7acb0424814fcb5eecf6c1d38f06e9d0a6ccfbf85ae612ab8735dfdf9ce84c372a77c8f9e1c1e952c3a61b7567dd0693016af51d2745822663d0c2367e3f4f0bed827feecc2aaf98c949b5ed0d35c3f1023d64ad1407924288d366ea159f46287e61ac",
+            "b799b045a58c8d2b4334cf54b78260b45eec544f9f2fb5bd12fb603eaee70db7317bf807c406e26373922b7b8920fa29142703dd52bdf280084fb7ef69da78afdf80b3586395b433dc66cde048a258e476a561e9deba7060af40adf30c64249ca7ddea79806ee5beb9a1422949471d267b21bc88e688e4014087a0b592b695ed",
+            "05b0bfef265dcee87654372777b7c44177e2ae4c13a27f103340d9cd11c86cb2426ffcad5bd964080c2aee97f03be1ca18e30a1f14e27bc11ebbd650f305269cc9fb1db08bf90bfc79b42a952b46daf810359e7bc36452684784a64952c343c52e5124cd1f71d474d5197fefc571a92929c9084ffe1112cf5eea5192ebff330b",
+        ],
+    ];
+
+    #[test]
+    fn test_expand_message_xmd() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+        for (len, vectors) in [32, 128].iter().zip(EXPANDER_SHA512.iter()) {
+            for (msg, v) in messages().iter().zip(vectors.iter()) {
+                assert_eq!(expand_message_xmd(msg, dst, *len).unwrap(), hex_decode(v));
+            }
+        }
+
+        assert!(expand_message_xmd(b"", b"", 32).is_err());
+        assert!(expand_message_xmd(b"", dst, 255 * 64 + 1).is_err());
+        assert_eq!(expand_message_xmd(b"abc", dst, 255 * 64).unwrap().len(), 255 * 64);
+        // an oversize dst is replaced by its hash, never rejected
+        let long_dst = [b'x'; 256];
+        assert_eq!(expand_message_xmd(b"abc", &long_dst, 48).unwrap().len(), 48);
+    }
+
+    #[test]
+    fn test_hash_to_field() {
+        for (msg, v) in messages().iter().zip(EDWARDS_RO.iter()) {
+            let u = hash_to_field(msg, EDWARDS_RO_DST, 2).unwrap();
+            assert_eq!(to_be_hex(&u[0]), v[0]);
+            assert_eq!(to_be_hex(&u[1]), v[1]);
+        }
+        for (msg, v) in messages().iter().zip(EDWARDS_NU.iter()) {
+            let u = hash_to_field(msg, EDWARDS_NU_DST, 1).unwrap();
+            assert_eq!(to_be_hex(&u[0]), v[0]);
+        }
+    }
+
+    #[test]
+    fn test_hash_to_curve_edwards25519() {
+        for (msg, v) in messages().iter().zip(EDWARDS_RO.iter()) {
+            let p = hash_to_curve::hash_to_curve_edwards25519(msg, EDWARDS_RO_DST).unwrap();
+            let (x, y) = affine(&p);
+            assert_eq!(to_be_hex(&x), v[2]);
+            assert_eq!(to_be_hex(&y), v[3]);
+        }
+    }
+
+    #[test]
+    fn test_encode_to_curve_edwards25519() {
+        for (msg, v) in messages().iter().zip(EDWARDS_NU.iter()) {
+            let p = hash_to_curve::encode_to_curve_edwards25519(msg, EDWARDS_NU_DST).unwrap();
+            let (x, y) = affine(&p);
+            assert_eq!(to_be_hex(&x), v[1]);
+            assert_eq!(to_be_hex(&y), v[2]);
+        }
+    }
+
+    // RFC 9380, Appendix J.4
+    #[test]
+    fn test_curve25519_suites() {
+        for (msg, v) in messages().iter().zip(CURVE_RO.iter()) {
+            let u = hash_to_field(msg, CURVE_RO_DST, 2).unwrap();
+            assert_eq!(to_be_hex(&u[0]), v[0]);
+            assert_eq!(to_be_hex(&u[1]), v[1]);
+            let (pu, pv) = hash_to_curve::hash_to_curve_curve25519(msg, CURVE_RO_DST).unwrap();
+            assert_eq!(to_be_hex(&pu), v[2]);
+            assert_eq!(to_be_hex(&pv), v[3]);
+        }
+        for (msg, v) in messages().iter().zip(CURVE_NU.iter()) {
+            let u = hash_to_field(msg, CURVE_NU_DST, 1).unwrap();
+            assert_eq!(to_be_hex(&u[0]), v[0]);
+            let (pu, pv) = hash_to_curve::encode_to_curve_curve25519(msg, CURVE_NU_DST).unwrap();
+            assert_eq!(to_be_hex(&pu), v[1]);
+            assert_eq!(to_be_hex(&pv), v[2]);
+        }
+    }
+
+    // The points of the exceptional cases of the rational map go to the identity.
+    #[test]
+    fn test_elligator2_zero() {
+        // r = 0 gives u = 0, v = 0, the point of order 2 of curve25519
+        let p = hash_to_curve::map_to_curve(Element::ZERO);
+        assert!(p.is_identity());
+    }
+}
@@ -0,0 +1,106 @@
+#![allow(dead_code)]
+
+use sha2::{Digest, Sha256};
+
+// HMAC-SHA-256 [RFC 2104] and HMAC_DRBG over it [NIST SP 800-90A, 10.1.2], without
+// reseeding, the form that RFC 6979 uses to derive deterministic ECDSA nonces.
+
+const BLOCK_SIZE: usize = 64;
+pub const OUTPUT_SIZE: usize = 32;
+
+// hmac_sha256 returns HMAC-SHA-256(key, parts[0] || parts[1] || ...):
+//     H((K ^ opad) || H((K ^ ipad) || message)),
+// with K the key padded to a block, or its hash if it is longer than a block.
+pub fn hmac_sha256(key: &[u8], parts: &[&[u8]]) -> [u8; OUTPUT_SIZE] {
+    let mut k: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        k[..OUTPUT_SIZE].copy_from_slice(&Sha256::digest(key));
+    } else {
+        k[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(k.map(|b| b ^ 0x36));
+    for part in parts {
+        inner.update(part);
+    }
+    let mut outer = Sha256::new();
+    outer.update(k.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+pub struct HmacDrbg {
+    k: [u8; OUTPUT_SIZE],
+    v: [u8; OUTPUT_SIZE],
+}
+
+impl HmacDrbg {
+    // new instantiates the DRBG with the seed material entropy || nonce || personalization,
+    // passed in parts [SP 800-90A, 10.1.2.3].
+    pub fn new(seed: &[&[u8]]) -> HmacDrbg {
+        let mut drbg = HmacDrbg { k: [0x00; OUTPUT_SIZE], v: [0x01; OUTPUT_SIZE] };
+        drbg.update(seed);
+        drbg
+    }
+
+    // update mixes the provided data into K and V [SP 800-90A, 10.1.2.2]. Without data, it
+    // only runs the first half.
+    fn update(&mut self, data: &[&[u8]]) {
+        let mut parts: Vec<&[u8]> = vec![&self.v, &[0x00]];
+        parts.extend_from_slice(data);
+        self.k = hmac_sha256(&self.k, &parts);
+        self.v = hmac_sha256(&self.k, &[&self.v]);
+        if data.iter().all(|d| d.is_empty()) {
+            return;
+        }
+
+        let mut parts: Vec<&[u8]> = vec![&self.v, &[0x01]];
+        parts.extend_from_slice(data);
+        self.k = hmac_sha256(&self.k, &parts);
+        self.v = hmac_sha256(&self.k, &[&self.v]);
+    }
+
+    // generate fills out with the next output, V = HMAC(K, V) a block at a time, and then
+    // updates the state so that a later call yields independent output [SP 800-90A, 10.1.2.5].
+    pub fn generate(&mut self, out: &mut [u8]) {
+        for chunk in out.chunks_mut(OUTPUT_SIZE) {
+            self.v = hmac_sha256(&self.k, &[&self.v]);
+            chunk.copy_from_slice(&self.v[..chunk.len()]);
+        }
+        self.update(&[]);
+    }
+}
+
+#[cfg(test)]
+mod hmac_drbg_test {
+    use crate::hmac_drbg::{hmac_sha256, HmacDrbg};
+    use crate::test_util::hex_decode;
+
+    // RFC 4231, Sections 4.2, 4.3 and 4.7: short keys, a message in two parts and a key longer
+    // than a block.
+    #[test]
+    fn test_hmac_sha256() {
+        assert_eq!(hmac_sha256(&[0x0b; 20], &[b"Hi There"]).to_vec(),
+            hex_decode("b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"));
+        assert_eq!(hmac_sha256(b"Jefe", &[b"what do ya want ", b"for nothing?"]).to_vec(),
+            hex_decode("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"));
+        assert_eq!(hmac_sha256(&[0xaa; 131], &[b"Test Using Larger Than Block-Size Key - Hash Key First"]).to_vec(),
+            hex_decode("60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"));
+    }
+
+    // A seed given in parts, an output of more than one block, and a second call after the
+    // update; the expected values follow SP 800-90A, 10.1.2.
+    #[test]
+    fn test_hmac_drbg() {
+        let seed: Vec<u8> = (0..48).collect();
+        let mut drbg = HmacDrbg::new(&[&seed[..32], &seed[32..]]);
+        let mut out: [u8; 40] = [0; 40];
+        drbg.generate(&mut out);
+        assert_eq!(out.to_vec(),
+            hex_decode("0ffb80875a3e9022a4941a3fa1b0d3611df14e1cf651a73ce9229b9f3ad56887680428845710288e"));
+        let mut out: [u8; 32] = [0; 32];
+        drbg.generate(&mut out);
+        assert_eq!(out.to_vec(), hex_decode("cac8490ba9b23ffc16f14f9b05d42adbabc2f9b96b2abe2561240450cdd38b52"));
+    }
+}
@@ -0,0 +1,181 @@
+#![allow(dead_code)]
+
+// Keccak-f[1600] and SHAKE256, as specified in FIPS 202.
+//
+// The state is a 5x5 array of 64-bit lanes, lane (x, y) at index x + 5*y. A sponge absorbs
+// its input rate octets at a time, XORed into the first lanes of the state in little-endian
+// order, and permutes the state after every block; it squeezes its output from the same
+// lanes. SHAKE256 has a rate of 136 octets (capacity 512 bits, 256-bit security) and pads
+// the input with the domain bits 1111 followed by pad10*1, that is, with the octets 0x1F
+// and, at the end of the block, 0x80.
+
+const ROUNDS: usize = 24;
+
+// the round constants of the iota step
+const RC: [u64; ROUNDS] = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808a, 0x8000000080008000,
+    0x000000000000808b, 0x0000000080000001, 0x8000000080008081, 0x8000000000008009,
+    0x000000000000008a, 0x0000000000000088, 0x0000000080008009, 0x000000008000000a,
+    0x000000008000808b, 0x800000000000008b, 0x8000000000008089, 0x8000000000008003,
+    0x8000000000008002, 0x8000000000000080, 0x000000000000800a, 0x800000008000000a,
+    0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+];
+
+// The rho and pi steps together move lane PI[i] to lane PI[i+1], rotated left by RHO[i],
+// along the single cycle of pi that starts at lane (1, 0).
+const RHO: [u32; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+// keccak_f1600 applies the 24 rounds of the permutation to the state.
+pub fn keccak_f1600(a: &mut [u64; 25]) {
+    for rc in RC {
+        // theta: XOR each lane with the parities of two neighbouring columns
+        let mut c: [u64; 5] = [0; 5];
+        for x in 0..5 {
+            c[x] = a[x] ^ a[x + 5] ^ a[x + 10] ^ a[x + 15] ^ a[x + 20];
+        }
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+
+        // rho and pi
+        let mut last = a[1];
+        for i in 0..24 {
+            let t = a[PI[i]];
+            a[PI[i]] = last.rotate_left(RHO[i]);
+            last = t;
+        }
+
+        // chi: the only non-linear step, along the rows
+        for y in 0..5 {
+            let row: [u64; 5] = a[5 * y..5 * y + 5].try_into().unwrap();
+            for x in 0..5 {
+                a[x + 5 * y] = row[x] ^ (!row[(x + 1) % 5] & row[(x + 2) % 5]);
+            }
+        }
+
+        // iota
+        a[0] ^= rc;
+    }
+}
+
+// Shake256 is the SHAKE256 extendable-output function. Input is fed with update, any
+// number of times; finalize then pads the input and squeezes an output of any length.
+#[derive(Clone)]
+pub struct Shake256 {
+    state: [u64; 25],
+    // the number of octets absorbed into the current block
+    offset: usize,
+}
+
+impl Shake256 {
+    pub const RATE: usize = 136;
+
+    pub fn new() -> Shake256 {
+        Shake256 { state: [0; 25], offset: 0 }
+    }
+
+    // xor_byte XORs b into octet i of the state, in little-endian lane order.
+    fn xor_byte(&mut self, i: usize, b: u8) {
+        self.state[i / 8] ^= (b as u64) << (8 * (i % 8));
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.xor_byte(self.offset, *b);
+            self.offset += 1;
+            if self.offset == Shake256::RATE {
+                keccak_f1600(&mut self.state);
+                self.offset = 0;
+            }
+        }
+    }
+
+    // finalize fills out with the first out.len() octets of the output.
+    pub fn finalize(mut self, out: &mut [u8]) {
+        self.xor_byte(self.offset, 0x1F);
+        self.xor_byte(Shake256::RATE - 1, 0x80);
+        keccak_f1600(&mut self.state);
+
+        for (i, chunk) in out.chunks_mut(Shake256::RATE).enumerate() {
+            if i > 0 {
+                keccak_f1600(&mut self.state);
+            }
+            for (j, b) in chunk.iter_mut().enumerate() {
+                *b = (self.state[j / 8] >> (8 * (j % 8))) as u8;
+            }
+        }
+    }
+}
+
+impl Default for Shake256 {
+    fn default() -> Shake256 {
+        Shake256::new()
+    }
+}
+
+// shake256 returns the first N octets of SHAKE256(parts[0] || parts[1] || ...).
+pub fn shake256<const N: usize>(parts: &[&[u8]]) -> [u8; N] {
+    let mut h = Shake256::new();
+    for part in parts {
+        h.update(part);
+    }
+    let mut out: [u8; N] = [0; N];
+    h.finalize(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod keccak_test {
+    use crate::keccak::{keccak_f1600, shake256, Shake256};
+    use crate::test_util::hex_decode;
+
+    // The state after one and two permutations of the all-zero state, from the Keccak team's
+    // KeccakF-1600-IntermediateValues.txt.
+    #[test]
+    fn test_keccak_f1600() {
+        let mut a: [u64; 25] = [0; 25];
+        keccak_f1600(&mut a);
+        assert_eq!(a[0], 0xF1258F7940E1DDE7);
+        assert_eq!(a[24], 0xEAF1FF7B5CECA249);
+        keccak_f1600(&mut a);
+        assert_eq!(a[0], 0x2D5C954DF96ECB3C);
+        assert_eq!(a[24], 0x20D06CD26A8FBF5C);
+    }
+
+    #[test]
+    fn test_shake256() {
+        assert_eq!(shake256::<32>(&[]).to_vec(),
+                   hex_decode("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762f"));
+        assert_eq!(shake256::<64>(&[b"abc"]).to_vec(),
+                   hex_decode("483366601360a8771c6863080cc4114d8db44530f8f1e1ee4f94ea37e78b5739d5a15bef186a5386c75744c0527e1faa9f8726e462a12a4feb06bd8801e751e4"));
+
+        // 256 octets span two blocks; the input may be split anywhere.
+        let msg: Vec<u8> = (0..=255).collect();
+        let expected = hex_decode("336c8aa7f2b08bda6bd7402cd2ea89760b7728a8b31802b80524756361165366");
+        assert_eq!(shake256::<32>(&[&msg]).to_vec(), expected);
+        for split in [1, 135, 136, 137] {
+            assert_eq!(shake256::<32>(&[&msg[..split], &msg[split..]]).to_vec(), expected);
+        }
+    }
+
+    // The output is a stream: a longer output extends a shorter one, across the block boundary.
+    #[test]
+    fn test_shake256_squeeze() {
+        let long: [u8; 300] = shake256(&[b"abc"]);
+        let short: [u8; 64] = shake256(&[b"abc"]);
+        assert_eq!(long[..64], short);
+        let mut h = Shake256::new();
+        h.update(b"abc");
+        let mut out: [u8; 137] = [0; 137];
+        h.finalize(&mut out);
+        assert_eq!(long[..137], out);
+    }
+}
@@ -1,10 +1,43 @@
 use crate::field::{Element};
 
 mod field;
+mod prime_field;
 mod binary;
 mod bits;
 mod subtle;
 mod x25519;
+mod scalar;
+mod edwards;
+mod ed25519;
+mod multiscalar;
+mod ristretto255;
+mod hash_to_curve;
+mod elligator;
+mod weierstrass;
+mod weierstrass_curve;
+mod montgomery;
+mod vrf;
+mod field448;
+mod x448;
+mod keccak;
+mod scalar448;
+mod edwards448;
+mod ed448;
+mod fp256;
+mod field_p256;
+mod p256;
+mod scalar_p256;
+mod hmac_drbg;
+mod ecdsa_p256;
+mod field_secp256k1;
+mod scalar_secp256k1;
+mod secp256k1;
+mod bip340;
+mod gf2m;
+mod extension_field;
+mod poly1305;
+#[cfg(test)]
+mod test_util;
 
 fn main() {
     let _: &Element = Element::ZERO;
@@ -0,0 +1,223 @@
+#![allow(dead_code)]
+
+use crate::field::Element;
+
+// MontgomeryPoint is an x-only point of Curve25519, v^2 = u^3 + A*u^2 + u, in projective
+// coordinates (X:Z) with u = X/Z. The point at infinity is (1:0). The sign of v is lost,
+// so P and -P share a representation; sums are only defined given the difference of the
+// summands (differential addition). The formulas are those of Montgomery, "Speeding the
+// Pollard and elliptic curve methods of factorization" (1987), as used in RFC 7748, and
+// they work unchanged on the twist.
+#[derive(Clone, Debug)]
+pub struct MontgomeryPoint {
+    pub(crate) x: Element,
+    pub(crate) z: Element,
+}
+
+impl MontgomeryPoint {
+    // (A + 2) / 4
+    const A24: u32 = 121666;
+    // the A coefficient of Curve25519
+    const A: u32 = 486662;
+
+    pub fn identity() -> MontgomeryPoint {
+        MontgomeryPoint { x: Element::ONE.clone(), z: Element::ZERO.clone() }
+    }
+
+    // from_u returns the point (u:1). Like x25519::scalar_mul, it ignores bit 255 and accepts
+    // non-canonical encodings.
+    pub fn from_u(u: &[u8; 32]) -> MontgomeryPoint {
+        MontgomeryPoint { x: Element::from_le_bytes(*u), z: Element::ONE.clone() }
+    }
+
+    // to_u returns the encoding of u = X/Z, or 0 for the point at infinity.
+    pub fn to_u(&self) -> [u8; 32] {
+        Element::multiply(&self.x, &Element::invert(&self.z)).to_le_bytes()
+    }
+
+    // Swap swaps p and q if cond == 1, and leaves them alone if cond == 0.
+    pub fn swap(p: &mut MontgomeryPoint, q: &mut MontgomeryPoint, cond: u32) {
+        Element::swap(&mut p.x, &mut q.x, cond);
+        Element::swap(&mut p.z, &mut q.z, cond);
+    }
+
+    // xdbl returns 2P:
+    //     X2 = (X+Z)^2 * (X-Z)^2,  Z2 = 4XZ * ((X-Z)^2 + A24 * 4XZ),  where 4XZ = (X+Z)^2 - (X-Z)^2.
+    pub fn xdbl(p: &MontgomeryPoint) -> MontgomeryPoint {
+        let aa = Element::square(&Element::add(&p.x, &p.z));
+        let bb = Element::square(&Element::subtract(&p.x, &p.z));
+        let e = Element::subtract(&aa, &bb);
+        MontgomeryPoint {
+            x: Element::multiply(&aa, &bb),
+            z: Element::multiply(&e, &Element::add(&bb, &Element::mul32(&e, MontgomeryPoint::A24))),
+        }
+    }
+
+    // xadd returns P + Q, given their difference P - Q:
+    //     X+ = Z- * ((XP-ZP)(XQ+ZQ) + (XP+ZP)(XQ-ZQ))^2,
+    //     Z+ = X- * ((XP-ZP)(XQ+ZQ) - (XP+ZP)(XQ-ZQ))^2.
+    // The difference must not be the point at infinity or (0:1), that is, P != +-Q and
+    // P - Q is not of order 2.
+    pub fn xadd(p: &MontgomeryPoint, q: &MontgomeryPoint, diff: &MontgomeryPoint) -> MontgomeryPoint {
+        let da = Element::multiply(&Element::subtract(&p.x, &p.z), &Element::add(&q.x, &q.z));
+        let cb = Element::multiply(&Element::add(&p.x, &p.z), &Element::subtract(&q.x, &q.z));
+        MontgomeryPoint {
+            x: Element::multiply(&diff.z, &Element::square(&Element::add(&da, &cb))),
+            z: Element::multiply(&diff.x, &Element::square(&Element::subtract(&da, &cb))),
+        }
+    }
+
+    // ladder returns ([k]P, [k+1]P) for a little-endian k of any length, usually 32 octets.
+    // Every step does one xadd and one xdbl on a conditionally swapped pair whose difference
+    // stays P, so the running time depends on the length of k but not on its value.
+    pub fn ladder(k: &[u8], p: &MontgomeryPoint) -> (MontgomeryPoint, MontgomeryPoint) {
+        let mut r0 = MontgomeryPoint::identity();
+        let mut r1 = p.clone();
+        let mut swap: u32 = 0;
+        for pos in (0..8 * k.len()).rev() {
+            let bit: u32 = ((k[pos / 8] >> (pos & 7)) & 1) as u32;
+            swap ^= bit;
+            MontgomeryPoint::swap(&mut r0, &mut r1, swap);
+            swap = bit;
+            r1 = MontgomeryPoint::xadd(&r0, &r1, p);
+            r0 = MontgomeryPoint::xdbl(&r0);
+        }
+        MontgomeryPoint::swap(&mut r0, &mut r1, swap);
+        (r0, r1)
+    }
+
+    // recover_y returns the affine (u, v) of Q given Q = (XQ:ZQ), R = Q + P = (XR:ZR), and
+    // the affine P = (u, v), with the formulas of Okeya and Sakurai, "Efficient elliptic curve
+    // cryptosystems from a scalar multiplication algorithm with recovery of the y-coordinate
+    // on a Montgomery-form elliptic curve" (CHES 2001), for B = 1:
+    //     Y' = ZR * (XQ + u*ZQ + 2A*ZQ) * (u*XQ + ZQ) - 2A*ZQ^2 * ZR - XR * (XQ - u*ZQ)^2
+    //     X' = 2v * ZQ * ZR * XQ,  Z' = 2v * ZQ * ZR * ZQ
+    // With the two outputs of ladder, it yields the full point [k]P. It fails if Q or R is
+    // the point at infinity, or if v = 0.
+    pub fn recover_y(u: &Element, v: &Element, q: &MontgomeryPoint, r: &MontgomeryPoint)
+                     -> Result<(Element, Element), ()> {
+        let a2 = Element::mul32(&Element(MontgomeryPoint::A as u64, 0, 0, 0, 0), 2);
+        let u_zq = Element::multiply(u, &q.z);
+        let t3 = Element::multiply(&Element::square(&Element::subtract(&q.x, &u_zq)), &r.x);
+        let a2_zq = Element::multiply(&a2, &q.z);
+        let t2 = Element::multiply(
+            &Element::add(&Element::add(&q.x, &u_zq), &a2_zq),
+            &Element::add(&Element::multiply(u, &q.x), &q.z));
+        let t2 = Element::multiply(&Element::subtract(&t2, &Element::multiply(&a2_zq, &q.z)), &r.z);
+        let y = Element::subtract(&t2, &t3);
+        let t1 = Element::multiply(&Element::multiply(&Element::mul32(v, 2), &q.z), &r.z);
+        let x = Element::multiply(&t1, &q.x);
+        let z = Element::multiply(&t1, &q.z);
+        if z.equal(Element::ZERO) {
+            return Err(());
+        }
+        let z_inv = Element::invert(&z);
+        Ok((Element::multiply(&x, &z_inv), Element::multiply(&y, &z_inv)))
+    }
+}
+
+#[cfg(test)]
+mod montgomery_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::field::Element;
+    use crate::montgomery::MontgomeryPoint;
+    use crate::test_util::hex_decode;
+    use crate::weierstrass::{AffinePoint, JacobianPoint};
+    use crate::x25519;
+
+    // RFC 7748, Section 5.2: the two single-shot vectors and the iterated one.
+    #[test]
+    fn test_ladder_rfc7748() {
+        let vectors = [
+            ("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4",
+             "e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c",
+             "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"),
+            ("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d",
+             "e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493",
+             "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957"),
+        ];
+        for (k, u, expected) in vectors {
+            let k = x25519::scalar_clamp(hex_decode(k).try_into().unwrap());
+            let p = MontgomeryPoint::from_u(&hex_decode(u).try_into().unwrap());
+            let (q, _) = MontgomeryPoint::ladder(&k, &p);
+            assert_eq!(q.to_u().to_vec(), hex_decode(expected));
+        }
+
+        let mut k: [u8; 32] = hex_decode("0900000000000000000000000000000000000000000000000000000000000000")
+            .try_into().unwrap();
+        let mut u = k;
+        for i in 1..=1000 {
+            let (q, _) = MontgomeryPoint::ladder(&x25519::scalar_clamp(k), &MontgomeryPoint::from_u(&u));
+            u = k;
+            k = q.to_u();
+            if i == 1 {
+                assert_eq!(k.to_vec(), hex_decode("422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"));
+            }
+        }
+        assert_eq!(k.to_vec(), hex_decode("684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"));
+    }
+
+    // The second output of the ladder is [k+1]P, and the first agrees with x25519::scalar_mul.
+    #[test]
+    fn test_ladder_pair() {
+        let mut rng = StdRng::seed_from_u64(0x1ADDE5);
+        let u: [u8; 32] = hex_decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f")
+            .try_into().unwrap();
+        let p = MontgomeryPoint::from_u(&u);
+        for _ in 0..8 {
+            let mut k: [u8; 32] = rng.gen();
+            k[31] &= 0x7F;
+            let (q, r) = MontgomeryPoint::ladder(&k, &p);
+
+            let mut k1 = k;
+            for b in k1.iter_mut() {
+                let (s, carry) = b.overflowing_add(1);
+                *b = s;
+                if !carry {
+                    break;
+                }
+            }
+            assert_eq!(r.to_u(), MontgomeryPoint::ladder(&k1, &p).0.to_u());
+
+            let mut expected: [u8; 32] = [0; 32];
+            x25519::scalar_mul(k, u, &mut expected);
+            assert_eq!(MontgomeryPoint::ladder(&x25519::scalar_clamp(k), &p).0.to_u(), expected);
+            assert_ne!(q.to_u(), [0; 32]);
+        }
+
+        // [2]P by xdbl, [3]P by xadd with difference P
+        let p2 = MontgomeryPoint::xdbl(&p);
+        let p3 = MontgomeryPoint::xadd(&p2, &p, &p);
+        let mut three: [u8; 32] = [0; 32];
+        three[0] = 3;
+        assert_eq!(MontgomeryPoint::ladder(&three, &p).0.to_u(), p3.to_u());
+        assert_eq!(MontgomeryPoint::identity().to_u(), [0; 32]);
+    }
+
+    // The recovered [k]P must be the point that double-and-add on Wei25519 computes.
+    #[test]
+    fn test_recover_y() {
+        let mut rng = StdRng::seed_from_u64(0x0C4A);
+        let g = AffinePoint::generator();
+        let (u, v) = g.to_montgomery();
+        let p = MontgomeryPoint::from_u(&u.to_le_bytes());
+        for _ in 0..8 {
+            let k: [u8; 32] = rng.gen();
+            let (q, r) = MontgomeryPoint::ladder(&k, &p);
+            let (qu, qv) = MontgomeryPoint::recover_y(&u, &v, &q, &r).unwrap();
+
+            let expected = JacobianPoint::vartime_scalar_mul(&k, &JacobianPoint::from_affine(&g)).to_affine().unwrap();
+            let (eu, ev) = expected.to_montgomery();
+            assert!(qu.equal(&eu));
+            assert!(qv.equal(&ev));
+        }
+
+        // Q at infinity
+        let l = crate::scalar::Scalar::ORDER;
+        let (q, r) = MontgomeryPoint::ladder(&l, &p);
+        assert!(MontgomeryPoint::recover_y(&u, &v, &q, &r).is_err());
+        assert!(MontgomeryPoint::recover_y(&u, Element::ZERO, &p, &MontgomeryPoint::xdbl(&p)).is_err());
+    }
+}
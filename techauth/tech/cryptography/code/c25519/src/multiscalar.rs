@@ -0,0 +1,222 @@
+#![allow(dead_code)]
+
+use crate::edwards::Point;
+use crate::scalar::Scalar;
+use crate::subtle;
+
+// Multiscalar multiplication computes
+//     [k_0]P_0 + [k_1]P_1 + ... + [k_(n-1)]P_(n-1)
+// for many points at once. Every method here shares a single chain of doublings between
+// all the points, instead of paying for 252 doublings per point.
+//
+// Straus' method keeps a small table of multiples for every point and adds one entry per
+// point and window. Its cost grows as n * (256 / w) additions for a fixed w.
+// Pippenger's bucket method sorts the points of every window into 2^(w-1) buckets by digit,
+// and sums the buckets with a running sum, so each window costs about n + 2^w additions.
+// With w close to log2(n), this is asymptotically better; with the formulas of edwards.rs
+// it overtakes Straus at around 250 points.
+impl Point {
+    // Below this many points vartime_multiscalar_mul uses Straus' method, above it Pippenger's.
+    const PIPPENGER_THRESHOLD: usize = 256;
+
+    // vartime_multiscalar_mul returns sum([k_i]P_i). Its running time depends on the scalars:
+    // use it only on public data.
+    pub fn vartime_multiscalar_mul(scalars: &[Scalar], points: &[Point]) -> Point {
+        assert_eq!(scalars.len(), points.len());
+        if scalars.len() < Point::PIPPENGER_THRESHOLD {
+            vartime_straus(scalars, points)
+        } else {
+            vartime_pippenger(scalars, points)
+        }
+    }
+
+    // multiscalar_mul returns sum([k_i]P_i) in time that depends only on the number of points,
+    // so the scalars may be secret. It is Straus' method over signed radix-16 digits, with
+    // every table read in full for every digit.
+    pub fn multiscalar_mul(scalars: &[Scalar], points: &[Point]) -> Point {
+        assert_eq!(scalars.len(), points.len());
+        let digits: Vec<[i8; 64]> = scalars.iter().map(|k| k.signed_radix16()).collect();
+        let tables: Vec<LookupTable> = points.iter().map(LookupTable::new).collect();
+
+        let mut q = Point::identity();
+        for i in (0..64).rev() {
+            if i != 63 {
+                for _ in 0..4 {
+                    q = Point::double(&q);
+                }
+            }
+            for (d, table) in digits.iter().zip(tables.iter()) {
+                q = Point::add(&q, &table.select(d[i]));
+            }
+        }
+        q
+    }
+}
+
+// vartime_straus interleaves the width-5 NAF of every scalar, adding or subtracting one of
+// [1]P_i, [3]P_i, ..., [15]P_i for each nonzero digit.
+fn vartime_straus(scalars: &[Scalar], points: &[Point]) -> Point {
+    let nafs: Vec<[i8; 256]> = scalars.iter().map(|k| k.non_adjacent_form(5)).collect();
+    let tables: Vec<Vec<Point>> = points.iter().map(|p| Point::odd_multiples(p, 8)).collect();
+
+    // Skip the leading zero digits, which would only double the identity.
+    let top = (0..256).rev().find(|&i| nafs.iter().any(|naf| naf[i] != 0));
+    let mut q = Point::identity();
+    for i in (0..=top.unwrap_or(0)).rev() {
+        q = Point::double(&q);
+        for (naf, table) in nafs.iter().zip(tables.iter()) {
+            match naf[i] {
+                d if d > 0 => q = Point::add(&q, &table[d as usize / 2]),
+                d if d < 0 => q = Point::subtract(&q, &table[-d as usize / 2]),
+                _ => {}
+            }
+        }
+    }
+    q
+}
+
+// vartime_pippenger processes the signed radix-2^w digits of all scalars one window at a time,
+// from the most significant. Within a window, P_i goes into bucket |d_i| (negated if d_i < 0),
+// and the weighted sum of the buckets, sum(j * bucket_j), is formed with two running sums.
+fn vartime_pippenger(scalars: &[Scalar], points: &[Point]) -> Point {
+    let w: usize = match scalars.len() {
+        n if n < 500 => 6,
+        n if n < 800 => 7,
+        _ => 8,
+    };
+    let digits: Vec<Vec<i16>> = scalars.iter().map(|k| k.signed_radix_2w(w)).collect();
+    let windows = digits.first().map_or(0, |d| d.len());
+
+    let mut q = Point::identity();
+    for i in (0..windows).rev() {
+        if i + 1 != windows {
+            for _ in 0..w {
+                q = Point::double(&q);
+            }
+        }
+
+        // bucket j collects the points whose digit is j + 1 or -(j + 1)
+        let mut buckets: Vec<Point> = vec![Point::identity(); 1 << (w - 1)];
+        for (d, p) in digits.iter().zip(points.iter()) {
+            match d[i] {
+                d if d > 0 => buckets[d as usize - 1] = Point::add(&buckets[d as usize - 1], p),
+                d if d < 0 => buckets[-d as usize - 1] = Point::subtract(&buckets[-d as usize - 1], p),
+                _ => {}
+            }
+        }
+
+        // running holds bucket_m + ... + bucket_j, and sum adds it once for every j,
+        // which counts bucket_j exactly j times.
+        let mut running = Point::identity();
+        let mut sum = Point::identity();
+        for bucket in buckets.iter().rev() {
+            running = Point::add(&running, bucket);
+            sum = Point::add(&sum, &running);
+        }
+        q = Point::add(&q, &sum);
+    }
+    q
+}
+
+// LookupTable holds [1]P, [2]P, ..., [8]P for multiscalar_mul.
+struct LookupTable([Point; 8]);
+
+impl LookupTable {
+    fn new(p: &Point) -> LookupTable {
+        let mut q = p.clone();
+        LookupTable(core::array::from_fn(|_| {
+            let entry = q.clone();
+            q = Point::add(&q, p);
+            entry
+        }))
+    }
+
+    // select returns [x]P for x in [-8, 8], reading every entry regardless of x.
+    fn select(&self, x: i8) -> Point {
+        // |x|, computed without branches
+        let x_mask = x >> 7;
+        let x_abs = ((x + x_mask) ^ x_mask) as u8;
+
+        let mut v = Point::identity();
+        for (j, entry) in self.0.iter().enumerate() {
+            let w = v.clone();
+            v.select(entry, &w, subtle::constant_time_byte_eq(x_abs, j as u8 + 1));
+        }
+        let w = v.clone();
+        v.select(&Point::negate(&w), &w, (x_mask & 1) as u32);
+        v
+    }
+}
+
+#[cfg(test)]
+mod multiscalar_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::edwards::Point;
+    use crate::multiscalar::{vartime_pippenger, vartime_straus};
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+
+    // random_inputs returns n random scalars and n points [r_i]B, every third of which
+    // also carries a point of order 8.
+    fn random_inputs(n: usize, rng: &mut StdRng) -> (Vec<Scalar>, Vec<Point>) {
+        let t8 = Point::from_bytes(&hex_decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a")
+            .try_into().unwrap()).unwrap();
+        let scalars: Vec<Scalar> = (0..n).map(|_| Scalar::from_bytes_mod_order(rng.gen())).collect();
+        let points: Vec<Point> = (0..n).map(|i| {
+            let p = Point::basepoint_mul(&rng.gen());
+            if i % 3 == 0 { Point::add(&p, &t8) } else { p }
+        }).collect();
+        (scalars, points)
+    }
+
+    fn naive_sum(scalars: &[Scalar], points: &[Point]) -> Point {
+        scalars.iter().zip(points.iter())
+            .fold(Point::identity(), |q, (k, p)| Point::add(&q, &Point::scalar_mul(&k.to_bytes(), p)))
+    }
+
+    #[test]
+    fn test_multiscalar_mul_small() {
+        let mut rng = StdRng::seed_from_u64(0x3A5);
+        for n in [0, 1, 2, 3, 16, 33] {
+            let (mut scalars, points) = random_inputs(n, &mut rng);
+            if n > 2 {
+                scalars[1] = Scalar::ZERO.clone();
+                scalars[2] = Scalar::MINUS_ONE.clone();
+            }
+            let expected = naive_sum(&scalars, &points);
+            assert!(vartime_straus(&scalars, &points).equal(&expected));
+            assert!(vartime_pippenger(&scalars, &points).equal(&expected));
+            assert!(Point::multiscalar_mul(&scalars, &points).equal(&expected));
+            assert!(Point::vartime_multiscalar_mul(&scalars, &points).equal(&expected));
+        }
+    }
+
+    // Large enough to take the Pippenger path in vartime_multiscalar_mul.
+    #[test]
+    fn test_multiscalar_mul_large() {
+        let mut rng = StdRng::seed_from_u64(0x9199);
+        let (scalars, points) = random_inputs(Point::PIPPENGER_THRESHOLD + 10, &mut rng);
+        let expected = naive_sum(&scalars, &points);
+        assert!(Point::vartime_multiscalar_mul(&scalars, &points).equal(&expected));
+        assert!(vartime_straus(&scalars, &points).equal(&expected));
+        assert!(Point::multiscalar_mul(&scalars, &points).equal(&expected));
+    }
+
+    // Every window size is exercised by varying the number of points.
+    #[test]
+    fn test_pippenger_window_sizes() {
+        let mut rng = StdRng::seed_from_u64(0x71B);
+        let (scalars, points) = random_inputs(1, &mut rng);
+        let expected = naive_sum(&scalars, &points);
+        for n in [499, 500, 800] {
+            // n copies of the same term
+            let ks: Vec<Scalar> = vec![scalars[0].clone(); n];
+            let ps: Vec<Point> = vec![points[0].clone(); n];
+            let mut n_bytes: [u8; 32] = [0; 32];
+            n_bytes[..8].copy_from_slice(&(n as u64).to_le_bytes());
+            assert!(vartime_pippenger(&ks, &ps).equal(&Point::scalar_mul(&n_bytes, &expected)));
+        }
+    }
+}
@@ -0,0 +1,358 @@
+#![allow(dead_code)]
+
+use crate::field_p256::Element;
+use crate::fp256;
+
+// P-256, the short-Weierstrass curve y^2 = x^3 - 3x + b over GF(2^256 - 2^224 + 2^192 + 2^96 - 1)
+// of NIST SP 800-186, Section 3.2.1.3, also known as secp256r1. Its group has prime order n,
+// so the cofactor is 1 and every point on the curve other than the identity generates it.
+//
+// Point is a point in homogeneous projective coordinates (X:Y:Z), x = X/Z and y = Y/Z, with the
+// identity (0:1:0). Addition and doubling use the complete formulas of Renes, Costello and
+// Batina, "Complete addition formulas for prime order elliptic curves" (EUROCRYPT 2016),
+// Algorithms 4 and 6 for a = -3: they are correct for all inputs, the identity and P + P
+// included, so the scalar multiplication has no exceptional cases and no branches.
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub(crate) x: Element,
+    pub(crate) y: Element,
+    pub(crate) z: Element,
+}
+
+pub const SCALAR_SIZE: usize = 32;
+pub const COMPRESSED_SIZE: usize = 33;
+pub const UNCOMPRESSED_SIZE: usize = 65;
+
+impl Point {
+    // b = 0x5ac635d8...27d2604b, in Montgomery form
+    pub(crate) const B: &'static Element = &Element::new([
+        0xd89cdf6229c4bddf, 0xacf005cd78843090, 0xe5a220abf7212ed6, 0xdc30061d04874834]);
+    // the generator G, in Montgomery form
+    const GX: &'static Element = &Element::new([
+        0x79e730d418a9143c, 0x75ba95fc5fedb601, 0x79fb732b77622510, 0x18905f76a53755c6]);
+    const GY: &'static Element = &Element::new([
+        0xddf25357ce95560a, 0x8b4ab8e4ba19e45c, 0xd2e88688dd21f325, 0x8571ff1825885d85]);
+    // n, the order of G, as plain little-endian limbs
+    pub const ORDER: [u64; 4] = [0xf3b9cac2fc632551, 0xbce6faada7179e84, 0xffffffffffffffff, 0xffffffff00000000];
+
+    pub fn identity() -> Point {
+        Point { x: Element::ZERO.clone(), y: Element::ONE.clone(), z: Element::ZERO.clone() }
+    }
+
+    pub fn generator() -> Point {
+        Point { x: Point::GX.clone(), y: Point::GY.clone(), z: Element::ONE.clone() }
+    }
+
+    // is_on_curve checks y^2 = x^3 - 3x + b for the affine (x, y).
+    pub fn is_on_curve(x: &Element, y: &Element) -> bool {
+        let x3 = Element::multiply(&Element::square(x), x);
+        let rhs = Element::add(&Element::subtract(&x3, &Element::mul_small(x, 3)), Point::B);
+        Element::square(y).equal(&rhs)
+    }
+
+    // from_affine returns the point (x, y), or an error if it is not on the curve.
+    pub fn from_affine(x: &Element, y: &Element) -> Result<Point, ()> {
+        if !Point::is_on_curve(x, y) {
+            return Err(());
+        }
+        Ok(Point { x: x.clone(), y: y.clone(), z: Element::ONE.clone() })
+    }
+
+    // to_affine returns (x, y), or an error for the identity, which has no affine coordinates.
+    pub fn to_affine(&self) -> Result<(Element, Element), ()> {
+        if self.is_identity() {
+            return Err(());
+        }
+        let z_inv = Element::invert(&self.z);
+        Ok((Element::multiply(&self.x, &z_inv), Element::multiply(&self.y, &z_inv)))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero() == 1
+    }
+
+    // Checks whether two points are equal: X1*Z2 == X2*Z1 and Y1*Z2 == Y2*Z1.
+    pub fn equal(&self, q: &Point) -> bool {
+        Element::multiply(&self.x, &q.z).equal(&Element::multiply(&q.x, &self.z))
+            && Element::multiply(&self.y, &q.z).equal(&Element::multiply(&q.y, &self.z))
+    }
+
+    pub fn negate(p: &Point) -> Point {
+        Point { x: p.x.clone(), y: Element::negate(&p.y), z: p.z.clone() }
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Point, b: &Point, cond: u32) {
+        self.x.select(&a.x, &b.x, cond);
+        self.y.select(&a.y, &b.y, cond);
+        self.z.select(&a.z, &b.z, cond);
+    }
+
+    // add returns p + q with Algorithm 4 of Renes-Costello-Batina: 12M + 2mb + 29a.
+    pub fn add(p: &Point, q: &Point) -> Point {
+        let b = Point::B;
+        let t0 = Element::multiply(&p.x, &q.x);
+        let t1 = Element::multiply(&p.y, &q.y);
+        let t2 = Element::multiply(&p.z, &q.z);
+        let t3 = Element::multiply(&Element::add(&p.x, &p.y), &Element::add(&q.x, &q.y));
+        let t3 = Element::subtract(&t3, &Element::add(&t0, &t1));
+        let t4 = Element::multiply(&Element::add(&p.y, &p.z), &Element::add(&q.y, &q.z));
+        let t4 = Element::subtract(&t4, &Element::add(&t1, &t2));
+        let x3 = Element::multiply(&Element::add(&p.x, &p.z), &Element::add(&q.x, &q.z));
+        let y3 = Element::subtract(&x3, &Element::add(&t0, &t2));
+        let x3 = Element::subtract(&y3, &Element::multiply(b, &t2));
+        let x3 = Element::add(&x3, &Element::add(&x3, &x3));
+        let z3 = Element::subtract(&t1, &x3);
+        let x3 = Element::add(&t1, &x3);
+        let y3 = Element::multiply(b, &y3);
+        let t2 = Element::add(&t2, &Element::add(&t2, &t2));
+        let y3 = Element::subtract(&Element::subtract(&y3, &t2), &t0);
+        let y3 = Element::add(&y3, &Element::add(&y3, &y3));
+        let t0 = Element::subtract(&Element::add(&t0, &Element::add(&t0, &t0)), &t2);
+        let t1 = Element::multiply(&t4, &y3);
+        let t2 = Element::multiply(&t0, &y3);
+        let y3 = Element::add(&Element::multiply(&x3, &z3), &t2);
+        let x3 = Element::subtract(&Element::multiply(&t3, &x3), &t1);
+        let z3 = Element::add(&Element::multiply(&t4, &z3), &Element::multiply(&t3, &t0));
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    // double returns 2p with Algorithm 6 of Renes-Costello-Batina: 8M + 3S + 2mb + 21a.
+    pub fn double(p: &Point) -> Point {
+        let b = Point::B;
+        let t0 = Element::square(&p.x);
+        let t1 = Element::square(&p.y);
+        let t2 = Element::square(&p.z);
+        let t3 = Element::multiply(&p.x, &p.y);
+        let t3 = Element::add(&t3, &t3);
+        let z3 = Element::multiply(&p.x, &p.z);
+        let z3 = Element::add(&z3, &z3);
+        let y3 = Element::subtract(&Element::multiply(b, &t2), &z3);
+        let y3 = Element::add(&y3, &Element::add(&y3, &y3));
+        let x3 = Element::subtract(&t1, &y3);
+        let y3 = Element::multiply(&x3, &Element::add(&t1, &y3));
+        let x3 = Element::multiply(&x3, &t3);
+        let t2 = Element::add(&t2, &Element::add(&t2, &t2));
+        let z3 = Element::subtract(&Element::subtract(&Element::multiply(b, &z3), &t2), &t0);
+        let z3 = Element::add(&z3, &Element::add(&z3, &z3));
+        let t0 = Element::subtract(&Element::add(&t0, &Element::add(&t0, &t0)), &t2);
+        let y3 = Element::add(&y3, &Element::multiply(&t0, &z3));
+        let t0 = Element::multiply(&p.y, &p.z);
+        let t0 = Element::add(&t0, &t0);
+        let x3 = Element::subtract(&x3, &Element::multiply(&t0, &z3));
+        let z3 = Element::multiply(&t0, &t1);
+        let z3 = Element::add(&z3, &z3);
+        let z3 = Element::add(&z3, &z3);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    // scalar_mul returns [k]P for a big-endian k of 32 octets. It doubles and adds for every
+    // bit, and keeps the sum only if the bit is set; with complete formulas, its running time
+    // does not depend on k or P.
+    pub fn scalar_mul(k: &[u8; SCALAR_SIZE], p: &Point) -> Point {
+        let mut q = Point::identity();
+        for byte in k {
+            for i in (0..8).rev() {
+                q = Point::double(&q);
+                let sum = Point::add(&q, p);
+                let prev = q.clone();
+                q.select(&sum, &prev, ((byte >> i) & 1) as u32);
+            }
+        }
+        q
+    }
+
+    pub fn basepoint_mul(k: &[u8; SCALAR_SIZE]) -> Point {
+        Point::scalar_mul(k, &Point::generator())
+    }
+
+    // to_uncompressed returns the SEC1 encoding 0x04 || x || y [SEC1, 2.3.3].
+    pub fn to_uncompressed(&self) -> Result<[u8; UNCOMPRESSED_SIZE], ()> {
+        let (x, y) = self.to_affine()?;
+        let mut b: [u8; UNCOMPRESSED_SIZE] = [0; UNCOMPRESSED_SIZE];
+        b[0] = 0x04;
+        b[1..33].copy_from_slice(&x.to_be_bytes());
+        b[33..].copy_from_slice(&y.to_be_bytes());
+        Ok(b)
+    }
+
+    // to_compressed returns the SEC1 encoding (0x02 + (y mod 2)) || x.
+    pub fn to_compressed(&self) -> Result<[u8; COMPRESSED_SIZE], ()> {
+        let (x, y) = self.to_affine()?;
+        let mut b: [u8; COMPRESSED_SIZE] = [0; COMPRESSED_SIZE];
+        b[0] = 0x02 | y.is_odd() as u8;
+        b[1..].copy_from_slice(&x.to_be_bytes());
+        Ok(b)
+    }
+
+    // from_sec1 decodes a compressed or uncompressed SEC1 encoding [SEC1, 2.3.4] and validates
+    // the point: the coordinates must be below p and satisfy the curve equation. The encoding
+    // 0x00 of the identity is rejected; with a cofactor of 1, every accepted point has order n.
+    pub fn from_sec1(b: &[u8]) -> Result<Point, ()> {
+        match (b.first(), b.len()) {
+            (Some(0x04), UNCOMPRESSED_SIZE) => {
+                let x = Element::from_be_bytes(&b[1..33].try_into().unwrap())?;
+                let y = Element::from_be_bytes(&b[33..].try_into().unwrap())?;
+                Point::from_affine(&x, &y)
+            }
+            (Some(0x02 | 0x03), COMPRESSED_SIZE) => {
+                let x = Element::from_be_bytes(&b[1..].try_into().unwrap())?;
+                let x3 = Element::multiply(&Element::square(&x), &x);
+                let rhs = Element::add(&Element::subtract(&x3, &Element::mul_small(&x, 3)), Point::B);
+                let (y, was_square) = Element::sqrt(&rhs);
+                if was_square == 0 {
+                    return Err(());
+                }
+                let mut y_signed = Element::ZERO.clone();
+                y_signed.select(&Element::negate(&y), &y, y.is_odd() ^ (b[0] & 1) as u32);
+                Point::from_affine(&x, &y_signed)
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+// is_valid_scalar returns true if the big-endian k is in [1, n - 1].
+fn is_valid_scalar(k: &[u8; SCALAR_SIZE]) -> bool {
+    let limbs = fp256::from_be_bytes(k);
+    fp256::is_zero(&limbs) == 0 && fp256::is_less_than(&limbs, &Point::ORDER) == 1
+}
+
+// ECDH on P-256 [SP 800-56A, 5.7.1.2]: the shared secret is the x-coordinate of [d]Q.
+pub struct PrivateKey {
+    private: [u8; SCALAR_SIZE],
+}
+
+#[derive(Clone, Debug)]
+pub struct PublicKey {
+    point: Point,
+}
+
+impl PrivateKey {
+    // new accepts a big-endian private key d in [1, n - 1].
+    pub fn new(key: [u8; SCALAR_SIZE]) -> Result<PrivateKey, ()> {
+        if !is_valid_scalar(&key) {
+            return Err(());
+        }
+        Ok(PrivateKey { private: key })
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey { point: Point::basepoint_mul(&self.private) }
+    }
+
+    // ecdh returns the x-coordinate of [d]Q. The peer key was validated when it was decoded,
+    // and a valid Q times d in [1, n - 1] is never the identity; the check is kept anyway.
+    pub fn ecdh(&self, peer: &PublicKey) -> Result<[u8; 32], ()> {
+        let (x, _) = Point::scalar_mul(&self.private, &peer.point).to_affine()?;
+        Ok(x.to_be_bytes())
+    }
+}
+
+impl PublicKey {
+    // from_sec1 decodes and validates a public key [SP 800-56A, 5.6.2.3.3].
+    pub fn from_sec1(b: &[u8]) -> Result<PublicKey, ()> {
+        Ok(PublicKey { point: Point::from_sec1(b)? })
+    }
+
+    pub fn to_uncompressed(&self) -> [u8; UNCOMPRESSED_SIZE] {
+        self.point.to_uncompressed().unwrap()
+    }
+
+    pub fn to_compressed(&self) -> [u8; COMPRESSED_SIZE] {
+        self.point.to_compressed().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod p256_test {
+    use crate::p256::{Point, PrivateKey, PublicKey, SCALAR_SIZE};
+    use crate::test_util::hex_decode;
+
+    fn uncompressed(x_hex: &str, y_hex: &str) -> Vec<u8> {
+        hex_decode(&format!("04{}{}", x_hex, y_hex))
+    }
+
+    // NIST CAVP ECC CDH primitive test vectors (KAS_ECC_CDH_PrimitiveTest.txt), the whole
+    // P-256 section.
+    #[test]
+    fn test_ecdh_cavp() {
+        let text = include_str!("../testdata/kas-ecc-cdh-p256.txt");
+        let values: Vec<&str> = text.lines()
+            .filter_map(|line| line.split_once(" = "))
+            .filter(|(k, _)| *k != "COUNT")
+            .map(|(_, v)| v)
+            .collect();
+        assert_eq!(values.len(), 25 * 6);
+        for v in values.chunks(6) {
+            let [qcavs_x, qcavs_y, d_iut, qiut_x, qiut_y, z_iut] = [v[0], v[1], v[2], v[3], v[4], v[5]];
+            let peer = PublicKey::from_sec1(&uncompressed(qcavs_x, qcavs_y)).unwrap();
+            let sk = PrivateKey::new(hex_decode(d_iut).try_into().unwrap()).unwrap();
+            assert_eq!(sk.public_key().to_uncompressed().to_vec(), uncompressed(qiut_x, qiut_y));
+            assert_eq!(sk.ecdh(&peer).unwrap().to_vec(), hex_decode(z_iut));
+
+            // the compressed encoding decodes to the same point
+            let compressed = peer.to_compressed();
+            assert_eq!(&compressed[1..], hex_decode(qcavs_x).as_slice());
+            assert_eq!(PublicKey::from_sec1(&compressed).unwrap().to_uncompressed(), peer.to_uncompressed());
+        }
+    }
+
+    // The complete formulas agree with each other on the special cases.
+    #[test]
+    fn test_group_law() {
+        let g = Point::generator();
+        let g2 = Point::double(&g);
+        assert!(Point::add(&g, &g).equal(&g2));
+        assert!(Point::add(&g, &Point::identity()).equal(&g));
+        assert!(Point::add(&Point::identity(), &Point::identity()).is_identity());
+        assert!(Point::double(&Point::identity()).is_identity());
+        assert!(Point::add(&g, &Point::negate(&g)).is_identity());
+
+        let mut three: [u8; SCALAR_SIZE] = [0; SCALAR_SIZE];
+        three[31] = 3;
+        assert!(Point::basepoint_mul(&three).equal(&Point::add(&g2, &g)));
+
+        // [n]G is the identity, and [n - 1]G = -G
+        let mut n: [u8; SCALAR_SIZE] = hex_decode("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551")
+            .try_into().unwrap();
+        assert!(Point::basepoint_mul(&n).is_identity());
+        n[31] -= 1;
+        assert!(Point::basepoint_mul(&n).equal(&Point::negate(&g)));
+    }
+
+    // Public keys must be on the curve with canonical coordinates; private keys in [1, n - 1].
+    #[test]
+    fn test_validation() {
+        let x = "700c48f77f56584c5cc632ca65640db91b6bacce3a4df6b42ce7cc838833d287";
+        let y = "db71e509e3fd9b060ddb20ba5c51dcc5948d46fbf640dfe0441782cab85fa4ac";
+        let valid = uncompressed(x, y);
+        assert!(PublicKey::from_sec1(&valid).is_ok());
+
+        let mut off_curve = valid.clone();
+        off_curve[64] ^= 1;
+        assert!(PublicKey::from_sec1(&off_curve).is_err());
+        assert!(PublicKey::from_sec1(&valid[..64]).is_err());
+        let mut hybrid = valid.clone();
+        hybrid[0] = 0x06;
+        assert!(PublicKey::from_sec1(&hybrid).is_err());
+        assert!(PublicKey::from_sec1(&[0x00]).is_err());
+        assert!(PublicKey::from_sec1(&[]).is_err());
+
+        // x = p is not a field element; x = 1 has no point (b - 2 is not a square)
+        let p = "ffffffff00000001000000000000000000000000ffffffffffffffffffffffff";
+        assert!(PublicKey::from_sec1(&hex_decode(&format!("02{}", p))).is_err());
+        assert!(PublicKey::from_sec1(&uncompressed(p, y)).is_err());
+        assert!(PublicKey::from_sec1(&hex_decode(
+            "020000000000000000000000000000000000000000000000000000000000000001")).is_err());
+
+        assert!(PrivateKey::new([0; SCALAR_SIZE]).is_err());
+        let n: [u8; SCALAR_SIZE] = hex_decode("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551")
+            .try_into().unwrap();
+        assert!(PrivateKey::new(n).is_err());
+        assert!(PrivateKey::new([0xFF; SCALAR_SIZE]).is_err());
+        let mut n_minus_1 = n;
+        n_minus_1[31] -= 1;
+        assert!(PrivateKey::new(n_minus_1).is_ok());
+    }
+}
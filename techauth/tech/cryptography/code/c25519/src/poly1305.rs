@@ -0,0 +1,262 @@
+#![allow(dead_code)]
+
+use crate::bits::{self, Uint128};
+use crate::subtle;
+
+// Poly1305, the one-time authenticator of RFC 8439, Section 2.5. The message is split into
+// 16-octet blocks, each read as a little-endian integer with a 1 appended above its last
+// octet, and the accumulator is updated as
+//     h = (h + block) * r (mod 2^130 - 5);
+// the tag is (h + s) mod 2^128. The key (r, s) must never be used for two messages.
+//
+// h and r are held in three limbs of 44, 44 and 42 bits, the radix-2^51 technique of
+// field.rs applied to 2^130 - 5: the product of two limbs fits in a Uint128 with room for
+// the sum of three, and a carry out of the top limb at 2^130 re-enters the bottom one
+// multiplied by 5. Clamping clears the low two bits of r1 and r2, so the wrapped terms
+// r1 * 5 * 2^2 and r2 * 5 * 2^2 can be precomputed as s1 and s2.
+
+pub const KEY_SIZE: usize = 32;
+pub const TAG_SIZE: usize = 16;
+const BLOCK_SIZE: usize = 16;
+
+const MASK_44BITS: u64 = (1 << 44) - 1;
+const MASK_42BITS: u64 = (1 << 42) - 1;
+
+pub struct Poly1305 {
+    r: [u64; 3],
+    // s1 = 20 * r1 and s2 = 20 * r2
+    s: [u64; 2],
+    // the final addend, as two little-endian words
+    pad: [u64; 2],
+    h: [u64; 3],
+    buffer: [u8; BLOCK_SIZE],
+    buffered: usize,
+}
+
+// shift_right_44 returns v >> 44 for a value below 2^108, as a u64.
+fn shift_right_44(v: &Uint128) -> u64 {
+    (v.lo >> 44) | (v.hi << 20)
+}
+
+// add_u64 returns v + x.
+fn add_u64(v: Uint128, x: u64) -> Uint128 {
+    let (lo, c) = bits::add64(v.lo, x, 0);
+    let (hi, _) = bits::add64(v.hi, 0, c);
+    Uint128 { lo, hi }
+}
+
+impl Poly1305 {
+    // new splits the 32-octet one-time key into r, which it clamps [RFC 8439, 2.5.1], and s.
+    pub fn new(key: &[u8; KEY_SIZE]) -> Poly1305 {
+        let t0 = u64::from_le_bytes(key[0..8].try_into().unwrap());
+        let t1 = u64::from_le_bytes(key[8..16].try_into().unwrap());
+        let r0 = t0 & 0xffc0fffffff;
+        let r1 = ((t0 >> 44) | (t1 << 20)) & 0xfffffc0ffff;
+        let r2 = (t1 >> 24) & 0x00ffffffc0f;
+        Poly1305 {
+            r: [r0, r1, r2],
+            s: [r1 * 20, r2 * 20],
+            pad: [
+                u64::from_le_bytes(key[16..24].try_into().unwrap()),
+                u64::from_le_bytes(key[24..32].try_into().unwrap())],
+            h: [0; 3],
+            buffer: [0; BLOCK_SIZE],
+            buffered: 0,
+        }
+    }
+
+    // block adds a 16-octet block, with hibit = 2^128 above it for a full block and 0 for the
+    // padded last one, and multiplies by r.
+    fn block(&mut self, m: &[u8; BLOCK_SIZE], hibit: u64) {
+        let [r0, r1, r2] = self.r;
+        let [s1, s2] = self.s;
+        let t0 = u64::from_le_bytes(m[0..8].try_into().unwrap());
+        let t1 = u64::from_le_bytes(m[8..16].try_into().unwrap());
+        let h0 = self.h[0] + (t0 & MASK_44BITS);
+        let h1 = self.h[1] + (((t0 >> 44) | (t1 << 20)) & MASK_44BITS);
+        let h2 = self.h[2] + (((t1 >> 24) & MASK_42BITS) | hibit);
+
+        // h * r, with the columns above 2^130 folded back in through s1 and s2
+        let d0 = bits::add_mul64(bits::add_mul64(bits::mul64(h0, r0), h1, s2), h2, s1);
+        let d1 = bits::add_mul64(bits::add_mul64(bits::mul64(h0, r1), h1, r0), h2, s2);
+        let d2 = bits::add_mul64(bits::add_mul64(bits::mul64(h0, r2), h1, r1), h2, r0);
+
+        // a partial carry chain leaves h0 and h1 at most 44 bits and h2 a little over 42
+        let c = shift_right_44(&d0);
+        let h0 = d0.lo & MASK_44BITS;
+        let d1 = add_u64(d1, c);
+        let c = shift_right_44(&d1);
+        let h1 = d1.lo & MASK_44BITS;
+        let d2 = add_u64(d2, c);
+        let c = (d2.lo >> 42) | (d2.hi << 22);
+        let h2 = d2.lo & MASK_42BITS;
+        let h0 = h0 + c * 5;
+        let c = h0 >> 44;
+        self.h = [h0 & MASK_44BITS, h1 + c, h2];
+    }
+
+    // update absorbs data of any length, buffering a partial block until it is completed.
+    pub fn update(&mut self, data: &[u8]) {
+        let mut data = data;
+        if self.buffered > 0 {
+            let n = (BLOCK_SIZE - self.buffered).min(data.len());
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+            if self.buffered < BLOCK_SIZE {
+                return;
+            }
+            let m = self.buffer;
+            self.block(&m, 1 << 40);
+            self.buffered = 0;
+        }
+        let mut chunks = data.chunks_exact(BLOCK_SIZE);
+        for m in &mut chunks {
+            self.block(m.try_into().unwrap(), 1 << 40);
+        }
+        let rest = chunks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    // finalize pads a last partial block with a 1 and zeros, reduces h fully modulo
+    // 2^130 - 5, and returns (h + s) mod 2^128 in little-endian order.
+    pub fn finalize(mut self) -> [u8; TAG_SIZE] {
+        if self.buffered > 0 {
+            let mut m: [u8; BLOCK_SIZE] = [0; BLOCK_SIZE];
+            m[..self.buffered].copy_from_slice(&self.buffer[..self.buffered]);
+            m[self.buffered] = 1;
+            self.block(&m, 0);
+        }
+
+        let [mut h0, mut h1, mut h2] = self.h;
+        let mut c;
+        for _ in 0..2 {
+            c = h1 >> 44;
+            h1 &= MASK_44BITS;
+            h2 += c;
+            c = h2 >> 42;
+            h2 &= MASK_42BITS;
+            h0 += c * 5;
+            c = h0 >> 44;
+            h0 &= MASK_44BITS;
+            h1 += c;
+        }
+
+        // g = h + 5 - 2^130; h < 2^130 - 5 exactly when g underflows
+        let mut g0 = h0 + 5;
+        c = g0 >> 44;
+        g0 &= MASK_44BITS;
+        let mut g1 = h1 + c;
+        c = g1 >> 44;
+        g1 &= MASK_44BITS;
+        let g2 = (h2 + c).wrapping_sub(1 << 42);
+        let mask = (g2 >> 63).wrapping_sub(1);
+        h0 = (h0 & !mask) | (g0 & mask);
+        h1 = (h1 & !mask) | (g1 & mask);
+        h2 = (h2 & !mask) | (g2 & mask);
+
+        // h + s, dropping the bits from 2^128 up
+        let [t0, t1] = self.pad;
+        h0 += t0 & MASK_44BITS;
+        c = h0 >> 44;
+        h0 &= MASK_44BITS;
+        h1 += (((t0 >> 44) | (t1 << 20)) & MASK_44BITS) + c;
+        c = h1 >> 44;
+        h1 &= MASK_44BITS;
+        h2 += ((t1 >> 24) & MASK_42BITS) + c;
+
+        let mut tag: [u8; TAG_SIZE] = [0; TAG_SIZE];
+        tag[..8].copy_from_slice(&(h0 | (h1 << 44)).to_le_bytes());
+        tag[8..].copy_from_slice(&((h1 >> 20) | (h2 << 24)).to_le_bytes());
+        tag
+    }
+
+    // verify compares the tag of the absorbed data with an expected tag in constant time.
+    pub fn verify(self, tag: &[u8; TAG_SIZE]) -> Result<(), ()> {
+        if !subtle::constant_time_compare(&self.finalize(), tag) {
+            return Err(());
+        }
+        Ok(())
+    }
+}
+
+// mac returns the tag of a whole message.
+pub fn mac(key: &[u8; KEY_SIZE], message: &[u8]) -> [u8; TAG_SIZE] {
+    let mut p = Poly1305::new(key);
+    p.update(message);
+    p.finalize()
+}
+
+pub fn verify(key: &[u8; KEY_SIZE], message: &[u8], tag: &[u8; TAG_SIZE]) -> Result<(), ()> {
+    let mut p = Poly1305::new(key);
+    p.update(message);
+    p.verify(tag)
+}
+
+#[cfg(test)]
+mod poly1305_test {
+    use crate::poly1305::{self, Poly1305, KEY_SIZE, TAG_SIZE};
+    use crate::test_util::hex_decode;
+
+    // (key, input, tag) from a file of "Key = ", "Input = " and "MAC = " lines.
+    fn vectors(text: &str) -> Vec<([u8; KEY_SIZE], Vec<u8>, [u8; TAG_SIZE])> {
+        let mut values = text.lines().filter_map(|line| line.split_once(" = ")).map(|(_, v)| v);
+        let mut vectors = vec![];
+        while let (Some(key), Some(input), Some(tag)) = (values.next(), values.next(), values.next()) {
+            vectors.push((hex_decode(key).try_into().unwrap(), hex_decode(input),
+                          hex_decode(tag).try_into().unwrap()));
+        }
+        vectors
+    }
+
+    // RFC 8439, Section 2.5.2.
+    #[test]
+    fn test_rfc8439_example() {
+        let key: [u8; KEY_SIZE] = hex_decode("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b")
+            .try_into().unwrap();
+        let tag = poly1305::mac(&key, b"Cryptographic Forum Research Group");
+        assert_eq!(tag.to_vec(), hex_decode("a8061dc1305136c6c22b8baf0c0127a9"));
+    }
+
+    // RFC 8439, Appendix A.3: vectors 1-4 are ordinary messages, 5-11 exercise the carries
+    // and the final reduction.
+    #[test]
+    fn test_rfc8439_vectors() {
+        let vectors = vectors(include_str!("../testdata/poly1305-rfc8439-a3.txt"));
+        assert_eq!(vectors.len(), 11);
+        for (i, (key, input, tag)) in vectors.iter().enumerate() {
+            assert_eq!(poly1305::mac(key, input), *tag, "vector {}", i + 1);
+            assert!(poly1305::verify(key, input, tag).is_ok());
+        }
+    }
+
+    // Splitting a message across update calls at any point gives the same tag.
+    #[test]
+    fn test_incremental() {
+        let (key, input, tag) = &vectors(include_str!("../testdata/poly1305-rfc8439-a3.txt"))[3];
+        for split in 0..=input.len() {
+            let mut p = Poly1305::new(key);
+            p.update(&input[..split]);
+            p.update(&[]);
+            p.update(&input[split..]);
+            assert_eq!(p.finalize(), *tag);
+        }
+        let mut p = Poly1305::new(key);
+        for byte in input {
+            p.update(&[*byte]);
+        }
+        assert_eq!(p.finalize(), *tag);
+    }
+
+    #[test]
+    fn test_verify() {
+        let (key, input, tag) = &vectors(include_str!("../testdata/poly1305-rfc8439-a3.txt"))[3];
+        for i in 0..TAG_SIZE * 8 {
+            let mut bad = *tag;
+            bad[i / 8] ^= 1 << (i % 8);
+            assert!(poly1305::verify(key, input, &bad).is_err());
+        }
+        assert!(poly1305::verify(key, &input[1..], tag).is_err());
+    }
+}
@@ -0,0 +1,297 @@
+#![allow(dead_code)]
+
+use core::fmt::Debug;
+
+// PrimeField is the interface of an element of a prime field GF(p) that curve code can be
+// written against, instead of a concrete Element. It mirrors the static-method style of the
+// field modules: PrimeField::add(&a, &b) rather than a.add(&b).
+//
+// Implementations must be constant time in the values of the elements, as the concrete
+// fields are. Conditions are u32 values of 0 or 1, as for Element::select.
+//
+// Each field has a single canonical encoding of ENCODED_SIZE octets, the integer in [0, p)
+// in the byte order of its standard (little-endian for 2^255-19, big-endian for the NIST
+// fields); from_canonical_bytes rejects every other string. The big-endian form, which SEC1
+// uses whatever the field, is derived from it.
+pub trait PrimeField: Clone + Debug + Sized {
+    const ENCODED_SIZE: usize;
+    // the byte order of the canonical encoding
+    const LITTLE_ENDIAN: bool;
+    // p, as a big-endian integer
+    const CHARACTERISTIC: &'static [u8];
+
+    fn zero() -> Self;
+    fn one() -> Self;
+    // from_u64 returns the element k mod p, for constants such as curve coefficients.
+    fn from_u64(k: u64) -> Self;
+
+    fn add(a: &Self, b: &Self) -> Self;
+    fn sub(a: &Self, b: &Self) -> Self;
+    fn neg(a: &Self) -> Self;
+    fn mul(a: &Self, b: &Self) -> Self;
+    fn square(a: &Self) -> Self {
+        Self::mul(a, a)
+    }
+    // invert returns 1/a, and 0 for a = 0.
+    fn invert(a: &Self) -> Self;
+    // sqrt returns (r, 1) with r^2 = a if a is a square, and (r, 0) with an unspecified r
+    // otherwise. Zero is a square.
+    fn sqrt(a: &Self) -> (Self, u32);
+    fn is_square(a: &Self) -> u32 {
+        Self::sqrt(a).1
+    }
+
+    fn to_canonical_bytes(&self) -> Vec<u8>;
+    fn from_canonical_bytes(b: &[u8]) -> Result<Self, ()>;
+
+    // to_be_bytes returns the integer in [0, p) as ENCODED_SIZE big-endian octets.
+    fn to_be_bytes(&self) -> Vec<u8> {
+        let mut b = self.to_canonical_bytes();
+        if Self::LITTLE_ENDIAN {
+            b.reverse();
+        }
+        b
+    }
+
+    fn from_be_bytes(b: &[u8]) -> Result<Self, ()> {
+        let mut b = b.to_vec();
+        if Self::LITTLE_ENDIAN {
+            b.reverse();
+        }
+        Self::from_canonical_bytes(&b)
+    }
+
+    // returns 1 if the integer in [0, p) is odd, and 0 otherwise.
+    fn is_odd(a: &Self) -> u32 {
+        (a.to_be_bytes()[Self::ENCODED_SIZE - 1] & 1) as u32
+    }
+
+    // returns 1 if a and b are equal, and 0 otherwise.
+    fn ct_equal(a: &Self, b: &Self) -> u32;
+    fn is_zero(a: &Self) -> u32 {
+        Self::ct_equal(a, &Self::zero())
+    }
+    // select returns a if cond == 1, and b if cond == 0.
+    fn select(a: &Self, b: &Self, cond: u32) -> Self;
+}
+
+// The conformance suite that every PrimeField implementation runs from its own tests. It
+// checks the field axioms on random elements, the square roots, the constant-time helpers and
+// the canonical encoding, without knowing p.
+#[cfg(test)]
+pub(crate) mod conformance {
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    use crate::prime_field::PrimeField;
+
+    fn eq<F: PrimeField>(a: &F, b: &F) -> bool {
+        F::ct_equal(a, b) == 1
+    }
+
+    // random returns a uniform element by rejection sampling of random encodings.
+    pub(crate) fn random<F: PrimeField>(rng: &mut StdRng) -> F {
+        let mut b = vec![0; F::ENCODED_SIZE];
+        loop {
+            rng.fill_bytes(&mut b);
+            if let Ok(a) = F::from_canonical_bytes(&b) {
+                return a;
+            }
+        }
+    }
+
+    pub(crate) fn check_constants<F: PrimeField>() {
+        assert!(!eq(&F::zero(), &F::one()));
+        assert!(eq(&F::from_u64(0), &F::zero()));
+        assert!(eq(&F::from_u64(1), &F::one()));
+        assert!(eq(&F::from_u64(2), &F::add(&F::one(), &F::one())));
+        assert!(eq(&F::from_u64(6), &F::mul(&F::from_u64(2), &F::from_u64(3))));
+        assert_eq!(F::is_zero(&F::zero()), 1);
+        assert_eq!(F::is_zero(&F::one()), 0);
+        assert!(eq(&F::invert(&F::zero()), &F::zero()));
+        assert!(eq(&F::invert(&F::one()), &F::one()));
+        assert!(eq(&F::neg(&F::zero()), &F::zero()));
+    }
+
+    pub(crate) fn check_axioms<F: PrimeField>(seed: u64, iterations: usize) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..iterations {
+            let (a, b, c): (F, F, F) = (random(&mut rng), random(&mut rng), random(&mut rng));
+
+            // commutative groups under + and *
+            assert!(eq(&F::add(&a, &b), &F::add(&b, &a)));
+            assert!(eq(&F::mul(&a, &b), &F::mul(&b, &a)));
+            assert!(eq(&F::add(&F::add(&a, &b), &c), &F::add(&a, &F::add(&b, &c))));
+            assert!(eq(&F::mul(&F::mul(&a, &b), &c), &F::mul(&a, &F::mul(&b, &c))));
+            assert!(eq(&F::add(&a, &F::zero()), &a));
+            assert!(eq(&F::mul(&a, &F::one()), &a));
+            assert!(eq(&F::mul(&a, &F::zero()), &F::zero()));
+            assert!(eq(&F::add(&a, &F::neg(&a)), &F::zero()));
+            assert!(eq(&F::sub(&a, &b), &F::add(&a, &F::neg(&b))));
+            assert!(eq(&F::sub(&F::add(&a, &b), &b), &a));
+
+            // distributivity
+            assert!(eq(&F::mul(&a, &F::add(&b, &c)), &F::add(&F::mul(&a, &b), &F::mul(&a, &c))));
+            assert!(eq(&F::square(&a), &F::mul(&a, &a)));
+
+            // inverses
+            if F::is_zero(&a) == 0 {
+                assert!(eq(&F::mul(&a, &F::invert(&a)), &F::one()));
+                assert!(eq(&F::invert(&F::invert(&a)), &a));
+            }
+        }
+    }
+
+    pub(crate) fn check_sqrt<F: PrimeField>(seed: u64, iterations: usize) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (r, was_square) = F::sqrt(&F::zero());
+        assert_eq!(was_square, 1);
+        assert!(eq(&r, &F::zero()));
+
+        let mut non_squares = 0;
+        for _ in 0..iterations {
+            let a: F = random(&mut rng);
+            let a2 = F::square(&a);
+            let (r, was_square) = F::sqrt(&a2);
+            assert_eq!(was_square, 1);
+            assert!(eq(&F::square(&r), &a2));
+            assert_eq!(F::is_square(&a2), 1);
+
+            // a root that is reported is a root, and a non-square has none
+            let (r, was_square) = F::sqrt(&a);
+            assert_eq!(was_square, F::is_square(&a));
+            assert_eq!(eq(&F::square(&r), &a), was_square == 1);
+            non_squares += 1 - was_square;
+        }
+        // about half of the elements are not squares, unless p = 2
+        if iterations >= 64 {
+            assert!(non_squares > 0);
+        }
+    }
+
+    pub(crate) fn check_select<F: PrimeField>(seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (a, b): (F, F) = (random(&mut rng), random(&mut rng));
+        assert!(eq(&F::select(&a, &b, 1), &a));
+        assert!(eq(&F::select(&a, &b, 0), &b));
+        assert_eq!(F::ct_equal(&a, &a.clone()), 1);
+        if !eq(&a, &b) {
+            assert_eq!(F::ct_equal(&a, &b), 0);
+        }
+    }
+
+    pub(crate) fn check_encoding<F: PrimeField>(seed: u64, iterations: usize) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        for _ in 0..iterations {
+            let a: F = random(&mut rng);
+            let b = a.to_canonical_bytes();
+            assert_eq!(b.len(), F::ENCODED_SIZE);
+            assert!(eq(&F::from_canonical_bytes(&b).unwrap(), &a));
+            assert_eq!(F::from_canonical_bytes(&b).unwrap().to_canonical_bytes(), b);
+        }
+
+        // -1 encodes p - 1, the largest canonical value
+        let minus_one = F::neg(&F::one()).to_canonical_bytes();
+        assert!(eq(&F::from_canonical_bytes(&minus_one).unwrap(), &F::neg(&F::one())));
+
+        // the big-endian form ends with the least significant octet, and p is odd
+        let one = F::one().to_be_bytes();
+        assert_eq!(one[F::ENCODED_SIZE - 1], 1);
+        assert!(one[..F::ENCODED_SIZE - 1].iter().all(|&x| x == 0));
+        assert!(eq(&F::from_be_bytes(&one).unwrap(), &F::one()));
+        assert_eq!((F::is_odd(&F::one()), F::is_odd(&F::zero()), F::is_odd(&F::from_u64(2))), (1, 0, 0));
+        assert_eq!(F::is_odd(&F::neg(&F::one())), 0);
+        for _ in 0..iterations {
+            let a: F = random(&mut rng);
+            assert!(eq(&F::from_be_bytes(&a.to_be_bytes()).unwrap(), &a));
+            if F::is_zero(&a) == 0 {
+                assert_eq!(F::is_odd(&a) ^ F::is_odd(&F::neg(&a)), 1);
+            }
+        }
+
+        // p itself is not canonical, and p - 1 is -1
+        let mut p = vec![0; F::ENCODED_SIZE - F::CHARACTERISTIC.len()];
+        p.extend_from_slice(F::CHARACTERISTIC);
+        assert!(F::from_be_bytes(&p).is_err());
+        p[F::ENCODED_SIZE - 1] -= 1;
+        assert!(eq(&F::from_be_bytes(&p).unwrap(), &F::neg(&F::one())));
+
+        // all-ones is at least p, and the length must be exact
+        assert!(F::from_canonical_bytes(&vec![0xFF; F::ENCODED_SIZE]).is_err());
+        assert!(F::from_canonical_bytes(&vec![0; F::ENCODED_SIZE - 1]).is_err());
+        assert!(F::from_canonical_bytes(&vec![0; F::ENCODED_SIZE + 1]).is_err());
+    }
+
+    // check_all runs the whole suite.
+    pub(crate) fn check_all<F: PrimeField>(seed: u64) {
+        check_constants::<F>();
+        check_axioms::<F>(seed, 64);
+        check_sqrt::<F>(seed + 1, 64);
+        check_select::<F>(seed + 2);
+        check_encoding::<F>(seed + 3, 64);
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod gf251 {
+    use crate::prime_field::PrimeField;
+
+    // Gf251 is GF(251), small enough to enumerate, for the tests of generic curve and field
+    // code. It is not constant time.
+    #[derive(Clone, Debug, PartialEq)]
+    pub(crate) struct Gf251(pub(crate) u64);
+
+    pub(crate) const P: u64 = 251;
+
+    impl PrimeField for Gf251 {
+        const ENCODED_SIZE: usize = 1;
+        const LITTLE_ENDIAN: bool = false;
+        const CHARACTERISTIC: &'static [u8] = &[251];
+
+        fn zero() -> Gf251 {
+            Gf251(0)
+        }
+        fn one() -> Gf251 {
+            Gf251(1)
+        }
+        fn from_u64(k: u64) -> Gf251 {
+            Gf251(k % P)
+        }
+        fn add(a: &Gf251, b: &Gf251) -> Gf251 {
+            Gf251((a.0 + b.0) % P)
+        }
+        fn sub(a: &Gf251, b: &Gf251) -> Gf251 {
+            Gf251((a.0 + P - b.0) % P)
+        }
+        fn neg(a: &Gf251) -> Gf251 {
+            Gf251((P - a.0) % P)
+        }
+        fn mul(a: &Gf251, b: &Gf251) -> Gf251 {
+            Gf251(a.0 * b.0 % P)
+        }
+        fn invert(a: &Gf251) -> Gf251 {
+            (0..P - 2).fold(Gf251(1), |r, _| Gf251::mul(&r, a))
+        }
+        // p = 3 (mod 4), so a^((p+1)/4) is a root of any square a
+        fn sqrt(a: &Gf251) -> (Gf251, u32) {
+            let r = (0..(P + 1) / 4).fold(Gf251(1), |r, _| Gf251::mul(&r, a));
+            let was_square = (Gf251::square(&r) == *a) as u32;
+            (r, was_square)
+        }
+        fn to_canonical_bytes(&self) -> Vec<u8> {
+            vec![self.0 as u8]
+        }
+        fn from_canonical_bytes(b: &[u8]) -> Result<Gf251, ()> {
+            match b {
+                [x] if (*x as u64) < P => Ok(Gf251(*x as u64)),
+                _ => Err(()),
+            }
+        }
+        fn ct_equal(a: &Gf251, b: &Gf251) -> u32 {
+            (a == b) as u32
+        }
+        fn select(a: &Gf251, b: &Gf251, cond: u32) -> Gf251 {
+            if cond == 1 { a.clone() } else { b.clone() }
+        }
+    }
+}
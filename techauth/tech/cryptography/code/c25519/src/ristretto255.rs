@@ -0,0 +1,343 @@
+#![allow(dead_code)]
+
+use crate::edwards::Point;
+use crate::field::Element;
+use crate::scalar::Scalar;
+use crate::subtle;
+
+// ristretto255 is a prime-order group of order l built on edwards25519 [RFC 9496].
+// A RistrettoPoint is an edwards25519 point that stands for its whole coset P + E[4],
+// where E[4] is the 4-torsion subgroup. The encoding picks one canonical representative
+// of the coset, so that two points are equal exactly when their encodings are, and the
+// cofactor never shows through the API.
+//
+// The internal representation is not unique: use equal, never the coordinates, to compare.
+#[derive(Clone, Debug)]
+pub struct RistrettoPoint(pub(crate) Point);
+
+// CompressedRistretto is the 32-octet canonical encoding of a RistrettoPoint.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompressedRistretto(pub [u8; 32]);
+
+impl RistrettoPoint {
+    // 1/sqrt(a - d), with a = -1
+    const INVSQRT_A_MINUS_D: &'static Element = &Element(
+        278908739862762, 821645201101625, 8113234426968, 1777959178193151, 2118520810568447);
+    // sqrt(a*d - 1). RFC 9496 fixes the negative root here, unlike the other constants.
+    const SQRT_AD_MINUS_ONE: &'static Element = &Element(
+        2241493124984347, 425987919032274, 2207028919301688, 1220490630685848, 974799131293748);
+    // 1 - d^2
+    const ONE_MINUS_D_SQ: &'static Element = &Element(
+        1136626929484150, 1998550399581263, 496427632559748, 118527312129759, 45110755273534);
+    // (d - 1)^2
+    const D_MINUS_ONE_SQ: &'static Element = &Element(
+        1507062230895904, 1572317787530805, 683053064812840, 317374165784489, 1572899562415810);
+
+    pub fn identity() -> RistrettoPoint {
+        RistrettoPoint(Point::identity())
+    }
+
+    // The generator of ristretto255 is the coset of the edwards25519 base point.
+    pub fn basepoint() -> RistrettoPoint {
+        RistrettoPoint(Point::basepoint())
+    }
+
+    // compress implements the encoding of RFC 9496, Section 4.3.2.
+    pub fn compress(&self) -> CompressedRistretto {
+        let (x0, y0, z0, t0) = (&self.0.x, &self.0.y, &self.0.z, &self.0.t);
+
+        let u1 = Element::multiply(&Element::add(z0, y0), &Element::subtract(z0, y0));
+        let u2 = Element::multiply(x0, y0);
+        // Ignore was_square, since this is always square.
+        let (invsqrt, _) = Element::sqrt_ratio(Element::ONE, &Element::multiply(&u1, &Element::square(&u2)));
+        let den1 = Element::multiply(&invsqrt, &u1);
+        let den2 = Element::multiply(&invsqrt, &u2);
+        let z_inv = Element::multiply(&Element::multiply(&den1, &den2), t0);
+
+        // Rotate by sqrt(-1) to the representative with non-negative x*y.
+        let ix0 = Element::multiply(x0, Element::SQRT_M1);
+        let iy0 = Element::multiply(y0, Element::SQRT_M1);
+        let enchanted_denominator = Element::multiply(&den1, RistrettoPoint::INVSQRT_A_MINUS_D);
+        let rotate = Element::multiply(t0, &z_inv).is_negative();
+        let mut x = Element::ZERO.clone();
+        let mut y = Element::ZERO.clone();
+        let mut den_inv = Element::ZERO.clone();
+        x.select(&iy0, x0, rotate);
+        y.select(&ix0, y0, rotate);
+        den_inv.select(&enchanted_denominator, &den2, rotate);
+
+        // Then to the one with non-negative x.
+        let y_neg = Element::negate(&y);
+        let y_pos = y.clone();
+        y.select(&y_neg, &y_pos, Element::multiply(&x, &z_inv).is_negative());
+
+        let s = Element::absolute(&Element::multiply(&den_inv, &Element::subtract(z0, &y)));
+        CompressedRistretto(s.to_le_bytes())
+    }
+
+    // equal reports whether p and q are the same element of the group, that is, whether
+    // they differ by a 4-torsion point: X1*Y2 == Y1*X2 or Y1*Y2 == X1*X2 [RFC 9496, 4.5].
+    pub fn equal(&self, q: &RistrettoPoint) -> bool {
+        let (p, q) = (&self.0, &q.0);
+        let x1y2 = Element::multiply(&p.x, &q.y);
+        let y1x2 = Element::multiply(&p.y, &q.x);
+        let y1y2 = Element::multiply(&p.y, &q.y);
+        let x1x2 = Element::multiply(&p.x, &q.x);
+        (x1y2.ct_equal(&y1x2) | y1y2.ct_equal(&x1x2)) == 1
+    }
+
+    // calculate p + q.
+    pub fn add(p: &RistrettoPoint, q: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(Point::add(&p.0, &q.0))
+    }
+
+    // calculate p - q.
+    pub fn subtract(p: &RistrettoPoint, q: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(Point::subtract(&p.0, &q.0))
+    }
+
+    // calculate -p.
+    pub fn negate(p: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(Point::negate(&p.0))
+    }
+
+    // calculate [k]p in constant time.
+    pub fn scalar_mul(k: &Scalar, p: &RistrettoPoint) -> RistrettoPoint {
+        RistrettoPoint(Point::scalar_mul(&k.to_bytes(), &p.0))
+    }
+
+    // calculate [k]B for the generator B, using the precomputed basepoint table.
+    pub fn basepoint_mul(k: &Scalar) -> RistrettoPoint {
+        RistrettoPoint(Point::basepoint_mul(&k.to_bytes()))
+    }
+
+    // from_uniform_bytes implements the one-way map of RFC 9496, Section 4.3.4.
+    // Given 64 uniformly random octets, such as the output of SHA-512, it returns a point
+    // that is indistinguishable from uniformly random. The high bit of each half is ignored.
+    pub fn from_uniform_bytes(b: &[u8; 64]) -> RistrettoPoint {
+        let r0 = Element::from_le_bytes(b[..32].try_into().unwrap());
+        let r1 = Element::from_le_bytes(b[32..].try_into().unwrap());
+        RistrettoPoint::add(&RistrettoPoint::elligator_map(&r0), &RistrettoPoint::elligator_map(&r1))
+    }
+
+    // elligator_map is the MAP function of RFC 9496, Section 4.3.4.
+    fn elligator_map(t: &Element) -> RistrettoPoint {
+        let one = Element::ONE;
+        let r = Element::multiply(Element::SQRT_M1, &Element::square(t));
+        let u = Element::multiply(&Element::add(&r, one), RistrettoPoint::ONE_MINUS_D_SQ);
+        let v = Element::multiply(
+            &Element::subtract(&Element::negate(one), &Element::multiply(&r, Point::D)),
+            &Element::add(&r, Point::D));
+
+        let (s, was_square) = Element::sqrt_ratio(&u, &v);
+        let s_prime = Element::negate(&Element::absolute(&Element::multiply(&s, t)));
+        let mut s_sel = Element::ZERO.clone();
+        s_sel.select(&s, &s_prime, was_square);
+        let mut c = Element::ZERO.clone();
+        c.select(&Element::negate(one), &r, was_square);
+
+        let n = Element::subtract(
+            &Element::multiply(&Element::multiply(&c, &Element::subtract(&r, one)), RistrettoPoint::D_MINUS_ONE_SQ),
+            &v);
+
+        let s2 = Element::square(&s_sel);
+        let w0 = Element::multiply(&Element::add(&s_sel, &s_sel), &v);
+        let w1 = Element::multiply(&n, RistrettoPoint::SQRT_AD_MINUS_ONE);
+        let w2 = Element::subtract(one, &s2);
+        let w3 = Element::add(one, &s2);
+        RistrettoPoint(Point {
+            x: Element::multiply(&w0, &w3),
+            y: Element::multiply(&w2, &w1),
+            z: Element::multiply(&w1, &w3),
+            t: Element::multiply(&w0, &w2),
+        })
+    }
+}
+
+impl CompressedRistretto {
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    // decompress implements the decoding of RFC 9496, Section 4.3.1. It rejects
+    // non-canonical and negative encodings of s, and the encodings of invalid points.
+    pub fn decompress(&self) -> Result<RistrettoPoint, ()> {
+        let s = Element::from_le_bytes(self.0);
+        let s_encoding_is_canonical = subtle::constant_time_compare(&s.to_le_bytes(), &self.0);
+        if !s_encoding_is_canonical || s.is_negative() == 1 {
+            return Err(());
+        }
+
+        let one = Element::ONE;
+        let ss = Element::square(&s);
+        let u1 = Element::subtract(one, &ss);
+        let u2 = Element::add(one, &ss);
+        let u2_sqr = Element::square(&u2);
+
+        // v = -(d * u1^2) - u2^2
+        let v = Element::subtract(
+            &Element::negate(&Element::multiply(Point::D, &Element::square(&u1))),
+            &u2_sqr);
+        let (invsqrt, was_square) = Element::sqrt_ratio(one, &Element::multiply(&v, &u2_sqr));
+
+        let den_x = Element::multiply(&invsqrt, &u2);
+        let den_y = Element::multiply(&Element::multiply(&invsqrt, &den_x), &v);
+        let x = Element::absolute(&Element::multiply(&Element::add(&s, &s), &den_x));
+        let y = Element::multiply(&u1, &den_y);
+        let t = Element::multiply(&x, &y);
+
+        if was_square == 0 || t.is_negative() == 1 || y.equal(Element::ZERO) {
+            return Err(());
+        }
+        Ok(RistrettoPoint(Point { x, y, z: one.clone(), t }))
+    }
+}
+
+#[cfg(test)]
+mod ristretto255_test {
+    use crate::edwards::Point;
+    use crate::ristretto255::{CompressedRistretto, RistrettoPoint};
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+
+    fn compressed(s: &str) -> CompressedRistretto {
+        CompressedRistretto(hex_decode(s).try_into().unwrap())
+    }
+
+    // RFC 9496, Appendix A.1: the encodings of [0]B through [15]B.
+    #[test]
+    fn test_multiples_of_generator() {
+        let encodings = [
+            "0000000000000000000000000000000000000000000000000000000000000000",
+            "e2f2ae0a6abc4e71a884a961c500515f58e30b6aa582dd8db6a65945e08d2d76",
+            "6a493210f7499cd17fecb510ae0cea23a110e8d5b901f8acadd3095c73a3b919",
+            "94741f5d5d52755ece4f23f044ee27d5d1ea1e2bd196b462166b16152a9d0259",
+            "da80862773358b466ffadfe0b3293ab3d9fd53c5ea6c955358f568322daf6a57",
+            "e882b131016b52c1d3337080187cf768423efccbb517bb495ab812c4160ff44e",
+            "f64746d3c92b13050ed8d80236a7f0007c3b3f962f5ba793d19a601ebb1df403",
+            "44f53520926ec81fbd5a387845beb7df85a96a24ece18738bdcfa6a7822a176d",
+            "903293d8f2287ebe10e2374dc1a53e0bc887e592699f02d077d5263cdd55601c",
+            "02622ace8f7303a31cafc63f8fc48fdc16e1c8c8d234b2f0d6685282a9076031",
+            "20706fd788b2720a1ed2a5dad4952b01f413bcf0e7564de8cdc816689e2db95f",
+            "bce83f8ba5dd2fa572864c24ba1810f9522bc6004afe95877ac73241cafdab42",
+            "e4549ee16b9aa03099ca208c67adafcafa4c3f3e4e5303de6026e3ca8ff84460",
+            "aa52e000df2e16f55fb1032fc33bc42742dad6bd5a8fc0be0167436c5948501f",
+            "46376b80f409b29dc2b5f6f0c52591990896e5716f41477cd30085ab7f10301e",
+            "e0c418f7c8d9c4cdd7395b93ea124f3ad99021bb681dfc3302a9d99a2e53e64e",
+        ];
+        let b = RistrettoPoint::basepoint();
+        let mut p = RistrettoPoint::identity();
+        for (i, enc) in encodings.iter().enumerate() {
+            let c = compressed(enc);
+            assert_eq!(p.compress(), c);
+
+            let q = c.decompress().unwrap();
+            assert!(q.equal(&p));
+            assert_eq!(q.compress(), c);
+
+            let mut k: [u8; 32] = [0; 32];
+            k[0] = i as u8;
+            assert!(RistrettoPoint::basepoint_mul(&Scalar::from_bytes_mod_order(k)).equal(&p));
+            p = RistrettoPoint::add(&p, &b);
+        }
+    }
+
+    // RFC 9496, Appendix A.2: encodings that must be rejected.
+    #[test]
+    fn test_invalid_encodings() {
+        let encodings = [
+            // non-canonical field encodings
+            "00ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "f3ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            // negative field elements
+            "0100000000000000000000000000000000000000000000000000000000000000",
+            "01ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+            "ed57ffd8c914fb201471d1c3d245ce3c746fcbe63a3679d51b6a516ebebe0e20",
+            "c34c4e1826e5d403b78e246e88aa051c36ccf0aafebffe137d148a2bf9104562",
+            "c940e5a4404157cfb1628b108db051a8d439e1a421394ec4ebccb9ec92a8ac78",
+            "47cfc5497c53dc8e61c91d17fd626ffb1c49e2bca94eed052281b510b1117a24",
+            "f1c6165d33367351b0da8f6e4511010c68174a03b6581212c71c0e1d026c3c72",
+            "87260f7a2f12495118360f02c26a470f450dadf34a413d21042b43b9d93e1309",
+            // non-square x^2
+            "26948d35ca62e643e26a83177332e6b6afeb9d08e4268b650f1f5bbd8d81d371",
+            "4eac077a713c57b4f4397629a4145982c661f48044dd3f96427d40b147d9742f",
+            "de6a7b00deadc788eb6b6c8d20c0ae96c2f2019078fa604fee5b87d6e989ad7b",
+            "bcab477be20861e01e4a0e295284146a510150d9817763caf1a6f4b422d67042",
+            "2a292df7e32cababbd9de088d1d1abec9fc0440f637ed2fba145094dc14bea08",
+            "f4a9e534fc0d216c44b218fa0c42d99635a0127ee2e53c712f70609649fdff22",
+            "8268436f8c4126196cf64b3c7ddbda90746a378625f9813dd9b8457077256731",
+            "2810e5cbc2cc4d4eece54f61c6f69758e289aa7ab440b3cbeaa21995c2f4232b",
+            // negative x*y value
+            "3eb858e78f5a7254d8c9731174a94f76755fd3941c0ac93735c07ba14579630e",
+            "a45fdc55c76448c049a1ab33f17023edfb2be3581e9c7aade8a6125215e04220",
+            "d483fe813c6ba647ebbfd3ec41adca1c6130c2beeee9d9bf065c8d151c5f396e",
+            "8a2e1d30050198c65a54483123960ccc38aef6848e1ec8f5f780e8523769ba32",
+            "32888462f8b486c68ad7dd9610be5192bbeaf3b443951ac1a8118419d9fa097b",
+            "227142501b9d4355ccba290404bde41575b037693cef1f438c47f8fbf35d1165",
+            "5c37cc491da847cfeb9281d407efc41e15144c876e0170b499a96a22ed31e01e",
+            "445425117cb8c90edcbc7c1cc0e74f747f2c1efa5630a967c64f287792a48a4b",
+            // s = -1, which causes y = 0
+            "ecffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+        ];
+        for enc in encodings {
+            assert!(compressed(enc).decompress().is_err(), "{enc}");
+        }
+    }
+
+    // RFC 9496, Appendix A.3 and A.4: the one-way map applied to the given 64-octet inputs.
+    // The last four inputs are non-canonical and all map to the same point.
+    #[test]
+    fn test_from_uniform_bytes() {
+        let vectors = [
+            ("5d1be09e3d0c82fc538112490e35701979d99e06ca3e2b5b54bffe8b4dc772c14d98b696a1bbfb5ca32c436cc61c16563790306c79eaca7705668b47dffe5bb6",
+             "3066f82a1a747d45120d1740f14358531a8f04bbffe6a819f86dfe50f44a0a46"),
+            ("f116b34b8f17ceb56e8732a60d913dd10cce47a6d53bee9204be8b44f6678b270102a56902e2488c46120e9276cfe54638286b9e4b3cdb470b542d46c2068d38",
+             "f26e5b6f7d362d2d2a94c5d0e7602cb4773c95a2e5c31a64f133189fa76ed61b"),
+            ("8422e1bbdaab52938b81fd602effb6f89110e1e57208ad12d9ad767e2e25510c27140775f9337088b982d83d7fcf0b2fa1edffe51952cbe7365e95c86eaf325c",
+             "006ccd2a9e6867e6a2c5cea83d3302cc9de128dd2a9a57dd8ee7b9d7ffe02826"),
+            ("ac22415129b61427bf464e17baee8db65940c233b98afce8d17c57beeb7876c2150d15af1cb1fb824bbd14955f2b57d08d388aab431a391cfc33d5bafb5dbbaf",
+             "f8f0c87cf237953c5890aec3998169005dae3eca1fbb04548c635953c817f92a"),
+            ("165d697a1ef3d5cf3c38565beefcf88c0f282b8e7dbd28544c483432f1cec7675debea8ebb4e5fe7d6f6e5db15f15587ac4d4d4a1de7191e0c1ca6664abcc413",
+             "ae81e7dedf20a497e10c304a765c1767a42d6e06029758d2d7e8ef7cc4c41179"),
+            ("a836e6c9a9ca9f1e8d486273ad56a78c70cf18f0ce10abb1c7172ddd605d7fd2979854f47ae1ccf204a33102095b4200e5befc0465accc263175485f0e17ea5c",
+             "e2705652ff9f5e44d3e841bf1c251cf7dddb77d140870d1ab2ed64f1a9ce8628"),
+            ("2cdc11eaeb95daf01189417cdddbf95952993aa9cb9c640eb5058d09702c74622c9965a697a3b345ec24ee56335b556e677b30e6f90ac77d781064f866a3c982",
+             "80bd07262511cdde4863f8a7434cef696750681cb9510eea557088f76d9e5065"),
+            ("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff1200000000000000000000000000000000000000000000000000000000000000",
+             "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f"),
+            ("edffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+             "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f"),
+            ("0000000000000000000000000000000000000000000000000000000000000080ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff7f",
+             "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f"),
+            ("00000000000000000000000000000000000000000000000000000000000000001200000000000000000000000000000000000000000000000000000000000080",
+             "304282791023b73128d277bdcb5c7746ef2eac08dde9f2983379cb8e5ef0517f"),
+        ];
+        for (input, output) in vectors {
+            let p = RistrettoPoint::from_uniform_bytes(&hex_decode(input).try_into().unwrap());
+            assert_eq!(p.compress(), compressed(output));
+        }
+    }
+
+    // Points that differ by a 4-torsion point are equal and share an encoding.
+    #[test]
+    fn test_equal_coset() {
+        // (sqrt(-1), 0), a point of order 4
+        let t4 = Point::from_bytes(&hex_decode("0000000000000000000000000000000000000000000000000000000000000080")
+            .try_into().unwrap()).unwrap();
+        let p = RistrettoPoint::basepoint_mul(&Scalar::from_bytes_mod_order([0x42; 32]));
+        let mut q = p.clone();
+        for _ in 0..4 {
+            q = RistrettoPoint(Point::add(&q.0, &t4));
+            assert!(q.equal(&p));
+            assert_eq!(q.compress(), p.compress());
+        }
+        assert!(!p.equal(&RistrettoPoint::basepoint()));
+        assert!(RistrettoPoint::add(&p, &RistrettoPoint::negate(&p)).equal(&RistrettoPoint::identity()));
+
+        let k = Scalar::from_bytes_mod_order([0x17; 32]);
+        let kp = RistrettoPoint::scalar_mul(&k, &p);
+        assert_eq!(kp.compress().decompress().unwrap().compress(), kp.compress());
+    }
+}
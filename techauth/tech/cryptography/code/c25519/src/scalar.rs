@@ -0,0 +1,401 @@
+#![allow(dead_code)]
+
+use crate::subtle;
+
+// Scalar represents an integer modulo the prime order of the edwards25519 base point
+//     l = 2^252 + 27742317777372353535851937790883648493.
+// A scalar is always kept in its canonical 32-octet little-endian encoding.
+//
+// Multiplication and reduction follow the ref10 implementation (sc_muladd and sc_reduce):
+// the operands are split into 21-bit signed limbs, and every limb at or above
+// 2^252 = 2^(12*21) is folded back using 2^252 = -q0 (mod l), where
+//     -q0 = 666643 + 470296*2^21 + 654183*2^42 - 997805*2^63 + 136657*2^84 - 683901*2^105.
+// See notes/ed25519-group-reduction.md for the derivation of these constants.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scalar(pub(crate) [u8; 32]);
+
+impl Scalar {
+    pub const ZERO: &'static Scalar = &Scalar([0; 32]);
+    pub const ONE: &'static Scalar = &Scalar([
+        1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ]);
+    // l - 1, so that a * (l - 1) = -a (mod l).
+    pub const MINUS_ONE: &'static Scalar = &Scalar([
+        0xec, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+    ]);
+    // l, the group order. This is not a valid Scalar; it is kept for encoding checks.
+    pub const ORDER: [u8; 32] = [
+        0xed, 0xd3, 0xf5, 0x5c, 0x1a, 0x63, 0x12, 0x58, 0xd6, 0x9c, 0xf7, 0xa2, 0xde, 0xf9, 0xde, 0x14,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10,
+    ];
+
+    const MASK_LOW_21BITS: i64 = (1 << 21) - 1;
+
+    // from_bytes_mod_order_wide reduces a 64-octet little-endian integer modulo l.
+    // This is how RFC 8032 turns SHA-512 digests into scalars.
+    pub fn from_bytes_mod_order_wide(b: &[u8; 64]) -> Scalar {
+        let mut s: [i64; 24] = [0; 24];
+        Scalar::unpack(b, &mut s);
+        Scalar(Scalar::reduce_limbs(&mut s))
+    }
+
+    // from_bytes_mod_order reduces a 32-octet little-endian integer modulo l.
+    pub fn from_bytes_mod_order(b: [u8; 32]) -> Scalar {
+        let mut wide: [u8; 64] = [0; 64];
+        wide[..32].copy_from_slice(&b);
+        Scalar::from_bytes_mod_order_wide(&wide)
+    }
+
+    // from_canonical_bytes accepts only the encodings of integers in [0, l).
+    pub fn from_canonical_bytes(b: [u8; 32]) -> Result<Scalar, ()> {
+        let s = Scalar::from_bytes_mod_order(b);
+        if subtle::constant_time_compare(&s.0, &b) {
+            Ok(s)
+        } else {
+            Err(())
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.0
+    }
+
+    // calculate a * b + c mod l.
+    // The inputs need not be reduced: any 256-bit little-endian integer is accepted.
+    // This is how RFC 8032 computes S = (r + k * s) mod l from the clamped secret scalar s.
+    pub fn mul_add_bytes(a: &[u8; 32], b: &[u8; 32], c: &[u8; 32]) -> Scalar {
+        let mut a_limbs: [i64; 12] = [0; 12];
+        let mut b_limbs: [i64; 12] = [0; 12];
+        let mut s: [i64; 24] = [0; 24];
+        Scalar::unpack(a, &mut a_limbs);
+        Scalar::unpack(b, &mut b_limbs);
+        Scalar::unpack(c, &mut s[..12]);
+
+        // Schoolbook multiplication into 23 column sums. Each limb is at most 25 bits
+        // wide, so every column stays well below 2^63.
+        for i in 0..12 {
+            for j in 0..12 {
+                s[i + j] += a_limbs[i] * b_limbs[j];
+            }
+        }
+
+        // Bring every column back to roughly 21 bits before folding the upper half.
+        for i in (0..23).step_by(2) {
+            Scalar::carry_round(&mut s, i);
+        }
+        for i in (1..22).step_by(2) {
+            Scalar::carry_round(&mut s, i);
+        }
+
+        Scalar(Scalar::reduce_limbs(&mut s))
+    }
+
+    // calculate a * b + c mod l.
+    pub fn mul_add(a: &Scalar, b: &Scalar, c: &Scalar) -> Scalar {
+        Scalar::mul_add_bytes(&a.0, &b.0, &c.0)
+    }
+
+    // calculate a * b mod l.
+    pub fn multiply(a: &Scalar, b: &Scalar) -> Scalar {
+        Scalar::mul_add(a, b, Scalar::ZERO)
+    }
+
+    // calculate a + b mod l.
+    pub fn add(a: &Scalar, b: &Scalar) -> Scalar {
+        Scalar::mul_add(a, Scalar::ONE, b)
+    }
+
+    // calculate a - b mod l.
+    pub fn subtract(a: &Scalar, b: &Scalar) -> Scalar {
+        Scalar::mul_add(b, Scalar::MINUS_ONE, a)
+    }
+
+    // calculate -a mod l.
+    pub fn negate(a: &Scalar) -> Scalar {
+        Scalar::mul_add(a, Scalar::MINUS_ONE, Scalar::ZERO)
+    }
+
+    // signed_radix16 writes the scalar as sum(digits[i] * 16^i) with every digit in [-8, 8).
+    // The last digit may reach 8, which is still in range because a reduced scalar is
+    // below 2^253. The digits are computed without branching on the scalar.
+    pub(crate) fn signed_radix16(&self) -> [i8; 64] {
+        assert!(self.0[31] <= 127);
+        let mut digits: [i8; 64] = [0; 64];
+        for (i, b) in self.0.iter().enumerate() {
+            digits[2 * i] = (b & 15) as i8;
+            digits[2 * i + 1] = ((b >> 4) & 15) as i8;
+        }
+        // Move the excess of every digit of 8 or more into the next one.
+        for i in 0..63 {
+            let carry = (digits[i] + 8) >> 4;
+            digits[i] -= carry << 4;
+            digits[i + 1] += carry;
+        }
+        digits
+    }
+
+    // non_adjacent_form returns the width-w NAF of the scalar, for w in [2, 8]: 256 digits,
+    // each either zero or odd and in (-2^(w-1), 2^(w-1)), such that any w consecutive digits
+    // contain at most one nonzero digit. It branches on the scalar, so use it only on public data.
+    pub(crate) fn non_adjacent_form(&self, w: usize) -> [i8; 256] {
+        assert!(self.0[31] <= 127);
+        assert!((2..=8).contains(&w));
+
+        // the scalar as little-endian 64-bit words, with one more word to read past the end
+        let mut words: [u64; 5] = [0; 5];
+        for (i, word) in words.iter_mut().take(4).enumerate() {
+            *word = u64::from_le_bytes(self.0[8 * i..8 * i + 8].try_into().unwrap());
+        }
+
+        let width: u64 = 1 << w;
+        let window_mask: u64 = width - 1;
+        let mut naf: [i8; 256] = [0; 256];
+        let mut pos: usize = 0;
+        let mut carry: u64 = 0;
+        while pos < 256 {
+            let (index, bit) = (pos / 64, pos % 64);
+            let bit_buf = if bit < 64 - w {
+                words[index] >> bit
+            } else {
+                (words[index] >> bit) | (words[index + 1] << (64 - bit))
+            };
+
+            let window = carry + (bit_buf & window_mask);
+            if window & 1 == 0 {
+                // An even window leaves the digit at pos zero; the carry moves along with pos.
+                pos += 1;
+                continue;
+            }
+            if window < width / 2 {
+                carry = 0;
+                naf[pos] = window as i8;
+            } else {
+                carry = 1;
+                naf[pos] = (window as i16 - width as i16) as i8;
+            }
+            pos += w;
+        }
+        naf
+    }
+
+    // signed_radix_2w writes the scalar as sum(digits[i] * 2^(w*i)) for w in [4, 8], with every
+    // digit in [-2^(w-1), 2^(w-1)). signed_radix16 is the case w = 4 with a fixed-size result.
+    pub(crate) fn signed_radix_2w(&self, w: usize) -> Vec<i16> {
+        assert!(self.0[31] <= 127);
+        assert!((4..=8).contains(&w));
+
+        // A reduced scalar has at most 253 bits; one more digit takes the final carry.
+        let n = 253_usize.div_ceil(w) + 1;
+        let mut digits: Vec<i16> = vec![0; n];
+        let mut carry: i16 = 0;
+        for (i, digit) in digits.iter_mut().enumerate() {
+            let bit = w * i;
+            let mut window: u16 = 0;
+            for k in 0..2 {
+                if bit / 8 + k < 32 {
+                    window |= (self.0[bit / 8 + k] as u16) << (8 * k);
+                }
+            }
+            let coefficient = carry + ((window >> (bit % 8)) & ((1 << w) - 1)) as i16;
+            carry = (coefficient + (1 << (w - 1))) >> w;
+            *digit = coefficient - (carry << w);
+        }
+        assert_eq!(carry, 0);
+        digits
+    }
+
+    // unpack splits a little-endian integer into 21-bit limbs, limb i holding bits
+    // 21*i through 21*i + 20. The last limb keeps all of the remaining high bits.
+    fn unpack(b: &[u8], limbs: &mut [i64]) {
+        let n = limbs.len();
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let bit = 21 * i;
+            let mut v: i64 = 0;
+            for k in 0..4 {
+                if bit / 8 + k < b.len() {
+                    v |= (b[bit / 8 + k] as i64) << (8 * k);
+                }
+            }
+            v >>= bit % 8;
+            if i + 1 < n {
+                v &= Scalar::MASK_LOW_21BITS;
+            }
+            *limb = v;
+        }
+    }
+
+    // carry_round moves the rounded carry of s[i] into s[i + 1], leaving s[i] in [-2^20, 2^20).
+    fn carry_round(s: &mut [i64; 24], i: usize) {
+        let carry = (s[i] + (1 << 20)) >> 21;
+        s[i + 1] += carry;
+        s[i] -= carry << 21;
+    }
+
+    // carry_floor moves the carry of s[i] into s[i + 1], leaving s[i] in [0, 2^21).
+    fn carry_floor(s: &mut [i64; 24], i: usize) {
+        let carry = s[i] >> 21;
+        s[i + 1] += carry;
+        s[i] -= carry << 21;
+    }
+
+    // fold replaces s[i] * 2^(21*i) with s[i] * -q0 * 2^(21*(i-12)), since 2^252 = -q0 (mod l).
+    fn fold(s: &mut [i64; 24], i: usize) {
+        s[i - 12] += s[i] * 666643;
+        s[i - 11] += s[i] * 470296;
+        s[i - 10] += s[i] * 654183;
+        s[i - 9] -= s[i] * 997805;
+        s[i - 8] += s[i] * 136657;
+        s[i - 7] -= s[i] * 683901;
+        s[i] = 0;
+    }
+
+    // reduce_limbs is the tail of ref10's sc_reduce: it folds the 24 limbs of a
+    // 504-bit value down to twelve, fully reduces the result modulo l and packs it.
+    fn reduce_limbs(s: &mut [i64; 24]) -> [u8; 32] {
+        for i in (18..=23).rev() {
+            Scalar::fold(s, i);
+        }
+        for i in (6..=16).step_by(2) {
+            Scalar::carry_round(s, i);
+        }
+        for i in (7..=15).step_by(2) {
+            Scalar::carry_round(s, i);
+        }
+
+        for i in (12..=17).rev() {
+            Scalar::fold(s, i);
+        }
+        for i in (0..=10).step_by(2) {
+            Scalar::carry_round(s, i);
+        }
+        for i in (1..=11).step_by(2) {
+            Scalar::carry_round(s, i);
+        }
+
+        // The carries above may have pushed a small value back into s[12].
+        Scalar::fold(s, 12);
+        for i in 0..=11 {
+            Scalar::carry_floor(s, i);
+        }
+
+        Scalar::fold(s, 12);
+        for i in 0..=10 {
+            Scalar::carry_floor(s, i);
+        }
+
+        let mut out: [u8; 32] = [0; 32];
+        for (i, limb) in s.iter().take(12).enumerate() {
+            let bit = 21 * i;
+            let v: u64 = (*limb as u64) << (bit % 8);
+            for k in 0..4 {
+                if bit / 8 + k < 32 {
+                    out[bit / 8 + k] |= (v >> (8 * k)) as u8;
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod scalar_test {
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+
+    fn scalar(s: &str) -> Scalar {
+        Scalar(hex_decode(s).try_into().unwrap())
+    }
+
+    #[test]
+    fn test_reduce_wide() {
+        // (2^512 - 1) mod l
+        let b: [u8; 64] = [0xff; 64];
+        assert_eq!(Scalar::from_bytes_mod_order_wide(&b),
+                   scalar("000f9c44e31106a447938568a71b0ed065bef517d273ecce3d9a307c1b419903"));
+
+        // l reduces to zero and l - 1 is left untouched.
+        let mut b: [u8; 64] = [0; 64];
+        b[..32].copy_from_slice(&Scalar::ORDER);
+        assert_eq!(Scalar::from_bytes_mod_order_wide(&b), Scalar::ZERO.clone());
+        b[..32].copy_from_slice(&Scalar::MINUS_ONE.0);
+        assert_eq!(Scalar::from_bytes_mod_order_wide(&b), Scalar::MINUS_ONE.clone());
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        assert!(Scalar::from_canonical_bytes(Scalar::MINUS_ONE.0).is_ok());
+        assert!(Scalar::from_canonical_bytes(Scalar::ORDER).is_err());
+        assert!(Scalar::from_canonical_bytes([0xff; 32]).is_err());
+        assert_eq!(Scalar::from_bytes_mod_order(Scalar::ORDER), Scalar::ZERO.clone());
+    }
+
+    #[test]
+    fn test_mul_add() {
+        let a = scalar("1c9e2b4a0d1b6f3e8c2b1a9f0e7d6c5b4a392817f6e5d4c3b2a1908f7e6d5c0b");
+        let b = scalar("5a4b3c2d1e0f9e8d7c6b5a49382716f5e4d3c2b1a0ff0e1d2c3b4a5968778801");
+        let c = scalar("e3d2c1b0a99887766554433221100ffeeddccbbaa99887766554433221100f0e");
+        assert_eq!(Scalar::mul_add(&a, &b, &c),
+                   scalar("5848c5903f2b8a1a38ceaaf6756ba0c1abca4eb4037f979533663bc4bdd1f001"));
+        assert_eq!(Scalar::multiply(&a, &b),
+                   scalar("6249f93cb0f514fca8165f67335570d8bded82f959e60f1fce11f8919cc1e103"));
+
+        let sum = Scalar::add(&a, &b);
+        assert_eq!(Scalar::subtract(&sum, &b), Scalar::from_bytes_mod_order(a.0));
+        assert_eq!(Scalar::add(&a, &Scalar::negate(&a)), Scalar::ZERO.clone());
+        assert_eq!(Scalar::multiply(Scalar::MINUS_ONE, Scalar::MINUS_ONE), Scalar::ONE.clone());
+    }
+
+    // eval_digits evaluates sum(digits[i] * 2^(w*i)) back into 32 little-endian bytes, from the
+    // most significant digit down. The digits are i8 or, for signed_radix_2w, i16.
+    fn eval_digits<D: Copy + Into<i32>>(digits: &[D], w: usize) -> [u8; 32] {
+        let mut v: [i32; 34] = [0; 34];
+        for d in digits.iter().rev() {
+            let mut carry: i32 = (*d).into();
+            for limb in v.iter_mut() {
+                let t = *limb * (1 << w) + carry;
+                *limb = t.rem_euclid(256);
+                carry = t.div_euclid(256);
+            }
+        }
+        core::array::from_fn(|i| v[i] as u8)
+    }
+
+    #[test]
+    fn test_non_adjacent_form() {
+        let scalars = [Scalar::ONE.clone(), Scalar::MINUS_ONE.clone(), Scalar::from_bytes_mod_order([0xab; 32])];
+        for s in scalars {
+            for w in 2..=8 {
+                let naf = s.non_adjacent_form(w);
+                let mut last: Option<usize> = None;
+                for (i, d) in naf.iter().enumerate().filter(|(_, d)| **d != 0) {
+                    assert!(d % 2 != 0 && (*d as i16).abs() < 1 << (w - 1));
+                    assert!(last.is_none_or(|j| i - j >= w));
+                    last = Some(i);
+                }
+
+                assert_eq!(eval_digits(&naf, 1), s.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_radix_2w() {
+        for s in [Scalar::ONE.clone(), Scalar::MINUS_ONE.clone(), Scalar::from_bytes_mod_order([0xc7; 32])] {
+            for w in 4..=8 {
+                let digits = s.signed_radix_2w(w);
+                assert!(digits.iter().all(|d| (-(1 << (w - 1))..1 << (w - 1)).contains(d)));
+                assert_eq!(eval_digits(&digits, w), s.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_signed_radix16() {
+        for s in [Scalar::ZERO.clone(), Scalar::MINUS_ONE.clone(), Scalar::from_bytes_mod_order([0x88; 32])] {
+            let digits = s.signed_radix16();
+            assert!(digits.iter().all(|d| (-8..8).contains(d) || *d == 8));
+            assert_eq!(eval_digits(&digits, 4), s.0);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+#![allow(dead_code)]
+
+use crate::subtle;
+
+// Scalar is an integer modulo the prime order of the Edwards448 base point,
+//     L = 2^446 - 13818066809895115352007386748515426880336692474882178609894547503885,
+// in its 57-octet little-endian encoding [RFC 8032, 5.2]. The top octet is always zero.
+//
+// Arithmetic works on little-endian 64-bit limbs. Since L is just below a power of two,
+//     2^446 = c (mod L),  c = 2^446 - L < 2^224,
+// a wide integer x = hi*2^446 + lo is reduced by replacing it with hi*c + lo, which cuts about
+// 222 bits per round. Four rounds bring any integer below 2^1024 under 2^446 + 2^224, and a
+// final conditional subtraction yields the result in [0, L). The number of rounds is fixed,
+// so the running time does not depend on the value.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Scalar(pub(crate) [u8; 57]);
+
+// limbs of the wide intermediate values, 1024 bits
+const WIDE: usize = 16;
+
+impl Scalar {
+    pub const ZERO: &'static Scalar = &Scalar([0; 57]);
+    // L in 64-bit limbs
+    const ORDER_LIMBS: [u64; 7] = [
+        0x2378c292ab5844f3, 0x216cc2728dc58f55, 0xc44edb49aed63690, 0xffffffff7cca23e9,
+        0xffffffffffffffff, 0xffffffffffffffff, 0x3fffffffffffffff,
+    ];
+    // c = 2^446 - L
+    const C: [u64; 4] = [0xdc873d6d54a7bb0d, 0xde933d8d723a70aa, 0x3bb124b65129c96f, 0x8335dc16];
+
+    fn to_limbs(b: &[u8]) -> [u64; WIDE] {
+        let mut x: [u64; WIDE] = [0; WIDE];
+        for (i, byte) in b.iter().enumerate() {
+            x[i / 8] |= (*byte as u64) << (8 * (i % 8));
+        }
+        x
+    }
+
+    // reduce_limbs returns x mod L.
+    fn reduce_limbs(mut x: [u64; WIDE]) -> Scalar {
+        for _ in 0..4 {
+            // hi = x >> 446, lo = x mod 2^446
+            let mut hi: [u64; WIDE - 6] = [0; WIDE - 6];
+            for (i, h) in hi.iter_mut().enumerate() {
+                let next = if i + 7 < WIDE { x[i + 7] } else { 0 };
+                *h = (x[i + 6] >> 62) | (next << 2);
+            }
+            x[6] &= (1 << 62) - 1;
+            x[7..].fill(0);
+
+            // x = lo + hi * c
+            for (i, h) in hi.iter().enumerate() {
+                let mut carry: u128 = 0;
+                for (j, c) in Scalar::C.iter().enumerate() {
+                    let t = (*h as u128) * (*c as u128) + x[i + j] as u128 + carry;
+                    x[i + j] = t as u64;
+                    carry = t >> 64;
+                }
+                for xk in x[i + Scalar::C.len()..].iter_mut() {
+                    let t = *xk as u128 + carry;
+                    *xk = t as u64;
+                    carry = t >> 64;
+                }
+            }
+        }
+
+        // x < 2^446 + 2^224 < 2L: subtract L unless that borrows.
+        let mut d: [u64; 7] = [0; 7];
+        let mut borrow: u64 = 0;
+        for (i, di) in d.iter_mut().enumerate() {
+            let (t, b1) = x[i].overflowing_sub(Scalar::ORDER_LIMBS[i]);
+            let (t, b2) = t.overflowing_sub(borrow);
+            *di = t;
+            borrow = (b1 | b2) as u64;
+        }
+        let m = borrow.wrapping_sub(1);
+        let mut s: [u8; 57] = [0; 57];
+        for i in 0..7 {
+            let limb = (m & d[i]) | (!m & x[i]);
+            s[8 * i..8 * i + 8].copy_from_slice(&limb.to_le_bytes());
+        }
+        Scalar(s)
+    }
+
+    // from_bytes_mod_order_wide reduces a 114-octet little-endian integer modulo L.
+    // This is how RFC 8032 turns SHAKE256 outputs into scalars.
+    pub fn from_bytes_mod_order_wide(b: &[u8; 114]) -> Scalar {
+        Scalar::reduce_limbs(Scalar::to_limbs(b))
+    }
+
+    // from_canonical_bytes accepts only the encodings of integers in [0, L).
+    pub fn from_canonical_bytes(b: &[u8; 57]) -> Result<Scalar, ()> {
+        let s = Scalar::reduce_limbs(Scalar::to_limbs(b));
+        if subtle::constant_time_compare(&s.0, b) {
+            Ok(s)
+        } else {
+            Err(())
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 57] {
+        self.0
+    }
+
+    // calculate a * b + c mod L.
+    // The inputs need not be reduced: any 57-octet little-endian integer is accepted, such as
+    // the clamped secret scalar s of S = (r + k * s) mod L.
+    pub fn mul_add_bytes(a: &[u8; 57], b: &[u8; 57], c: &[u8; 57]) -> Scalar {
+        let a = Scalar::to_limbs(a);
+        let b = Scalar::to_limbs(b);
+        let mut x = Scalar::to_limbs(c);
+        // 57 octets fit in 8 limbs, and the product in 16.
+        for i in 0..8 {
+            let mut carry: u128 = 0;
+            for j in 0..8 {
+                let t = (a[i] as u128) * (b[j] as u128) + x[i + j] as u128 + carry;
+                x[i + j] = t as u64;
+                carry = t >> 64;
+            }
+            for xk in x[i + 8..].iter_mut() {
+                let t = *xk as u128 + carry;
+                *xk = t as u64;
+                carry = t >> 64;
+            }
+        }
+        Scalar::reduce_limbs(x)
+    }
+}
+
+#[cfg(test)]
+mod scalar448_test {
+    use crate::scalar448::Scalar;
+    use crate::test_util::hex_decode;
+
+    const ORDER: &str = "f34458ab92c27823558fc58d72c26c219036d6ae49db4ec4e923ca7cffffffffffffffffffffffffffffffffffffffffffffffffffffff3f00";
+
+    #[test]
+    fn test_canonical() {
+        let l: [u8; 57] = hex_decode(ORDER).try_into().unwrap();
+        assert!(Scalar::from_canonical_bytes(&l).is_err());
+        let mut l_minus_1 = l;
+        l_minus_1[0] -= 1;
+        assert_eq!(Scalar::from_canonical_bytes(&l_minus_1), Ok(Scalar(l_minus_1)));
+        let mut top: [u8; 57] = [0; 57];
+        top[56] = 1;
+        assert!(Scalar::from_canonical_bytes(&top).is_err());
+        assert_eq!(Scalar::from_canonical_bytes(&[0; 57]).as_ref(), Ok(Scalar::ZERO));
+    }
+
+    // Reduction checked against Python: 2^912 - 1 mod L, and (2^456 - 1)^2 + 2^456 - 1 mod L.
+    #[test]
+    fn test_reduce() {
+        assert_eq!(Scalar::from_bytes_mod_order_wide(&[0xFF; 114]).to_bytes().to_vec(), hex_decode(
+            "81dee731a93f88112e1dad8707160f80293ea637fb19e320c5b624bb85c972cf17ae447cc4a34bc19c1aaf70d0e4b7bc522029b723f8392900"));
+        assert_eq!(Scalar::mul_add_bytes(&[0xFF; 57], &[0xFF; 57], &[0xFF; 57]).to_bytes().to_vec(), hex_decode(
+            "82aafb92568a92f4bb71ea9d3ee01833af7e8090b640505cd65db4e378c772cf17ae447cc4a34bc19c1aaf70d0e4b7bc522029b723f8392900"));
+
+        // L reduces to zero, and L + 1 to one.
+        let mut wide: [u8; 114] = [0; 114];
+        wide[..57].copy_from_slice(&hex_decode(ORDER));
+        assert_eq!(Scalar::from_bytes_mod_order_wide(&wide), *Scalar::ZERO);
+        wide[0] += 1;
+        let mut one: [u8; 57] = [0; 57];
+        one[0] = 1;
+        assert_eq!(Scalar::from_bytes_mod_order_wide(&wide), Scalar(one));
+    }
+}
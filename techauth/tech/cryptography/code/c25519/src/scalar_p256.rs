@@ -0,0 +1,57 @@
+#![allow(dead_code)]
+
+use crate::fp256::{self, Limbs, Modulus, Ring};
+
+// The integers modulo the prime order of the P-256 generator,
+//     n = 0xffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551,
+// held in Montgomery form like field_p256::Element, so the two share fp256. Encodings are
+// 32-octet big-endian integers, as in SEC1 and ECDSA.
+pub struct Order;
+
+impl Ring for Order {
+    const MODULUS: &'static Modulus = &Modulus {
+        m: [0xf3b9cac2fc632551, 0xbce6faada7179e84, 0xffffffffffffffff, 0xffffffff00000000],
+        m_prime: 0xccd1c8aaee00bc4f,
+        r2: [0x83244c95be79eea2, 0x4699799c49bd6fa6, 0x2845b2392b6bec59, 0x66e12d94f3d95620],
+    };
+    // 2^256 mod n
+    const ONE: Limbs = [0x0c46353d039cdaaf, 0x4319055258e8617b, 0x0000000000000000, 0x00000000ffffffff];
+}
+
+pub type Scalar = fp256::Element<Order>;
+
+#[cfg(test)]
+mod scalar_p256_test {
+    use crate::scalar_p256::Scalar;
+    use crate::test_util::hex_decode;
+
+    #[test]
+    fn test_reduction() {
+        let n: [u8; 32] = hex_decode("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551")
+            .try_into().unwrap();
+        assert!(Scalar::from_be_bytes(&n).is_err());
+        assert_eq!(Scalar::from_be_bytes_mod_order(&n), Scalar::ZERO.clone());
+
+        // 2^256 - 1 = n + 0x00000000ffffffff00000000000000004319055258e8617b0c46353d039cdaae
+        let reduced = Scalar::from_be_bytes_mod_order(&[0xFF; 32]);
+        assert_eq!(reduced.to_be_bytes().to_vec(),
+            hex_decode("00000000ffffffff00000000000000004319055258e8617b0c46353d039cdaae"));
+
+        let mut n_minus_1 = n;
+        n_minus_1[31] -= 1;
+        let minus_one = Scalar::from_be_bytes(&n_minus_1).unwrap();
+        assert_eq!(Scalar::add(&minus_one, Scalar::ONE).is_zero(), 1);
+        assert_eq!(Scalar::negate(&minus_one), Scalar::ONE.clone());
+        assert_eq!(Scalar::multiply(&minus_one, &minus_one), Scalar::ONE.clone());
+        assert_eq!(Scalar::multiply(&reduced, &Scalar::invert(&reduced)), Scalar::ONE.clone());
+
+        assert_eq!(minus_one.is_high(), 1);
+        assert_eq!(Scalar::ONE.is_high(), 0);
+        // (n - 1)/2 is low, (n + 1)/2 is high
+        let half: [u8; 32] = hex_decode("7fffffff800000007fffffffffffffffde737d56d38bcf4279dce5617e3192a8")
+            .try_into().unwrap();
+        let half = Scalar::from_be_bytes(&half).unwrap();
+        assert_eq!(half.is_high(), 0);
+        assert_eq!(Scalar::add(&half, Scalar::ONE).is_high(), 1);
+    }
+}
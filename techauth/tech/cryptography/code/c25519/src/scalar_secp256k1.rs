@@ -0,0 +1,58 @@
+#![allow(dead_code)]
+
+use crate::fp256::{self, Limbs, Modulus, Ring};
+
+// The integers modulo the prime order of the secp256k1 generator,
+//     n = 0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141,
+// in Montgomery form, like scalar_p256::Scalar. Encodings are 32-octet big-endian integers.
+pub struct Order;
+
+impl Ring for Order {
+    const MODULUS: &'static Modulus = &Modulus {
+        m: [0xbfd25e8cd0364141, 0xbaaedce6af48a03b, 0xfffffffffffffffe, 0xffffffffffffffff],
+        m_prime: 0x4b0dff665588b13f,
+        r2: [0x896cf21467d7d140, 0x741496c20e7cf878, 0xe697f5e45bcd07c6, 0x9d671cd581c69bc5],
+    };
+    // 2^256 mod n
+    const ONE: Limbs = [0x402da1732fc9bebf, 0x4551231950b75fc4, 0x0000000000000001, 0];
+}
+
+pub type Scalar = fp256::Element<Order>;
+
+#[cfg(test)]
+mod scalar_secp256k1_test {
+    use crate::scalar_secp256k1::Scalar;
+    use crate::test_util::hex_decode;
+
+    #[test]
+    fn test_reduction() {
+        let n: [u8; 32] = hex_decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+            .try_into().unwrap();
+        assert!(Scalar::from_be_bytes(&n).is_err());
+        assert_eq!(Scalar::from_be_bytes_mod_order(&n), Scalar::ZERO.clone());
+
+        // 2^256 - 1 = n + 0x000000000000000000000000000000014551231950b75fc4402da1732fc9bebe
+        let reduced = Scalar::from_be_bytes_mod_order(&[0xFF; 32]);
+        assert_eq!(reduced.to_be_bytes().to_vec(),
+            hex_decode("000000000000000000000000000000014551231950b75fc4402da1732fc9bebe"));
+        assert_eq!(Scalar::invert(&reduced).to_be_bytes().to_vec(),
+            hex_decode("1a2f66582f865803fc36e5fd38feed2cd04dd978f7b69d07f178ad1b6c2151c8"));
+        assert_eq!(Scalar::multiply(&reduced, &Scalar::invert(&reduced)), Scalar::ONE.clone());
+        assert_eq!(Scalar::invert(Scalar::ZERO), Scalar::ZERO.clone());
+
+        let mut n_minus_1 = n;
+        n_minus_1[31] -= 1;
+        let minus_one = Scalar::from_be_bytes(&n_minus_1).unwrap();
+        assert_eq!(Scalar::add(&minus_one, Scalar::ONE).is_zero(), 1);
+        assert_eq!(Scalar::subtract(Scalar::ZERO, Scalar::ONE), minus_one);
+        assert_eq!(Scalar::negate(&minus_one), Scalar::ONE.clone());
+        assert_eq!(Scalar::multiply(&minus_one, &minus_one), Scalar::ONE.clone());
+
+        // (n - 1)/2 is low, (n + 1)/2 is high
+        let half: [u8; 32] = hex_decode("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0")
+            .try_into().unwrap();
+        let half = Scalar::from_be_bytes(&half).unwrap();
+        assert_eq!(half.is_high(), 0);
+        assert_eq!(Scalar::add(&half, Scalar::ONE).is_high(), 1);
+    }
+}
@@ -0,0 +1,249 @@
+#![allow(dead_code)]
+
+use crate::field_secp256k1::Element;
+
+// secp256k1, the Koblitz curve y^2 = x^3 + 7 over GF(2^256 - 2^32 - 977) [SEC 2, 2.4.1], of
+// prime order n and cofactor 1.
+//
+// Point is a point in homogeneous projective coordinates (X:Y:Z), with the identity (0:1:0),
+// like p256::Point, and uses the complete formulas of Renes, Costello and Batina for a = 0,
+// Algorithms 7 and 9, with b3 = 3*b = 21. Scalar multiplication is a plain double-and-add-always
+// over all 256 bits: it does not use the GLV endomorphism, which halves the doublings but
+// needs a scalar decomposition that is hard to make constant time.
+#[derive(Clone, Debug)]
+pub struct Point {
+    pub(crate) x: Element,
+    pub(crate) y: Element,
+    pub(crate) z: Element,
+}
+
+pub const SCALAR_SIZE: usize = 32;
+pub const COMPRESSED_SIZE: usize = 33;
+pub const UNCOMPRESSED_SIZE: usize = 65;
+
+impl Point {
+    // 3*b = 21, in Montgomery form
+    const B3: &'static Element = &Element::new([0x0000001500005025, 0, 0, 0]);
+    // the generator G, in Montgomery form
+    const GX: &'static Element = &Element::new([
+        0xd7362e5a487e2097, 0x231e295329bc66db, 0x979f48c033fd129c, 0x9981e643e9089f48]);
+    const GY: &'static Element = &Element::new([
+        0xb15ea6d2d3dbabe2, 0x8dfc5d5d1f1dc64d, 0x70b6b59aac19c136, 0xcf3f851fd4a582d6]);
+
+    pub fn identity() -> Point {
+        Point { x: Element::ZERO.clone(), y: Element::ONE.clone(), z: Element::ZERO.clone() }
+    }
+
+    pub fn generator() -> Point {
+        Point { x: Point::GX.clone(), y: Point::GY.clone(), z: Element::ONE.clone() }
+    }
+
+    // curve_rhs returns x^3 + 7.
+    fn curve_rhs(x: &Element) -> Element {
+        Element::add(&Element::multiply(&Element::square(x), x), &Element::from_u64(7))
+    }
+
+    // from_affine returns the point (x, y), or an error if it is not on the curve.
+    pub fn from_affine(x: &Element, y: &Element) -> Result<Point, ()> {
+        if !Element::square(y).equal(&Point::curve_rhs(x)) {
+            return Err(());
+        }
+        Ok(Point { x: x.clone(), y: y.clone(), z: Element::ONE.clone() })
+    }
+
+    // lift_x returns the point with the given x and an even y, or an error if there is none
+    // [BIP-340, "Specification"].
+    pub fn lift_x(x: &Element) -> Result<Point, ()> {
+        let (y, was_square) = Element::sqrt(&Point::curve_rhs(x));
+        if was_square == 0 {
+            return Err(());
+        }
+        let mut y_even = Element::ZERO.clone();
+        y_even.select(&Element::negate(&y), &y, y.is_odd());
+        Ok(Point { x: x.clone(), y: y_even, z: Element::ONE.clone() })
+    }
+
+    // to_affine returns (x, y), or an error for the identity.
+    pub fn to_affine(&self) -> Result<(Element, Element), ()> {
+        if self.is_identity() {
+            return Err(());
+        }
+        let z_inv = Element::invert(&self.z);
+        Ok((Element::multiply(&self.x, &z_inv), Element::multiply(&self.y, &z_inv)))
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.is_zero() == 1
+    }
+
+    // Checks whether two points are equal: X1*Z2 == X2*Z1 and Y1*Z2 == Y2*Z1.
+    pub fn equal(&self, q: &Point) -> bool {
+        Element::multiply(&self.x, &q.z).equal(&Element::multiply(&q.x, &self.z))
+            && Element::multiply(&self.y, &q.z).equal(&Element::multiply(&q.y, &self.z))
+    }
+
+    pub fn negate(p: &Point) -> Point {
+        Point { x: p.x.clone(), y: Element::negate(&p.y), z: p.z.clone() }
+    }
+
+    // Select sets v to a if cond == 1, and to b if cond == 0.
+    pub fn select(&mut self, a: &Point, b: &Point, cond: u32) {
+        self.x.select(&a.x, &b.x, cond);
+        self.y.select(&a.y, &b.y, cond);
+        self.z.select(&a.z, &b.z, cond);
+    }
+
+    // add returns p + q with Algorithm 7 of Renes-Costello-Batina: 12M + 2m3b + 19a.
+    pub fn add(p: &Point, q: &Point) -> Point {
+        let b3 = Point::B3;
+        let t0 = Element::multiply(&p.x, &q.x);
+        let t1 = Element::multiply(&p.y, &q.y);
+        let t2 = Element::multiply(&p.z, &q.z);
+        let t3 = Element::multiply(&Element::add(&p.x, &p.y), &Element::add(&q.x, &q.y));
+        let t3 = Element::subtract(&t3, &Element::add(&t0, &t1));
+        let t4 = Element::multiply(&Element::add(&p.y, &p.z), &Element::add(&q.y, &q.z));
+        let t4 = Element::subtract(&t4, &Element::add(&t1, &t2));
+        let y3 = Element::multiply(&Element::add(&p.x, &p.z), &Element::add(&q.x, &q.z));
+        let y3 = Element::subtract(&y3, &Element::add(&t0, &t2));
+        let t0 = Element::add(&t0, &Element::add(&t0, &t0));
+        let t2 = Element::multiply(b3, &t2);
+        let z3 = Element::add(&t1, &t2);
+        let t1 = Element::subtract(&t1, &t2);
+        let y3 = Element::multiply(b3, &y3);
+        let x3 = Element::subtract(&Element::multiply(&t3, &t1), &Element::multiply(&t4, &y3));
+        let y3 = Element::add(&Element::multiply(&t1, &z3), &Element::multiply(&y3, &t0));
+        let z3 = Element::add(&Element::multiply(&z3, &t4), &Element::multiply(&t0, &t3));
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    // double returns 2p with Algorithm 9 of Renes-Costello-Batina: 6M + 2S + 1m3b + 9a.
+    pub fn double(p: &Point) -> Point {
+        let t0 = Element::square(&p.y);
+        let z3 = Element::add(&t0, &t0);
+        let z3 = Element::add(&z3, &z3);
+        let z3 = Element::add(&z3, &z3);
+        let t1 = Element::multiply(&p.y, &p.z);
+        let t2 = Element::multiply(Point::B3, &Element::square(&p.z));
+        let x3 = Element::multiply(&t2, &z3);
+        let y3 = Element::add(&t0, &t2);
+        let z3 = Element::multiply(&t1, &z3);
+        let t2 = Element::add(&t2, &Element::add(&t2, &t2));
+        let t0 = Element::subtract(&t0, &t2);
+        let y3 = Element::add(&x3, &Element::multiply(&t0, &y3));
+        let x3 = Element::multiply(&t0, &Element::multiply(&p.x, &p.y));
+        let x3 = Element::add(&x3, &x3);
+        Point { x: x3, y: y3, z: z3 }
+    }
+
+    // scalar_mul returns [k]P for a big-endian k of 32 octets, doubling and adding for every
+    // bit and keeping the sum only if the bit is set.
+    pub fn scalar_mul(k: &[u8; SCALAR_SIZE], p: &Point) -> Point {
+        let mut q = Point::identity();
+        for byte in k {
+            for i in (0..8).rev() {
+                q = Point::double(&q);
+                let sum = Point::add(&q, p);
+                let prev = q.clone();
+                q.select(&sum, &prev, ((byte >> i) & 1) as u32);
+            }
+        }
+        q
+    }
+
+    pub fn basepoint_mul(k: &[u8; SCALAR_SIZE]) -> Point {
+        Point::scalar_mul(k, &Point::generator())
+    }
+
+    // to_uncompressed returns the SEC1 encoding 0x04 || x || y.
+    pub fn to_uncompressed(&self) -> Result<[u8; UNCOMPRESSED_SIZE], ()> {
+        let (x, y) = self.to_affine()?;
+        let mut b: [u8; UNCOMPRESSED_SIZE] = [0; UNCOMPRESSED_SIZE];
+        b[0] = 0x04;
+        b[1..33].copy_from_slice(&x.to_be_bytes());
+        b[33..].copy_from_slice(&y.to_be_bytes());
+        Ok(b)
+    }
+
+    // to_compressed returns the SEC1 encoding (0x02 + (y mod 2)) || x.
+    pub fn to_compressed(&self) -> Result<[u8; COMPRESSED_SIZE], ()> {
+        let (x, y) = self.to_affine()?;
+        let mut b: [u8; COMPRESSED_SIZE] = [0; COMPRESSED_SIZE];
+        b[0] = 0x02 | y.is_odd() as u8;
+        b[1..].copy_from_slice(&x.to_be_bytes());
+        Ok(b)
+    }
+
+    // from_sec1 decodes and validates a compressed or uncompressed SEC1 encoding, like
+    // p256::Point::from_sec1.
+    pub fn from_sec1(b: &[u8]) -> Result<Point, ()> {
+        match (b.first(), b.len()) {
+            (Some(0x04), UNCOMPRESSED_SIZE) => {
+                let x = Element::from_be_bytes(&b[1..33].try_into().unwrap())?;
+                let y = Element::from_be_bytes(&b[33..].try_into().unwrap())?;
+                Point::from_affine(&x, &y)
+            }
+            (Some(0x02 | 0x03), COMPRESSED_SIZE) => {
+                let x = Element::from_be_bytes(&b[1..].try_into().unwrap())?;
+                let even = Point::lift_x(&x)?;
+                let mut p = even.clone();
+                p.select(&Point::negate(&even), &even, (b[0] & 1) as u32);
+                Ok(p)
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod secp256k1_test {
+    use crate::secp256k1::{Point, SCALAR_SIZE};
+    use crate::test_util::hex_decode;
+
+    #[test]
+    fn test_group_law() {
+        let g = Point::generator();
+        let g2 = Point::double(&g);
+        assert!(Point::add(&g, &g).equal(&g2));
+        assert!(Point::add(&g, &Point::identity()).equal(&g));
+        assert!(Point::double(&Point::identity()).is_identity());
+        assert!(Point::add(&g, &Point::negate(&g)).is_identity());
+
+        // [3]G, whose x is the public key of BIP-340 test vector 0
+        let mut three: [u8; SCALAR_SIZE] = [0; SCALAR_SIZE];
+        three[31] = 3;
+        let g3 = Point::basepoint_mul(&three);
+        assert!(g3.equal(&Point::add(&g2, &g)));
+        assert_eq!(g3.to_compressed().unwrap().to_vec(),
+            hex_decode("02f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"));
+
+        let mut n: [u8; SCALAR_SIZE] = hex_decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141")
+            .try_into().unwrap();
+        assert!(Point::basepoint_mul(&n).is_identity());
+        n[31] -= 1;
+        assert!(Point::basepoint_mul(&n).equal(&Point::negate(&g)));
+    }
+
+    #[test]
+    fn test_sec1() {
+        let g = Point::generator();
+        let uncompressed = g.to_uncompressed().unwrap();
+        assert_eq!(uncompressed.to_vec(), hex_decode(
+            "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"));
+        assert!(Point::from_sec1(&uncompressed).unwrap().equal(&g));
+        let neg = Point::negate(&g);
+        assert!(Point::from_sec1(&g.to_compressed().unwrap()).unwrap().equal(&g));
+        assert!(Point::from_sec1(&neg.to_compressed().unwrap()).unwrap().equal(&neg));
+
+        let mut off_curve = uncompressed;
+        off_curve[64] ^= 1;
+        assert!(Point::from_sec1(&off_curve).is_err());
+        assert!(Point::from_sec1(&uncompressed[..33]).is_err());
+        assert!(Point::identity().to_compressed().is_err());
+        // x = 5 is not on the curve: 5^3 + 7 is not a square
+        let mut x5: [u8; 33] = [0; 33];
+        x5[0] = 0x02;
+        x5[32] = 5;
+        assert!(Point::from_sec1(&x5).is_err());
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 // constant_time_compare returns true if the two slices, x and y, have equal contents
 // and false otherwise. The time taken is a function of the length of the slices and
 // is independent of the contents. If the lengths of x and y do not match it
@@ -22,3 +24,8 @@ pub fn is_zero(a: &[u8]) -> bool {
     }
     acc == 0
 }
+
+// constant_time_byte_eq returns 1 if x == y and 0 otherwise.
+pub fn constant_time_byte_eq(x: u8, y: u8) -> u32 {
+    (((x ^ y) as u32).wrapping_sub(1)) >> 31
+}
@@ -0,0 +1,10 @@
+#![allow(dead_code)]
+
+// Helpers shared by the test modules.
+
+// hex_decode decodes a hex string, failing the test if it is malformed.
+pub(crate) fn hex_decode(s: &str) -> Vec<u8> {
+    let r = hex::decode(s);
+    assert!(r.is_ok());
+    r.unwrap()
+}
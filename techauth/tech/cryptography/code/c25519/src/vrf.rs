@@ -0,0 +1,305 @@
+#![allow(dead_code)]
+
+use sha2::{Digest, Sha512};
+
+use crate::ed25519::{self, PrivateKey, PublicKey};
+use crate::edwards::Point;
+use crate::hash_to_curve;
+use crate::scalar::Scalar;
+use crate::subtle;
+
+// ECVRF, the elliptic curve verifiable random function of RFC 9381, on edwards25519.
+//
+// The holder of an Ed25519 key turns an input alpha into an output beta = proof_to_hash(pi)
+// along with a proof pi, which anyone can check against the public key Y = [x]B:
+//     H = encode_to_curve(Y, alpha),  Gamma = [x]H,  k = nonce(H),
+//     c = challenge(Y, H, Gamma, [k]B, [k]H),  s = k + c*x mod l,
+//     pi = Gamma || c || s,  beta = SHA-512(suite || 0x03 || [8]Gamma || 0x00)
+// The verifier recomputes U = [s]B - [c]Y and V = [s]H - [c]Gamma and checks that they give
+// back the challenge c. The two suites differ only in suite_string and encode_to_curve:
+//     EDWARDS25519-SHA512-TAI   0x03  try-and-increment [RFC 9381, 5.4.1.1]
+//     EDWARDS25519-SHA512-ELL2  0x04  edwards25519_XMD:SHA-512_ELL2_NU_ [RFC 9380]
+// Keys are Ed25519 keys [RFC 8032, 5.1.5], the challenge is cLen = 16 octets long, and the
+// nonce is derived from the prefix of the key as Ed25519 does [RFC 9381, 5.4.2.2].
+
+pub const PROOF_SIZE: usize = 80;
+pub const OUTPUT_SIZE: usize = 64;
+
+const CHALLENGE_SIZE: usize = 16;
+
+// domain separators of the hashes [RFC 9381, 5.4]
+const ENCODE_TO_CURVE_DOMAIN_SEPARATOR_FRONT: u8 = 0x01;
+const CHALLENGE_GENERATION_DOMAIN_SEPARATOR_FRONT: u8 = 0x02;
+const PROOF_TO_HASH_DOMAIN_SEPARATOR_FRONT: u8 = 0x03;
+const DOMAIN_SEPARATOR_BACK: u8 = 0x00;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Suite {
+    EdwardsSha512Tai,
+    EdwardsSha512Ell2,
+}
+
+impl Suite {
+    fn suite_string(self) -> u8 {
+        match self {
+            Suite::EdwardsSha512Tai => 0x03,
+            Suite::EdwardsSha512Ell2 => 0x04,
+        }
+    }
+
+    // encode_to_curve maps alpha, salted with the encoding of the public key, to a point H of
+    // order l [RFC 9381, 5.4.1].
+    fn encode_to_curve(self, public: &[u8; 32], alpha: &[u8]) -> Result<Point, ()> {
+        match self {
+            Suite::EdwardsSha512Tai => encode_to_curve_try_and_increment(public, alpha),
+            Suite::EdwardsSha512Ell2 => {
+                let mut dst: Vec<u8> = b"ECVRF_edwards25519_XMD:SHA-512_ELL2_NU_".to_vec();
+                dst.push(self.suite_string());
+                let mut msg: Vec<u8> = Vec::with_capacity(public.len() + alpha.len());
+                msg.extend_from_slice(public);
+                msg.extend_from_slice(alpha);
+                hash_to_curve::encode_to_curve_edwards25519(&msg, &dst)
+            }
+        }
+    }
+
+    // challenge_generation hashes the five points into the 16-octet challenge c [RFC 9381, 5.4.3].
+    fn challenge_generation(self, points: [&[u8; 32]; 5]) -> [u8; CHALLENGE_SIZE] {
+        let mut h = Sha512::new();
+        h.update([self.suite_string(), CHALLENGE_GENERATION_DOMAIN_SEPARATOR_FRONT]);
+        for p in points {
+            h.update(p);
+        }
+        h.update([DOMAIN_SEPARATOR_BACK]);
+        h.finalize()[..CHALLENGE_SIZE].try_into().unwrap()
+    }
+}
+
+// decode_point is string_to_point of RFC 9381, which decodes as RFC 8032, Section 5.1.3, does:
+// unlike Point::from_bytes, it rejects the non-canonical encodings.
+fn decode_point(b: &[u8; 32]) -> Result<Point, ()> {
+    let p = Point::from_bytes(b)?;
+    if !subtle::constant_time_compare(&p.to_bytes(), b) {
+        return Err(());
+    }
+    Ok(p)
+}
+
+// encode_to_curve_try_and_increment hashes alpha with a one-octet counter until the first
+// half of the digest is the encoding of a point, and returns that point times the cofactor.
+// Each try succeeds with probability about 1/2. Its running time depends on alpha.
+fn encode_to_curve_try_and_increment(public: &[u8; 32], alpha: &[u8]) -> Result<Point, ()> {
+    for ctr in 0..=255u8 {
+        let mut h = Sha512::new();
+        h.update([Suite::EdwardsSha512Tai.suite_string(), ENCODE_TO_CURVE_DOMAIN_SEPARATOR_FRONT]);
+        h.update(public);
+        h.update(alpha);
+        h.update([ctr, DOMAIN_SEPARATOR_BACK]);
+        let digest: [u8; 64] = h.finalize().into();
+        if let Ok(p) = decode_point(&digest[..32].try_into().unwrap()) {
+            let p = Point::mul_by_cofactor(&p);
+            if !p.is_identity() {
+                return Ok(p);
+            }
+        }
+    }
+    Err(())
+}
+
+// proof_to_hash returns the VRF output beta of a proof [RFC 9381, 5.2]. It does not verify
+// the proof: only PublicKey::vrf_verify tells whether beta is the output for alpha.
+pub fn proof_to_hash(suite: Suite, pi: &[u8; PROOF_SIZE]) -> Result<[u8; OUTPUT_SIZE], ()> {
+    let (gamma, _, _) = decode_proof(pi)?;
+    Ok(gamma_to_hash(suite, &gamma))
+}
+
+fn gamma_to_hash(suite: Suite, gamma: &Point) -> [u8; OUTPUT_SIZE] {
+    let mut h = Sha512::new();
+    h.update([suite.suite_string(), PROOF_TO_HASH_DOMAIN_SEPARATOR_FRONT]);
+    h.update(Point::mul_by_cofactor(gamma).to_bytes());
+    h.update([DOMAIN_SEPARATOR_BACK]);
+    h.finalize().into()
+}
+
+// decode_proof splits pi into Gamma, c and s, and rejects s >= l [RFC 9381, 5.4.4].
+fn decode_proof(pi: &[u8; PROOF_SIZE]) -> Result<(Point, Scalar, Scalar), ()> {
+    let gamma = decode_point(&pi[..32].try_into().unwrap())?;
+    let mut c: [u8; 32] = [0; 32];
+    c[..CHALLENGE_SIZE].copy_from_slice(&pi[32..32 + CHALLENGE_SIZE]);
+    let s = Scalar::from_canonical_bytes(pi[32 + CHALLENGE_SIZE..].try_into().unwrap())?;
+    Ok((gamma, Scalar(c), s))
+}
+
+impl PrivateKey {
+    // vrf_prove returns the proof pi for alpha [RFC 9381, 5.1]. The output beta is
+    // proof_to_hash(pi). The secret scalar enters only constant-time scalar multiplications;
+    // with the TAI suite, the time taken to hash alpha to the curve depends on alpha.
+    pub fn vrf_prove(&self, suite: Suite, alpha: &[u8]) -> Result<[u8; PROOF_SIZE], ()> {
+        let public = &self.public_key().public;
+        let h = suite.encode_to_curve(public, alpha)?;
+        let h_bytes = h.to_bytes();
+        let gamma = Point::scalar_mul(&self.scalar, &h);
+
+        // k = SHA-512(prefix || h_string) mod l
+        let k = ed25519::hash_to_scalar(&[&self.prefix, &h_bytes]);
+        let kb = Point::basepoint_mul(&k.to_bytes());
+        let kh = Point::scalar_mul(&k.to_bytes(), &h);
+        let gamma_bytes = gamma.to_bytes();
+        let c = suite.challenge_generation([public, &h_bytes, &gamma_bytes, &kb.to_bytes(), &kh.to_bytes()]);
+
+        // s = (k + c * x) mod l
+        let mut c_bytes: [u8; 32] = [0; 32];
+        c_bytes[..CHALLENGE_SIZE].copy_from_slice(&c);
+        let s = Scalar::mul_add_bytes(&c_bytes, &self.scalar, &k.to_bytes());
+
+        let mut pi: [u8; PROOF_SIZE] = [0; PROOF_SIZE];
+        pi[..32].copy_from_slice(&gamma_bytes);
+        pi[32..32 + CHALLENGE_SIZE].copy_from_slice(&c);
+        pi[32 + CHALLENGE_SIZE..].copy_from_slice(&s.to_bytes());
+        Ok(pi)
+    }
+}
+
+impl PublicKey {
+    // vrf_verify checks the proof pi for alpha and returns the output beta [RFC 9381, 5.3].
+    // It validates the public key as ECVRF_validate_key does, rejecting non-canonical
+    // encodings and points of small order, so that beta is unique even for an adversarial key.
+    pub fn vrf_verify(&self, suite: Suite, alpha: &[u8], pi: &[u8; PROOF_SIZE]) -> Result<[u8; OUTPUT_SIZE], ()> {
+        let y = decode_point(&self.public)?;
+        if y.is_small_order() {
+            return Err(());
+        }
+        let (gamma, c, s) = decode_proof(pi)?;
+        let h = suite.encode_to_curve(&self.public, alpha)?;
+
+        // U = [s]B - [c]Y,  V = [s]H - [c]Gamma, in variable time since all inputs are public.
+        // The points are negated rather than c, for the same reason as in Ed25519 verification.
+        let u = Point::vartime_double_scalar_base_mul(&c, &Point::negate(&y), &s);
+        let v = Point::vartime_multiscalar_mul(&[s, c.clone()], &[h.clone(), Point::negate(&gamma)]);
+        let expected = suite.challenge_generation(
+            [&self.public, &h.to_bytes(), &pi[..32].try_into().unwrap(), &u.to_bytes(), &v.to_bytes()]);
+
+        if subtle::constant_time_compare(&expected, &c.to_bytes()[..CHALLENGE_SIZE]) {
+            Ok(gamma_to_hash(suite, &gamma))
+        } else {
+            Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod vrf_test {
+    use crate::ed25519::{PrivateKey, PublicKey};
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+    use crate::vrf::{proof_to_hash, Suite, PROOF_SIZE};
+
+    fn _test_vrf_(suite: Suite, sk_hex: &str, pk_hex: &str, alpha_hex: &str, h_hex: &str, pi_hex: &str, beta_hex: &str) {
+        let sk = PrivateKey::new(hex_decode(sk_hex).try_into().unwrap());
+        let pk = sk.public_key();
+        assert_eq!(pk.to_bytes().to_vec(), hex_decode(pk_hex));
+        let alpha = hex_decode(alpha_hex);
+
+        assert_eq!(suite.encode_to_curve(&pk.to_bytes(), &alpha).unwrap().to_bytes().to_vec(), hex_decode(h_hex));
+        let pi = sk.vrf_prove(suite, &alpha).unwrap();
+        assert_eq!(pi.to_vec(), hex_decode(pi_hex));
+        assert_eq!(proof_to_hash(suite, &pi).unwrap().to_vec(), hex_decode(beta_hex));
+        assert_eq!(pk.vrf_verify(suite, &alpha, &pi).unwrap().to_vec(), hex_decode(beta_hex));
+
+        // The proof is bound to alpha, to the key and to the suite.
+        assert!(pk.vrf_verify(suite, b"alpha", &pi).is_err());
+        assert!(PrivateKey::new([7; 32]).public_key().vrf_verify(suite, &alpha, &pi).is_err());
+        let other = if suite == Suite::EdwardsSha512Tai { Suite::EdwardsSha512Ell2 } else { Suite::EdwardsSha512Tai };
+        assert!(pk.vrf_verify(other, &alpha, &pi).is_err());
+        for i in [0, 32, 47, 48, 79] {
+            let mut tampered = pi;
+            tampered[i] ^= 0x01;
+            assert!(pk.vrf_verify(suite, &alpha, &tampered).is_err());
+        }
+    }
+
+    // ECVRF-EDWARDS25519-SHA512-TAI test vectors from RFC 9381, Appendix B.3 (Examples 16-18).
+    #[test]
+    fn test_vrf_tai_rfc9381() {
+        _test_vrf_(
+            Suite::EdwardsSha512Tai,
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "91bbed02a99461df1ad4c6564a5f5d829d0b90cfc7903e7a5797bd658abf3318",
+            "8657106690b5526245a92b003bb079ccd1a92130477671f6fc01ad16f26f723f26f8a57ccaed74ee1b190bed1f479d9727d2d0f9b005a6e456a35d4fb0daab1268a1b0db10836d9826a528ca76567805",
+            "90cf1df3b703cce59e2a35b925d411164068269d7b2d29f3301c03dd757876ff66b71dda49d2de59d03450451af026798e8f81cd2e333de5cdf4f3e140fdd8ae");
+        _test_vrf_(
+            Suite::EdwardsSha512Tai,
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "5b659fc3d4e9263fd9a4ed1d022d75eaacc20df5e09f9ea937502396598dc551",
+            "f3141cd382dc42909d19ec5110469e4feae18300e94f304590abdced48aed5933bf0864a62558b3ed7f2fea45c92a465301b3bbf5e3e54ddf2d935be3b67926da3ef39226bbc355bdc9850112c8f4b02",
+            "eb4440665d3891d668e7e0fcaf587f1b4bd7fbfe99d0eb2211ccec90496310eb5e33821bc613efb94db5e5b54c70a848a0bef4553a41befc57663b56373a5031");
+        _test_vrf_(
+            Suite::EdwardsSha512Tai,
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "bf4339376f5542811de615e3313d2b36f6f53c0acfebb482159711201192576a",
+            "9bc0f79119cc5604bf02d23b4caede71393cedfbb191434dd016d30177ccbf8096bb474e53895c362d8628ee9f9ea3c0e52c7a5c691b6c18c9979866568add7a2d41b00b05081ed0f58ee5e31b3a970e",
+            "645427e5d00c62a23fb703732fa5d892940935942101e456ecca7bb217c61c452118fec1219202a0edcf038bb6373241578be7217ba85a2687f7a0310b2df19f");
+    }
+
+    // ECVRF-EDWARDS25519-SHA512-ELL2 test vectors from RFC 9381, Appendix B.4 (Examples 19-21).
+    #[test]
+    fn test_vrf_ell2_rfc9381() {
+        _test_vrf_(
+            Suite::EdwardsSha512Ell2,
+            "9d61b19deffd5a60ba844af492ec2cc44449c5697b326919703bac031cae7f60",
+            "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+            "",
+            "b8066ebbb706c72b64390324e4a3276f129569eab100c26b9f05011200c1bad9",
+            "7d9c633ffeee27349264cf5c667579fc583b4bda63ab71d001f89c10003ab46f14adf9a3cd8b8412d9038531e865c341cafa73589b023d14311c331a9ad15ff2fb37831e00f0acaa6d73bc9997b06501",
+            "9d574bf9b8302ec0fc1e21c3ec5368269527b87b462ce36dab2d14ccf80c53cccf6758f058c5b1c856b116388152bbe509ee3b9ecfe63d93c3b4346c1fbc6c54");
+        _test_vrf_(
+            Suite::EdwardsSha512Ell2,
+            "4ccd089b28ff96da9db6c346ec114e0f5b8a319f35aba624da8cf6ed4fb8a6fb",
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+            "72",
+            "76ac3ccb86158a9104dff819b1ca293426d305fd76b39b13c9356d9b58c08e57",
+            "47b327393ff2dd81336f8a2ef10339112401253b3c714eeda879f12c509072ef055b48372bb82efbdce8e10c8cb9a2f9d60e93908f93df1623ad78a86a028d6bc064dbfc75a6a57379ef855dc6733801",
+            "38561d6b77b71d30eb97a062168ae12b667ce5c28caccdf76bc88e093e4635987cd96814ce55b4689b3dd2947f80e59aac7b7675f8083865b46c89b2ce9cc735");
+        _test_vrf_(
+            Suite::EdwardsSha512Ell2,
+            "c5aa8df43f9f837bedb7442f31dcb7b166d38535076f094b85ce3a2e0b4458f7",
+            "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+            "af82",
+            "13d2a8b5ca32db7e98094a61f656a08c6c964344e058879a386a947a4e189ed1",
+            "926e895d308f5e328e7aa159c06eddbe56d06846abf5d98c2512235eaa57fdce35b46edfc655bc828d44ad09d1150f31374e7ef73027e14760d42e77341fe05467bb286cc2c9d7fde29120a0b2320d04",
+            "121b7f9b9aaaa29099fc04a94ba52784d44eac976dd1a3cca458733be5cd090a7b5fbd148444f17f8daf1fb55cb04b1ae85a626e30a54b4b0f8abf4a43314a58");
+    }
+
+    // Keys of small order and non-canonical encodings of Y or Gamma are INVALID, and so is s >= l.
+    #[test]
+    fn test_vrf_invalid() {
+        let sk = PrivateKey::new([0x42; 32]);
+        let pk = sk.public_key();
+        let pi = sk.vrf_prove(Suite::EdwardsSha512Ell2, b"abc").unwrap();
+        assert!(pk.vrf_verify(Suite::EdwardsSha512Ell2, b"abc", &pi).is_ok());
+
+        // the identity, and y = 1 + p, a non-canonical encoding of it
+        let mut identity: [u8; 32] = [0; 32];
+        identity[0] = 1;
+        assert!(PublicKey::new(identity).vrf_verify(Suite::EdwardsSha512Ell2, b"abc", &pi).is_err());
+        let mut non_canonical: [u8; 32] = [0xFF; 32];
+        non_canonical[0] = 0xEE;
+        non_canonical[31] = 0x7F;
+        assert!(PublicKey::new(non_canonical).vrf_verify(Suite::EdwardsSha512Ell2, b"abc", &pi).is_err());
+
+        let mut bad_gamma: [u8; PROOF_SIZE] = pi;
+        bad_gamma[..32].copy_from_slice(&non_canonical);
+        assert!(proof_to_hash(Suite::EdwardsSha512Ell2, &bad_gamma).is_err());
+
+        let mut bad_s: [u8; PROOF_SIZE] = pi;
+        bad_s[48..].copy_from_slice(&Scalar::ORDER);
+        assert!(proof_to_hash(Suite::EdwardsSha512Ell2, &bad_s).is_err());
+        assert!(pk.vrf_verify(Suite::EdwardsSha512Ell2, b"abc", &bad_s).is_err());
+    }
+}
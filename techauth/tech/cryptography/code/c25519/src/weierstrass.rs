@@ -0,0 +1,379 @@
+#![allow(dead_code)]
+
+use crate::field::Element;
+use crate::weierstrass_curve::WeierstrassCurve;
+
+// Wei25519, the short Weierstrass form of Curve25519 [NIST SP 800-186, 3.2.2.1 and B.1;
+// see techauth/tech/fintech/payments/notes/NIST.SP.800-186.pdf]
+//     y^2 = x^3 + a*x + b
+// over GF(2^255-19). Curve25519, v^2 = u^3 + A*u^2 + u, becomes this curve under the
+// substitution x = u + A/3, y = v, which yields
+//     a = (3 - A^2) / 3,  b = (2*A^3 - 9*A) / 27.
+// The map is an isomorphism of groups, so a ladder on u-coordinates and double-and-add on
+// Wei25519 compute the same multiples. Wei25519 is here for interoperability with tools
+// that only know short Weierstrass curves; the arithmetic is variable-time.
+
+// AffinePoint is a point (x, y) of Wei25519 other than the point at infinity.
+#[derive(Clone, Debug)]
+pub struct AffinePoint {
+    pub(crate) x: Element,
+    pub(crate) y: Element,
+}
+
+// JacobianPoint represents (X/Z^2, Y/Z^3), or the point at infinity when Z = 0.
+#[derive(Clone, Debug)]
+pub struct JacobianPoint {
+    pub(crate) x: Element,
+    pub(crate) y: Element,
+    pub(crate) z: Element,
+}
+
+impl AffinePoint {
+    // a = 0x2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa984914a144
+    pub const A: &'static Element = &Element(
+        750520991260996, 1501199875790165, 750599937895082, 1501199875790165, 750599937895082);
+    // b = 0x7b425ed097b425ed097b425ed097b425ed097b425ed097b4260b5e9c7710c864
+    pub const B: &'static Element = &Element(
+        948451035629668, 83399993099457, 667199944795629, 833999930994536, 2168399820585794);
+    // A/3 = 486662/3, the shift between u and x
+    const DELTA: &'static Element = &Element(
+        750599938057297, 1501199875790165, 750599937895082, 1501199875790165, 750599937895082);
+
+    // The generator G = (9 + A/3, v) of SP 800-186, the image of the X25519 base point u = 9.
+    pub fn generator() -> AffinePoint {
+        let mut nine: [u8; 32] = [0; 32];
+        nine[0] = 9;
+        // v = 0x20ae19a1b8a086b4e01edd2c7748d14c923d4d7e6d7c61b229e9c5a27eced3d9 is odd.
+        AffinePoint::from_montgomery_u(&nine, 1).unwrap()
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let rhs = Element::add(
+            &Element::multiply(&self.x, &Element::add(&Element::square(&self.x), AffinePoint::A)),
+            AffinePoint::B);
+        Element::square(&self.y).equal(&rhs)
+    }
+
+    // from_montgomery maps the Curve25519 point (u, v) to (u + A/3, v).
+    pub fn from_montgomery(u: &Element, v: &Element) -> AffinePoint {
+        AffinePoint { x: Element::add(u, AffinePoint::DELTA), y: v.clone() }
+    }
+
+    // to_montgomery maps (x, y) to the Curve25519 point (x - A/3, y).
+    pub fn to_montgomery(&self) -> (Element, Element) {
+        (Element::subtract(&self.x, AffinePoint::DELTA), self.y.clone())
+    }
+
+    // from_montgomery_u returns the point with x = u + A/3 whose y-coordinate has the given
+    // sign, 0 or 1. A u-coordinate determines a point only up to sign, and the X25519
+    // functions ignore it. It fails if u lies on the twist of Curve25519.
+    pub fn from_montgomery_u(u: &[u8; 32], sign: u32) -> Result<AffinePoint, ()> {
+        let x = Element::add(&Element::from_le_bytes(*u), AffinePoint::DELTA);
+        let rhs = Element::add(
+            &Element::multiply(&x, &Element::add(&Element::square(&x), AffinePoint::A)),
+            AffinePoint::B);
+        let (y, was_square) = Element::sqrt_ratio(&rhs, Element::ONE);
+        if was_square == 0 {
+            return Err(());
+        }
+        let mut y_signed = Element::ZERO.clone();
+        y_signed.select(&Element::negate(&y), &y, sign & 1);
+        Ok(AffinePoint { x, y: y_signed })
+    }
+
+    // to_montgomery_u returns the X25519 encoding of the u-coordinate x - A/3.
+    pub fn to_montgomery_u(&self) -> [u8; 32] {
+        Element::subtract(&self.x, AffinePoint::DELTA).to_le_bytes()
+    }
+}
+
+impl JacobianPoint {
+    pub fn identity() -> JacobianPoint {
+        JacobianPoint {
+            x: Element::ONE.clone(),
+            y: Element::ONE.clone(),
+            z: Element::ZERO.clone(),
+        }
+    }
+
+    pub fn from_affine(p: &AffinePoint) -> JacobianPoint {
+        JacobianPoint { x: p.x.clone(), y: p.y.clone(), z: Element::ONE.clone() }
+    }
+
+    // to_affine fails on the point at infinity, which has no affine coordinates.
+    pub fn to_affine(&self) -> Result<AffinePoint, ()> {
+        if self.is_identity() {
+            return Err(());
+        }
+        let z_inv = Element::invert(&self.z);
+        let z_inv2 = Element::square(&z_inv);
+        Ok(AffinePoint {
+            x: Element::multiply(&self.x, &z_inv2),
+            y: Element::multiply(&self.y, &Element::multiply(&z_inv2, &z_inv)),
+        })
+    }
+
+    pub fn is_identity(&self) -> bool {
+        self.z.equal(Element::ZERO)
+    }
+
+    // returns true if p and q represent the same point: X1*Z2^2 == X2*Z1^2 and Y1*Z2^3 == Y2*Z1^3.
+    pub fn equal(&self, q: &JacobianPoint) -> bool {
+        if self.is_identity() || q.is_identity() {
+            return self.is_identity() && q.is_identity();
+        }
+        let z1z1 = Element::square(&self.z);
+        let z2z2 = Element::square(&q.z);
+        Element::multiply(&self.x, &z2z2).equal(&Element::multiply(&q.x, &z1z1)) &&
+            Element::multiply(&self.y, &Element::multiply(&z2z2, &q.z))
+                .equal(&Element::multiply(&q.y, &Element::multiply(&z1z1, &self.z)))
+    }
+
+    pub fn negate(p: &JacobianPoint) -> JacobianPoint {
+        JacobianPoint { x: p.x.clone(), y: Element::negate(&p.y), z: p.z.clone() }
+    }
+
+    // calculate 2p with the dbl-2007-bl formulas for arbitrary a.
+    pub fn double(p: &JacobianPoint) -> JacobianPoint {
+        let xx = Element::square(&p.x);
+        let yy = Element::square(&p.y);
+        let yyyy = Element::square(&yy);
+        let zz = Element::square(&p.z);
+        // S = 2*((X1 + YY)^2 - XX - YYYY)
+        let s = Element::mul32(
+            &Element::subtract(&Element::subtract(&Element::square(&Element::add(&p.x, &yy)), &xx), &yyyy), 2);
+        // M = 3*XX + a*ZZ^2
+        let m = Element::add(&Element::mul32(&xx, 3), &Element::multiply(AffinePoint::A, &Element::square(&zz)));
+        // T = M^2 - 2*S
+        let t = Element::subtract(&Element::square(&m), &Element::mul32(&s, 2));
+        JacobianPoint {
+            y: Element::subtract(&Element::multiply(&m, &Element::subtract(&s, &t)), &Element::mul32(&yyyy, 8)),
+            x: t,
+            z: Element::subtract(&Element::subtract(&Element::square(&Element::add(&p.y, &p.z)), &yy), &zz),
+        }
+    }
+
+    // calculate p + q with the add-2007-bl formulas. They are incomplete: the cases of
+    // the point at infinity, p = q and p = -q are handled separately.
+    pub fn add(p: &JacobianPoint, q: &JacobianPoint) -> JacobianPoint {
+        if p.is_identity() {
+            return q.clone();
+        }
+        if q.is_identity() {
+            return p.clone();
+        }
+        let z1z1 = Element::square(&p.z);
+        let z2z2 = Element::square(&q.z);
+        let u1 = Element::multiply(&p.x, &z2z2);
+        let u2 = Element::multiply(&q.x, &z1z1);
+        let s1 = Element::multiply(&p.y, &Element::multiply(&q.z, &z2z2));
+        let s2 = Element::multiply(&q.y, &Element::multiply(&p.z, &z1z1));
+        let h = Element::subtract(&u2, &u1);
+        let r = Element::mul32(&Element::subtract(&s2, &s1), 2);
+        if h.equal(Element::ZERO) {
+            return if r.equal(Element::ZERO) { JacobianPoint::double(p) } else { JacobianPoint::identity() };
+        }
+        let i = Element::square(&Element::mul32(&h, 2));
+        let j = Element::multiply(&h, &i);
+        let v = Element::multiply(&u1, &i);
+        let x3 = Element::subtract(&Element::subtract(&Element::square(&r), &j), &Element::mul32(&v, 2));
+        JacobianPoint {
+            y: Element::subtract(
+                &Element::multiply(&r, &Element::subtract(&v, &x3)),
+                &Element::mul32(&Element::multiply(&s1, &j), 2)),
+            x: x3,
+            z: Element::multiply(
+                &Element::subtract(&Element::subtract(&Element::square(&Element::add(&p.z, &q.z)), &z1z1), &z2z2),
+                &h),
+        }
+    }
+
+    // vartime_scalar_mul returns [k]P for a little-endian 256-bit k with left-to-right
+    // double-and-add. Its running time depends on k: use it only on public data.
+    pub fn vartime_scalar_mul(k: &[u8; 32], p: &JacobianPoint) -> JacobianPoint {
+        let mut q = JacobianPoint::identity();
+        for pos in (0..256).rev() {
+            q = JacobianPoint::double(&q);
+            if (k[pos / 8] >> (pos & 7)) & 1 == 1 {
+                q = JacobianPoint::add(&q, p);
+            }
+        }
+        q
+    }
+}
+
+// Wei25519 as a WeierstrassCurve, for the constant-time weierstrass_curve::Point. Its group
+// has order 8*l, so the complete formulas are exception-free only on the subgroup of order l
+// generated by G; from_sec1 does not check that a decoded point lies in it.
+#[derive(Clone, Debug)]
+pub struct Wei25519;
+
+impl WeierstrassCurve for Wei25519 {
+    type Field = Element;
+
+    fn a() -> Element {
+        AffinePoint::A.clone()
+    }
+
+    fn b() -> Element {
+        AffinePoint::B.clone()
+    }
+
+    fn generator() -> (Element, Element) {
+        let g = AffinePoint::generator();
+        (g.x, g.y)
+    }
+
+    // l = 2^252 + 27742317777372353535851937790883648493
+    const ORDER: &'static [u8] = &[
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x14, 0xde, 0xf9, 0xde, 0xa2, 0xf7, 0x9c, 0xd6, 0x58, 0x12, 0x63, 0x1a, 0x5c, 0xf5, 0xd3, 0xed];
+    const COFACTOR: u64 = 8;
+}
+
+#[cfg(test)]
+mod weierstrass_test {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use crate::field::Element;
+    use crate::scalar::Scalar;
+    use crate::test_util::hex_decode;
+    use crate::weierstrass::{AffinePoint, JacobianPoint, Wei25519};
+    use crate::weierstrass_curve::{self, WeierstrassCurve};
+    use crate::x25519;
+
+    fn to_be_hex(b: [u8; 32]) -> String {
+        let mut b = b;
+        b.reverse();
+        hex::encode(b)
+    }
+
+    // The parameters and the generator of Wei25519 in SP 800-186, Appendix B.1.
+    #[test]
+    fn test_wei25519_parameters() {
+        assert_eq!(to_be_hex(AffinePoint::A.to_le_bytes()),
+                   "2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa984914a144");
+        assert_eq!(to_be_hex(AffinePoint::B.to_le_bytes()),
+                   "7b425ed097b425ed097b425ed097b425ed097b425ed097b4260b5e9c7710c864");
+        let g = AffinePoint::generator();
+        assert!(g.is_on_curve());
+        assert_eq!(to_be_hex(g.x.to_le_bytes()),
+                   "2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a");
+        assert_eq!(to_be_hex(g.y.to_le_bytes()),
+                   "20ae19a1b8a086b4e01edd2c7748d14c923d4d7e6d7c61b229e9c5a27eced3d9");
+
+        // G has order l.
+        let g = JacobianPoint::from_affine(&g);
+        assert!(JacobianPoint::vartime_scalar_mul(&Scalar::ORDER, &g).is_identity());
+        assert!(!JacobianPoint::vartime_scalar_mul(&Scalar::ONE.to_bytes(), &g).is_identity());
+    }
+
+    #[test]
+    fn test_group_law() {
+        let g = JacobianPoint::from_affine(&AffinePoint::generator());
+        let g2 = JacobianPoint::double(&g);
+        assert!(JacobianPoint::add(&g, &g).equal(&g2));
+        let g3 = JacobianPoint::add(&g2, &g);
+        assert!(JacobianPoint::add(&g, &g2).equal(&g3));
+        assert!(g3.to_affine().unwrap().is_on_curve());
+        assert!(JacobianPoint::add(&g3, &JacobianPoint::negate(&g3)).is_identity());
+        assert!(JacobianPoint::add(&JacobianPoint::identity(), &g3).equal(&g3));
+        assert!(JacobianPoint::identity().to_affine().is_err());
+    }
+
+    // x25519::scalar_mul clamps the scalar and runs the Montgomery ladder on u-coordinates;
+    // double-and-add on Wei25519 with the clamped scalar must land on the same u.
+    #[test]
+    fn test_ladder_agrees_with_wei25519() {
+        let mut rng = StdRng::seed_from_u64(0x25519);
+        let mut points: Vec<[u8; 32]> = vec![hex_decode("0900000000000000000000000000000000000000000000000000000000000000")
+            .try_into().unwrap()];
+        // RFC 7748, Section 6.1
+        points.push(hex_decode("de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f").try_into().unwrap());
+        while points.len() < 8 {
+            // about half of the random u-coordinates lie on the curve rather than its twist
+            let mut u: [u8; 32] = rng.gen();
+            u[31] &= 0x7F;
+            if AffinePoint::from_montgomery_u(&u, 0).is_ok() {
+                points.push(u);
+            }
+        }
+
+        for u in points {
+            let p = JacobianPoint::from_affine(&AffinePoint::from_montgomery_u(&u, 0).unwrap());
+            for _ in 0..4 {
+                let mut k: [u8; 32] = rng.gen();
+                let mut expected: [u8; 32] = [0; 32];
+                x25519::scalar_mul(k, u, &mut expected);
+
+                k[0] &= 0xF8;
+                k[31] = (k[31] & 0x7F) | 0x40;
+                let q = JacobianPoint::vartime_scalar_mul(&k, &p);
+                assert_eq!(q.to_affine().unwrap().to_montgomery_u(), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_montgomery_round_trip() {
+        let g = AffinePoint::generator();
+        let (u, v) = g.to_montgomery();
+        let h = AffinePoint::from_montgomery(&u, &v);
+        assert!(h.x.equal(&g.x) && h.y.equal(&g.y));
+        assert_eq!(g.to_montgomery_u(), hex_decode("0900000000000000000000000000000000000000000000000000000000000000").as_slice());
+
+        // u = 2 is on the twist
+        let mut two: [u8; 32] = [0; 32];
+        two[0] = 2;
+        assert!(AffinePoint::from_montgomery_u(&two, 0).is_err());
+    }
+
+    // The generic constant-time Point over Wei25519 computes the same multiples as the
+    // variable-time JacobianPoint, and G has order l.
+    #[test]
+    fn test_generic_wei25519() {
+        type Point = weierstrass_curve::Point<Wei25519>;
+        let g = Point::generator();
+        let jg = JacobianPoint::from_affine(&AffinePoint::generator());
+        let mut rng = StdRng::seed_from_u64(0x47);
+        for _ in 0..8 {
+            let k: [u8; 32] = rng.gen();
+            let mut k_be = k;
+            k_be.reverse();
+            let (x, y) = Point::scalar_mul(&k_be, &g).to_affine().unwrap();
+            let expected = JacobianPoint::vartime_scalar_mul(&k, &jg).to_affine().unwrap();
+            assert!(x.equal(&expected.x) && y.equal(&expected.y));
+        }
+
+        let mut order = Scalar::ORDER;
+        order.reverse();
+        assert_eq!(Wei25519::ORDER, order.as_slice());
+        assert!(Point::basepoint_mul(Wei25519::ORDER).is_identity());
+        assert!(g.is_torsion_free());
+        let g2 = Point::double(&g);
+        assert!(Point::add(&g, &g).equal(&g2));
+        assert!(Point::add(&g2, &Point::negate(&g2)).is_identity());
+
+        let uncompressed = g.to_uncompressed().unwrap();
+        assert_eq!(hex::encode(&uncompressed[1..33]),
+                   "2aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaad245a");
+        assert!(Point::from_sec1(&uncompressed).unwrap().equal(&g));
+        let compressed = g.to_compressed().unwrap();
+        // y is odd
+        assert_eq!(compressed[0], 0x03);
+        assert!(Point::from_sec1(&compressed).unwrap().equal(&g));
+
+        // (A/3, 0), the image of the point u = 0 of order two, decodes but is not torsion-free
+        let zero = Element::ZERO.clone();
+        let mut delta = AffinePoint::from_montgomery(&zero, &zero).x.to_le_bytes();
+        delta.reverse();
+        let mut two_torsion = vec![0x04];
+        two_torsion.extend_from_slice(&delta);
+        two_torsion.extend_from_slice(&[0; 32]);
+        let t2 = Point::from_sec1(&two_torsion).unwrap();
+        assert!(!t2.is_torsion_free());
+        assert!(Point::double(&t2).is_identity());
+        assert!(Point::mul_by_cofactor(&t2).is_identity());
+    }
+}
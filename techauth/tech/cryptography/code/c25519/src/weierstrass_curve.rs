@@ -0,0 +1,403 @@
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+
+use crate::prime_field::PrimeField;
+
+// WeierstrassCurve describes a short-Weierstrass curve y^2 = x^3 + a*x + b over a PrimeField,
+// with a generator of prime order n and the cofactor h = #E / n. Point<C> implements the
+// group of any such curve, so a new curve needs only its field and constants.
+//
+// The field constants are functions, since PrimeField constructors are not const.
+pub trait WeierstrassCurve {
+    type Field: PrimeField;
+
+    fn a() -> Self::Field;
+    fn b() -> Self::Field;
+    // the affine coordinates of the generator G
+    fn generator() -> (Self::Field, Self::Field);
+    // n, the order of G, as a big-endian integer
+    const ORDER: &'static [u8];
+    const COFACTOR: u64;
+}
+
+// Point is a point in homogeneous projective coordinates (X:Y:Z), x = X/Z and y = Y/Z, with
+// the identity (0:1:0). Addition and doubling use Algorithms 1 and 3 of Renes, Costello and
+// Batina for a general a, which p256::Point and secp256k1::Point specialize to a = -3 and a = 0.
+// They are complete on any group of odd order [RCB, Theorem 1]: on a curve with points of
+// order two, such as Wei25519, they stay exceptional-free inside the odd-order subgroup.
+#[derive(Debug)]
+pub struct Point<C: WeierstrassCurve> {
+    pub(crate) x: C::Field,
+    pub(crate) y: C::Field,
+    pub(crate) z: C::Field,
+    curve: PhantomData<C>,
+}
+
+// Clone is not derived, as that would require C: Clone.
+impl<C: WeierstrassCurve> Clone for Point<C> {
+    fn clone(&self) -> Point<C> {
+        Point::new(self.x.clone(), self.y.clone(), self.z.clone())
+    }
+}
+
+impl<C: WeierstrassCurve> Point<C> {
+    fn new(x: C::Field, y: C::Field, z: C::Field) -> Point<C> {
+        Point { x, y, z, curve: PhantomData }
+    }
+
+    pub fn identity() -> Point<C> {
+        Point::new(C::Field::zero(), C::Field::one(), C::Field::zero())
+    }
+
+    pub fn generator() -> Point<C> {
+        let (x, y) = C::generator();
+        Point::new(x, y, C::Field::one())
+    }
+
+    // curve_rhs returns x^3 + a*x + b.
+    fn curve_rhs(x: &C::Field) -> C::Field {
+        let x2_a = C::Field::add(&C::Field::square(x), &C::a());
+        C::Field::add(&C::Field::mul(&x2_a, x), &C::b())
+    }
+
+    pub fn is_on_curve(x: &C::Field, y: &C::Field) -> bool {
+        C::Field::ct_equal(&C::Field::square(y), &Point::<C>::curve_rhs(x)) == 1
+    }
+
+    // from_affine returns the point (x, y), or an error if it is not on the curve.
+    pub fn from_affine(x: &C::Field, y: &C::Field) -> Result<Point<C>, ()> {
+        if !Point::<C>::is_on_curve(x, y) {
+            return Err(());
+        }
+        Ok(Point::new(x.clone(), y.clone(), C::Field::one()))
+    }
+
+    // to_affine returns (x, y), or an error for the identity.
+    pub fn to_affine(&self) -> Result<(C::Field, C::Field), ()> {
+        if C::Field::is_zero(&self.z) == 1 {
+            return Err(());
+        }
+        let z_inv = C::Field::invert(&self.z);
+        Ok((C::Field::mul(&self.x, &z_inv), C::Field::mul(&self.y, &z_inv)))
+    }
+
+    // is_identity checks for (0:Y:0) with Y != 0. On a curve with points of order two, the
+    // formulas can return (0:0:0) when P - Q has order two, which is no point at all and is
+    // not the identity; every later operation keeps it at (0:0:0).
+    pub fn is_identity(&self) -> bool {
+        C::Field::is_zero(&self.z) & (1 - C::Field::is_zero(&self.y)) == 1
+    }
+
+    // Checks whether two points are equal: X1*Z2 == X2*Z1 and Y1*Z2 == Y2*Z1.
+    pub fn equal(&self, q: &Point<C>) -> bool {
+        let f = |a: &C::Field, b: &C::Field, c: &C::Field, d: &C::Field| {
+            C::Field::ct_equal(&C::Field::mul(a, b), &C::Field::mul(c, d))
+        };
+        f(&self.x, &q.z, &q.x, &self.z) & f(&self.y, &q.z, &q.y, &self.z) == 1
+    }
+
+    pub fn negate(p: &Point<C>) -> Point<C> {
+        Point::new(p.x.clone(), C::Field::neg(&p.y), p.z.clone())
+    }
+
+    // select returns a if cond == 1, and b if cond == 0.
+    pub fn select(a: &Point<C>, b: &Point<C>, cond: u32) -> Point<C> {
+        Point::new(
+            C::Field::select(&a.x, &b.x, cond),
+            C::Field::select(&a.y, &b.y, cond),
+            C::Field::select(&a.z, &b.z, cond))
+    }
+
+    // add returns p + q with Algorithm 1 of Renes-Costello-Batina: 12M + 3ma + 2m3b + 23a.
+    pub fn add(p: &Point<C>, q: &Point<C>) -> Point<C> {
+        type F<C> = <C as WeierstrassCurve>::Field;
+        let (a, b3) = (C::a(), F::<C>::mul(&F::<C>::from_u64(3), &C::b()));
+        let t0 = F::<C>::mul(&p.x, &q.x);
+        let t1 = F::<C>::mul(&p.y, &q.y);
+        let t2 = F::<C>::mul(&p.z, &q.z);
+        let t3 = F::<C>::mul(&F::<C>::add(&p.x, &p.y), &F::<C>::add(&q.x, &q.y));
+        let t3 = F::<C>::sub(&t3, &F::<C>::add(&t0, &t1));
+        let t4 = F::<C>::mul(&F::<C>::add(&p.x, &p.z), &F::<C>::add(&q.x, &q.z));
+        let t4 = F::<C>::sub(&t4, &F::<C>::add(&t0, &t2));
+        let t5 = F::<C>::mul(&F::<C>::add(&p.y, &p.z), &F::<C>::add(&q.y, &q.z));
+        let t5 = F::<C>::sub(&t5, &F::<C>::add(&t1, &t2));
+        let z3 = F::<C>::add(&F::<C>::mul(&b3, &t2), &F::<C>::mul(&a, &t4));
+        let x3 = F::<C>::sub(&t1, &z3);
+        let z3 = F::<C>::add(&t1, &z3);
+        let y3 = F::<C>::mul(&x3, &z3);
+        let t1 = F::<C>::add(&t0, &F::<C>::add(&t0, &t0));
+        let t2 = F::<C>::mul(&a, &t2);
+        let t4 = F::<C>::mul(&b3, &t4);
+        let t1 = F::<C>::add(&t1, &t2);
+        let t2 = F::<C>::mul(&a, &F::<C>::sub(&t0, &t2));
+        let t4 = F::<C>::add(&t4, &t2);
+        let y3 = F::<C>::add(&y3, &F::<C>::mul(&t1, &t4));
+        let x3 = F::<C>::sub(&F::<C>::mul(&t3, &x3), &F::<C>::mul(&t5, &t4));
+        let z3 = F::<C>::add(&F::<C>::mul(&t5, &z3), &F::<C>::mul(&t3, &t1));
+        Point::new(x3, y3, z3)
+    }
+
+    // double returns 2p with Algorithm 3 of Renes-Costello-Batina: 8M + 3S + 3ma + 2m3b + 15a.
+    pub fn double(p: &Point<C>) -> Point<C> {
+        type F<C> = <C as WeierstrassCurve>::Field;
+        let (a, b3) = (C::a(), F::<C>::mul(&F::<C>::from_u64(3), &C::b()));
+        let t0 = F::<C>::square(&p.x);
+        let t1 = F::<C>::square(&p.y);
+        let t2 = F::<C>::square(&p.z);
+        let t3 = F::<C>::mul(&p.x, &p.y);
+        let t3 = F::<C>::add(&t3, &t3);
+        let z3 = F::<C>::mul(&p.x, &p.z);
+        let z3 = F::<C>::add(&z3, &z3);
+        let y3 = F::<C>::add(&F::<C>::mul(&a, &z3), &F::<C>::mul(&b3, &t2));
+        let x3 = F::<C>::sub(&t1, &y3);
+        let y3 = F::<C>::mul(&x3, &F::<C>::add(&t1, &y3));
+        let x3 = F::<C>::mul(&t3, &x3);
+        let z3 = F::<C>::mul(&b3, &z3);
+        let t2 = F::<C>::mul(&a, &t2);
+        let t3 = F::<C>::add(&F::<C>::mul(&a, &F::<C>::sub(&t0, &t2)), &z3);
+        let t0 = F::<C>::add(&F::<C>::add(&t0, &F::<C>::add(&t0, &t0)), &t2);
+        let y3 = F::<C>::add(&y3, &F::<C>::mul(&t0, &t3));
+        let t2 = F::<C>::mul(&p.y, &p.z);
+        let t2 = F::<C>::add(&t2, &t2);
+        let x3 = F::<C>::sub(&x3, &F::<C>::mul(&t2, &t3));
+        let z3 = F::<C>::mul(&t2, &t1);
+        let z3 = F::<C>::add(&z3, &z3);
+        let z3 = F::<C>::add(&z3, &z3);
+        Point::new(x3, y3, z3)
+    }
+
+    // scalar_mul returns [k]P for a big-endian k of any length. It doubles and adds for every
+    // bit and keeps the sum only if the bit is set, so its running time depends only on the
+    // length of k.
+    pub fn scalar_mul(k: &[u8], p: &Point<C>) -> Point<C> {
+        let mut q = Point::identity();
+        for byte in k {
+            for i in (0..8).rev() {
+                q = Point::double(&q);
+                let sum = Point::add(&q, p);
+                q = Point::select(&sum, &q, ((byte >> i) & 1) as u32);
+            }
+        }
+        q
+    }
+
+    pub fn basepoint_mul(k: &[u8]) -> Point<C> {
+        Point::scalar_mul(k, &Point::generator())
+    }
+
+    // mul_by_cofactor returns [h]P. h is public, so it branches on its bits and starts from P
+    // instead of the identity: a cofactor that is a power of two, as for Wei25519, takes only
+    // doublings, which have no exceptions, so it clears the torsion of any point on the curve.
+    pub fn mul_by_cofactor(p: &Point<C>) -> Point<C> {
+        let h = C::COFACTOR;
+        let mut q = p.clone();
+        for i in (0..63 - h.leading_zeros()).rev() {
+            q = Point::double(&q);
+            if (h >> i) & 1 == 1 {
+                q = Point::add(&q, p);
+            }
+        }
+        q
+    }
+
+    // is_torsion_free returns true if [n]P is the identity, that is, if P lies in the subgroup
+    // generated by G. With a cofactor of 1, every point on the curve does. On the subgroup the
+    // formulas are complete; outside it [n]P is either a nonzero torsion point or (0:0:0),
+    // neither of which is the identity.
+    pub fn is_torsion_free(&self) -> bool {
+        Point::scalar_mul(C::ORDER, self).is_identity()
+    }
+
+    // to_uncompressed returns the SEC1 encoding 0x04 || x || y, with big-endian coordinates.
+    pub fn to_uncompressed(&self) -> Result<Vec<u8>, ()> {
+        let (x, y) = self.to_affine()?;
+        let mut b = vec![0x04];
+        b.extend_from_slice(&x.to_be_bytes());
+        b.extend_from_slice(&y.to_be_bytes());
+        Ok(b)
+    }
+
+    // to_compressed returns the SEC1 encoding (0x02 + (y mod 2)) || x.
+    pub fn to_compressed(&self) -> Result<Vec<u8>, ()> {
+        let (x, y) = self.to_affine()?;
+        let mut b = vec![0x02 | C::Field::is_odd(&y) as u8];
+        b.extend_from_slice(&x.to_be_bytes());
+        Ok(b)
+    }
+
+    // from_sec1 decodes a compressed or uncompressed SEC1 encoding, and validates that the
+    // coordinates are canonical and on the curve. It does not check the subgroup; callers on
+    // curves with a cofactor use is_torsion_free or mul_by_cofactor.
+    pub fn from_sec1(b: &[u8]) -> Result<Point<C>, ()> {
+        let size = C::Field::ENCODED_SIZE;
+        match b.first() {
+            Some(0x04) if b.len() == 1 + 2 * size => {
+                let x = C::Field::from_be_bytes(&b[1..1 + size])?;
+                let y = C::Field::from_be_bytes(&b[1 + size..])?;
+                Point::from_affine(&x, &y)
+            }
+            Some(0x02 | 0x03) if b.len() == 1 + size => {
+                let x = C::Field::from_be_bytes(&b[1..])?;
+                let (y, was_square) = C::Field::sqrt(&Point::<C>::curve_rhs(&x));
+                if was_square == 0 {
+                    return Err(());
+                }
+                let flip = C::Field::is_odd(&y) ^ (b[0] & 1) as u32;
+                let y = C::Field::select(&C::Field::neg(&y), &y, flip);
+                Ok(Point::new(x, y, C::Field::one()))
+            }
+            _ => Err(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod weierstrass_curve_test {
+    use crate::prime_field::gf251::{Gf251 as Toy, P};
+    use crate::prime_field::{self, PrimeField};
+    use crate::weierstrass_curve::{Point, WeierstrassCurve};
+
+    // y^2 = x^3 + 2x + 1 over GF(251) has 249 = 3 * 83 points; G = (8, 23) has order 83.
+    #[derive(Clone, Debug)]
+    struct ToyCurve;
+
+    impl WeierstrassCurve for ToyCurve {
+        type Field = Toy;
+        fn a() -> Toy {
+            Toy(2)
+        }
+        fn b() -> Toy {
+            Toy(1)
+        }
+        fn generator() -> (Toy, Toy) {
+            (Toy(8), Toy(23))
+        }
+        const ORDER: &'static [u8] = &[83];
+        const COFACTOR: u64 = 3;
+    }
+
+    type ToyPoint = Point<ToyCurve>;
+
+    // affine_add is the textbook chord-and-tangent law, with None for the identity.
+    fn affine_add(p: Option<(u64, u64)>, q: Option<(u64, u64)>) -> Option<(u64, u64)> {
+        let ((x1, y1), (x2, y2)) = match (p, q) {
+            (None, _) => return q,
+            (_, None) => return p,
+            (Some(p), Some(q)) => (p, q),
+        };
+        if x1 == x2 && (y1 + y2) % P == 0 {
+            return None;
+        }
+        let l = if x1 == x2 {
+            (3 * x1 * x1 + 2) % P * Toy::invert(&Toy(2 * y1 % P)).0 % P
+        } else {
+            (y2 + P - y1) % P * Toy::invert(&Toy((x2 + P - x1) % P)).0 % P
+        };
+        let x3 = (l * l + 2 * P - x1 - x2) % P;
+        Some((x3, (l * ((x1 + P - x3) % P) + P - y1) % P))
+    }
+
+    fn to_option(p: &ToyPoint) -> Option<(u64, u64)> {
+        p.to_affine().ok().map(|(x, y)| (x.0, y.0))
+    }
+
+    fn all_points() -> Vec<Option<(u64, u64)>> {
+        let mut points = vec![None];
+        for x in 0..P {
+            for y in 0..P {
+                if (y * y) % P == (x * x % P * x + 2 * x + 1) % P {
+                    points.push(Some((x, y)));
+                }
+            }
+        }
+        points
+    }
+
+    fn from_option(p: Option<(u64, u64)>) -> ToyPoint {
+        match p {
+            None => ToyPoint::identity(),
+            Some((x, y)) => ToyPoint::from_affine(&Toy(x), &Toy(y)).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_toy_field() {
+        prime_field::conformance::check_all::<Toy>(251);
+    }
+
+    // The complete formulas agree with the affine group law on every pair of points.
+    #[test]
+    fn test_toy_exhaustive_group_law() {
+        let points = all_points();
+        assert_eq!(points.len(), 249);
+        for &p in &points {
+            let pp = from_option(p);
+            assert_eq!(to_option(&ToyPoint::double(&pp)), affine_add(p, p));
+            for &q in &points {
+                assert_eq!(to_option(&ToyPoint::add(&pp, &from_option(q))), affine_add(p, q));
+            }
+        }
+    }
+
+    // Every point has an order dividing 249, exactly 83 points are torsion-free, and the
+    // multiples of G are the torsion-free points.
+    #[test]
+    fn test_toy_exhaustive_scalar_mul() {
+        let points = all_points();
+        let mut torsion_free = 0;
+        for &p in &points {
+            let pp = from_option(p);
+            assert!(ToyPoint::scalar_mul(&[249], &pp).is_identity());
+            if pp.is_torsion_free() {
+                torsion_free += 1;
+            }
+            assert!(ToyPoint::mul_by_cofactor(&pp).is_torsion_free());
+        }
+        assert_eq!(torsion_free, 83);
+
+        let mut multiple = None;
+        let g = ToyPoint::generator();
+        for k in 0..83u16 {
+            assert_eq!(to_option(&ToyPoint::scalar_mul(&k.to_be_bytes(), &g)), multiple);
+            assert!(from_option(multiple).is_torsion_free());
+            multiple = affine_add(multiple, Some((8, 23)));
+        }
+        assert_eq!(multiple, None);
+    }
+
+    // Every point round-trips through both SEC1 encodings, and every other string of the
+    // right shape is rejected unless it encodes a point.
+    #[test]
+    fn test_toy_exhaustive_sec1() {
+        let points = all_points();
+        for &p in &points[1..] {
+            let pp = from_option(p);
+            let (x, y) = p.unwrap();
+            assert_eq!(pp.to_uncompressed().unwrap(), vec![0x04, x as u8, y as u8]);
+            assert_eq!(pp.to_compressed().unwrap(), vec![0x02 | (y & 1) as u8, x as u8]);
+            assert!(ToyPoint::from_sec1(&pp.to_compressed().unwrap()).unwrap().equal(&pp));
+        }
+        assert!(ToyPoint::identity().to_compressed().is_err());
+
+        let mut compressed = 0;
+        for prefix in [0x02, 0x03] {
+            for x in 0..=255u8 {
+                if let Ok(p) = ToyPoint::from_sec1(&[prefix, x]) {
+                    assert!(points.contains(&to_option(&p)));
+                    compressed += 1;
+                }
+            }
+        }
+        // no point has y = 0, since the group has odd order
+        assert_eq!(compressed, 248);
+        for x in 0..=255u8 {
+            for y in 0..=255u8 {
+                let valid = ToyPoint::from_sec1(&[0x04, x, y]).is_ok();
+                assert_eq!(valid, points.contains(&Some((x as u64, y as u64))));
+            }
+        }
+        assert!(ToyPoint::from_sec1(&[0x04, 8]).is_err());
+        assert!(ToyPoint::from_sec1(&[0x00]).is_err());
+    }
+}
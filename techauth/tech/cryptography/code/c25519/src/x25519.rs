@@ -1,67 +1,166 @@
+#![allow(dead_code)]
+
+use rand::{CryptoRng, RngCore};
+
+use crate::edwards::Point;
 use crate::field;
+use crate::montgomery::MontgomeryPoint;
 use crate::subtle;
 
+pub(crate) fn scalar_clamp(scalar: [u8; 32]) -> [u8; 32] {
+    let mut clamped: [u8; 32] = scalar;
+    clamped[0] &= 0xF8;
+    clamped[31] = (clamped[31] & 0x7F) | 0x40;
+    clamped
+}
+
 // scalar: little endian sequence of bytes
 // point: little endian sequence of bytes
 pub fn scalar_mul(scalar: [u8; 32], point: [u8; 32], dst: &mut [u8; 32]) {
-    fn scalar_clamp(scalar: [u8; 32]) -> [u8; 32] {
-        let mut clamped: [u8; 32] = scalar.clone();
-        clamped[0] &= 0xF8;
-        clamped[31] = (clamped[31] & 0x7F) | 0x40;
-        clamped
+    let (q, _) = MontgomeryPoint::ladder(&scalar_clamp(scalar), &MontgomeryPoint::from_u(&point));
+    *dst = q.to_u();
+}
+
+// N = 8*l*l', the least common multiple of the order 8*l of Curve25519 and the order 4*l' of
+// its twist, as little-endian 64-bit limbs. It is 508 bits long, and l' is the prime
+//     l' = 2^253 - 55484635554744707071703875581767296995.
+// [N]P is the point at infinity for every u-coordinate in GF(p).
+const CURVE_AND_TWIST_EXPONENT: [u64; 8] = [
+    0x0bf4b83b29b3cec8, 0xa110920000d1b1d9, 0x231138c2de80a413, 0x64c66bee483cf65c,
+    0xfffffffffffffffb, 0xffffffffffffffff, 0xffffffffffffffff, 0x0fffffffffffffff,
+];
+
+// blind_scalar returns k + r*N as a 576-bit little-endian integer.
+fn blind_scalar(k: &[u8; 32], r: u64) -> [u8; 72] {
+    let mut blinded: [u8; 72] = [0; 72];
+    let mut carry: u128 = 0;
+    for (i, n) in CURVE_AND_TWIST_EXPONENT.iter().enumerate() {
+        let ki = if i < 4 { u64::from_le_bytes(k[8 * i..8 * i + 8].try_into().unwrap()) } else { 0 };
+        let t = (r as u128) * (*n as u128) + ki as u128 + carry;
+        blinded[8 * i..8 * i + 8].copy_from_slice(&(t as u64).to_le_bytes());
+        carry = t >> 64;
     }
+    blinded[64..].copy_from_slice(&(carry as u64).to_le_bytes());
+    blinded
+}
 
-    let clamped = scalar_clamp(scalar);
-    let x1 = field::Element::from_le_bytes(point);
-    let mut x2 = field::Element::ONE.clone();
-    let mut x3 = x1.clone();
-    let mut z2 = field::Element::ZERO.clone();
-    let mut z3 = field::Element::ONE.clone();
-    let mut swap: u32 = 0;
-
-    for pos in (0..=254).rev() {
-        let bit: u32 = ((clamped[pos / 8] >> (pos & 7)) & 1) as u32;
-        swap ^= bit;
-        field::Element::swap(&mut x2, &mut x3, swap);
-        field::Element::swap(&mut z2, &mut z3, swap);
-        swap = bit;
-
-        let t0 = field::Element::subtract(&x3, &z3);
-        let t1 = field::Element::subtract(&x2, &z2);
-        x2 = field::Element::add(&x2, &z2);
-        z2 = field::Element::add(&x3, &z3);
-        z3 = field::Element::multiply(&t0, &x2);
-        z2 = field::Element::multiply(&z2, &t1);
-        let t0 = field::Element::square(&t1);
-        let t1 = field::Element::square(&x2);
-        x3 = field::Element::add(&z3, &z2);
-        z2 = field::Element::subtract(&z3, &z2);
-        x2 = field::Element::multiply(&t1, &t0);
-        let t1 = field::Element::subtract(&t1, &t0);
-        z2 = field::Element::square(&z2);
-        z3 = field::Element::mul32(&t1, 121666);
-        x3 = field::Element::square(&x3);
-        let t0 = field::Element::add(&t0, &z3);
-        z3 = field::Element::multiply(&x1, &z2);
-        z2 = field::Element::multiply(&t1, &t0)
+// scalar_mul_hardened computes the same as scalar_mul, with two countermeasures against
+// power analysis of the ladder from Coron, "Resistance against differential power analysis
+// for elliptic curve cryptosystems" (CHES 1999):
+//   - the point is randomized as (lambda*u : lambda) for a random nonzero lambda, so the
+//     intermediate coordinates cannot be predicted from u;
+//   - the clamped scalar k is blinded as k + r*N for a random 64-bit r, so the bits that
+//     drive the ladder change with every call. [N]P vanishes on the curve and on its twist,
+//     so the result equals that of scalar_mul for every u.
+// The blinded scalar is 576 bits long, which makes the ladder about 2.25 times slower.
+pub fn scalar_mul_hardened<R: RngCore + CryptoRng>(scalar: [u8; 32], point: [u8; 32], dst: &mut [u8; 32], rng: &mut R) {
+    let mut lambda = field::Element::ZERO.clone();
+    while lambda.equal(field::Element::ZERO) {
+        let mut b: [u8; 64] = [0; 64];
+        rng.fill_bytes(&mut b);
+        lambda = field::Element::from_wide_bytes(&b);
     }
+    let p = MontgomeryPoint {
+        x: field::Element::multiply(&field::Element::from_le_bytes(point), &lambda),
+        z: lambda,
+    };
+    let k = blind_scalar(&scalar_clamp(scalar), rng.next_u64());
+    let (q, _) = MontgomeryPoint::ladder(&k, &p);
+    *dst = q.to_u();
+}
 
-    field::Element::swap(&mut x2, &mut x3, swap);
-    field::Element::swap(&mut z2, &mut z3, swap);
+// scalar_base_mul computes the same result as scalar_mul(scalar, 9), without the ladder.
+// The base point u = 9 corresponds to the edwards25519 base point B under the birational map
+//     u = (1 + y) / (1 - y) = (Z + Y) / (Z - Y),
+// so [k]9 is read off [k]B, which the precomputed basepoint table computes much faster.
+// The clamped scalar is a nonzero multiple of 8 below 8*l, so [k]B is never the identity
+// and Z - Y is never zero.
+pub fn scalar_base_mul(scalar: [u8; 32], dst: &mut [u8; 32]) {
+    *dst = Point::basepoint_mul(&scalar_clamp(scalar)).to_montgomery();
+}
 
-    z2 = field::Element::invert(&z2);
-    x2 = field::Element::multiply(&x2, &z2);
+// The u-coordinates of the points of order 1, 2, 4 and 8 on Curve25519 and its twist,
+// with the non-canonical encodings p and p + 1 of 0 and 1. Bit 255 is ignored as in
+// scalar_mul, so these are all the 32-octet strings that encode small-order points.
+const SMALL_ORDER_BLOCKLIST: [[u8; 32]; 7] = [
+    // 0, order 2 (and the point at infinity, whose encoding is also 0)
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // 1, order 4
+    [0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    // order 8
+    [0xe0, 0xeb, 0x7a, 0x7c, 0x3b, 0x41, 0xb8, 0xae, 0x16, 0x56, 0xe3, 0xfa, 0xf1, 0x9f, 0xc4, 0x6a,
+     0xda, 0x09, 0x8d, 0xeb, 0x9c, 0x32, 0xb1, 0xfd, 0x86, 0x62, 0x05, 0x16, 0x5f, 0x49, 0xb8, 0x00],
+    // order 8
+    [0x5f, 0x9c, 0x95, 0xbc, 0xa3, 0x50, 0x8c, 0x24, 0xb1, 0xd0, 0xb1, 0x55, 0x9c, 0x83, 0xef, 0x5b,
+     0x04, 0x44, 0x5c, 0xc4, 0x58, 0x1c, 0x8e, 0x86, 0xd8, 0x22, 0x4e, 0xdd, 0xd0, 0x9f, 0x11, 0x57],
+    // p - 1, order 4 on the twist
+    [0xec, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+    // p, a non-canonical 0
+    [0xed, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+    // p + 1, a non-canonical 1
+    [0xee, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+     0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f],
+];
+
+// is_small_order returns true if u encodes a point of order 1, 2, 4 or 8, on the curve or
+// on its twist. The multiplication of such a point by a clamped scalar, a multiple of 8,
+// always yields 0. It compares u to every entry of the blocklist, in constant time.
+pub fn is_small_order(u: &[u8; 32]) -> bool {
+    let mut b = *u;
+    b[31] &= 0x7F;
+    let mut found = false;
+    for entry in SMALL_ORDER_BLOCKLIST.iter() {
+        found |= subtle::constant_time_compare(&b, entry);
+    }
+    found
+}
 
-    x2.le_bytes(dst);
+// mul_by_cofactor returns the u-coordinate of [8]P, where P is a point with u-coordinate u
+// on the curve or its twist, with three x-only doublings. Like scalar_mul, it encodes the
+// point at infinity as 0.
+pub fn mul_by_cofactor(u: &[u8; 32]) -> [u8; 32] {
+    let mut p = MontgomeryPoint::from_u(u);
+    for _ in 0..3 {
+        p = MontgomeryPoint::xdbl(&p);
+    }
+    p.to_u()
+}
+
+// is_torsion_free returns true if u is the u-coordinate of a point of order l on Curve25519,
+// that is, of a point without small-order component. Points on the twist never are.
+pub fn is_torsion_free(u: &[u8; 32]) -> bool {
+    // The sign of x does not matter: P and -P have the same order.
+    match Point::from_montgomery(u, 0) {
+        Ok(p) => !p.is_small_order() && p.is_torsion_free(),
+        Err(()) => false,
+    }
+}
+
+// PublicKeyValidation selects which peer public keys ecdh_with_validation accepts before
+// the ladder runs. RFC 7748 asks for no validation, and relies on the check of the shared
+// secret against zero; some protocols need more.
+//
+//    Lax               any u; an all-zero shared secret is still rejected, as in ecdh
+//    RejectSmallOrder  u must not be on the small-order blocklist, as in libsodium
+//    Full              u must encode a point of order l on the curve, not on the twist
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PublicKeyValidation {
+    Lax,
+    RejectSmallOrder,
+    Full,
 }
 
 pub struct PrivateKey {
-    private: [u8; 32],
+    pub(crate) private: [u8; 32],
 }
 
 #[derive(Debug)]
 pub struct PublicKey {
-    public: [u8; 32],
+    pub(crate) public: [u8; 32],
 }
 
 impl PrivateKey {
@@ -73,13 +172,8 @@ impl PrivateKey {
     }
 
     pub fn public_key(&self) -> PublicKey {
-        let x25519_base_point: [u8; 32] = [
-            9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        ];
-        assert_eq!(x25519_base_point[0], 9);
         let mut pub_key_bytes: [u8; 32] = [0; 32];
-        assert!(!subtle::is_zero(&x25519_base_point));
-        scalar_mul(self.private, x25519_base_point, &mut pub_key_bytes);
+        scalar_base_mul(self.private, &mut pub_key_bytes);
         assert!(!subtle::is_zero(&pub_key_bytes));
         PublicKey::new(pub_key_bytes)
     }
@@ -94,6 +188,24 @@ impl PrivateKey {
             Ok(ss)
         }
     }
+
+    // ecdh_hardened computes the same shared secret as ecdh with scalar_mul_hardened.
+    pub fn ecdh_hardened<R: RngCore + CryptoRng>(&self, peer: &PublicKey, rng: &mut R) -> Result<[u8; 32], ()> {
+        let mut ss: [u8; 32] = [0; 32];
+        assert!(!subtle::is_zero(&self.private));
+        scalar_mul_hardened(self.private, peer.public, &mut ss, rng);
+        if subtle::is_zero(&ss) {
+            Err(())
+        } else {
+            Ok(ss)
+        }
+    }
+
+    // ecdh_with_validation validates peer as selected, and only then computes the shared secret.
+    pub fn ecdh_with_validation(&self, peer: &PublicKey, validation: PublicKeyValidation) -> Result<[u8; 32], ()> {
+        peer.validate(validation)?;
+        self.ecdh(peer)
+    }
 }
 
 impl PublicKey {
@@ -102,20 +214,31 @@ impl PublicKey {
             public: key
         }
     }
+
+    pub fn validate(&self, validation: PublicKeyValidation) -> Result<(), ()> {
+        let valid = match validation {
+            PublicKeyValidation::Lax => true,
+            PublicKeyValidation::RejectSmallOrder => !is_small_order(&self.public),
+            PublicKeyValidation::Full => !is_small_order(&self.public) && is_torsion_free(&self.public),
+        };
+        if valid {
+            Ok(())
+        } else {
+            Err(())
+        }
+    }
 }
 
 #[cfg(test)]
 mod test_x25519 {
-    use rand::Rng;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
 
+    use crate::edwards::Point;
     use crate::field;
-    use crate::x25519::{PrivateKey, PublicKey};
-
-    fn hex_decode(s: &str) -> Vec<u8> {
-        let r = hex::decode(s);
-        assert!(r.is_ok());
-        r.unwrap()
-    }
+    use crate::montgomery::MontgomeryPoint;
+    use crate::test_util::hex_decode;
+    use crate::x25519::{self, PrivateKey, PublicKey};
 
     #[test]
     fn test_failure_01() {
@@ -124,6 +247,7 @@ mod test_x25519 {
             let pub_key = PublicKey::new(public);
             let res = pr_key.ecdh(&pub_key);
             assert!(matches!(res, Err(())));
+            assert!(pr_key.ecdh_hardened(&pub_key, &mut StdRng::seed_from_u64(0xDEF)).is_err());
         }
 
         let identity: &[u8] = &hex_decode("0000000000000000000000000000000000000000000000000000000000000000");
@@ -146,6 +270,7 @@ mod test_x25519 {
             let peer_pub_key = PublicKey::new(peer_pub_key_bytes);
             let ecdh_res = pr_key.ecdh(&peer_pub_key);
             assert!(matches!(ecdh_res, Err(())));
+            assert!(pr_key.ecdh_hardened(&peer_pub_key, &mut StdRng::seed_from_u64(0xDEF)).is_err());
         }
 
         {
@@ -185,6 +310,9 @@ mod test_x25519 {
         let peer_pub_key = PublicKey::new(peer_pub_key_bytes);
         let ecdh_res = pr_key.ecdh(&peer_pub_key);
         assert!(matches!(ecdh_res, Ok(ss) if ss == expected_ss_bytes));
+        // the hardened path must agree on every vector
+        let ecdh_res = pr_key.ecdh_hardened(&peer_pub_key, &mut StdRng::seed_from_u64(0xDEF));
+        assert!(matches!(ecdh_res, Ok(ss) if ss == expected_ss_bytes));
     }
 
     // X25519 test vector from RFC 7748, Section 6.1.
@@ -258,6 +386,112 @@ mod test_x25519 {
         }
     }
 
+    // scalar_base_mul must agree with the ladder applied to the base point 9.
+    #[test]
+    fn test_scalar_base_mul() {
+        let mut nine: [u8; 32] = [0; 32];
+        nine[0] = 9;
+        let mut rng = StdRng::seed_from_u64(0x9);
+        for _ in 0..32 {
+            let k: [u8; 32] = rng.gen();
+            let mut expected: [u8; 32] = [0; 32];
+            let mut u: [u8; 32] = [0; 32];
+            x25519::scalar_mul(k, nine, &mut expected);
+            x25519::scalar_base_mul(k, &mut u);
+            assert_eq!(u, expected);
+        }
+    }
+
+    // Random points on the curve and on the twist, with fresh randomness on every call.
+    #[test]
+    fn test_scalar_mul_hardened() {
+        let mut rng = StdRng::seed_from_u64(0xB11D);
+        for _ in 0..16 {
+            let k: [u8; 32] = rng.gen();
+            let u: [u8; 32] = rng.gen();
+            let mut expected: [u8; 32] = [0; 32];
+            x25519::scalar_mul(k, u, &mut expected);
+            for _ in 0..2 {
+                let mut ss: [u8; 32] = [0; 32];
+                x25519::scalar_mul_hardened(k, u, &mut ss, &mut rng);
+                assert_eq!(ss, expected);
+            }
+        }
+
+        // k + r*N for the largest r still fits, and r = 0 leaves k alone.
+        let k = x25519::scalar_clamp([0xFF; 32]);
+        assert_eq!(&x25519::blind_scalar(&k, 0)[..32], &k);
+        assert_eq!(&x25519::blind_scalar(&k, 0)[32..], &[0; 40]);
+        let blinded = x25519::blind_scalar(&k, u64::MAX);
+        let mut nine: [u8; 32] = [0; 32];
+        nine[0] = 9;
+        let (q, _) = MontgomeryPoint::ladder(&blinded, &MontgomeryPoint::from_u(&nine));
+        let mut expected: [u8; 32] = [0; 32];
+        x25519::scalar_mul(k, nine, &mut expected);
+        assert_eq!(q.to_u(), expected);
+    }
+
+    #[test]
+    fn test_small_order_blocklist() {
+        for entry in x25519::SMALL_ORDER_BLOCKLIST {
+            assert!(x25519::is_small_order(&entry));
+            assert_eq!(x25519::mul_by_cofactor(&entry), [0; 32]);
+            assert!(!x25519::is_torsion_free(&entry));
+            // bit 255 is ignored
+            let mut high = entry;
+            high[31] |= 0x80;
+            assert!(x25519::is_small_order(&high));
+            assert_eq!(x25519::mul_by_cofactor(&high), [0; 32]);
+        }
+
+        let mut rng = StdRng::seed_from_u64(0xB10C);
+        for _ in 0..32 {
+            let u: [u8; 32] = rng.gen();
+            assert!(!x25519::is_small_order(&u));
+            assert_ne!(x25519::mul_by_cofactor(&u), [0; 32]);
+        }
+    }
+
+    #[test]
+    fn test_public_key_validation() {
+        use x25519::PublicKeyValidation::{Full, Lax, RejectSmallOrder};
+
+        let pr_key = PrivateKey::new(hex_decode("77076D0A7318A57D3C16C17251B26645DF4C2F87EBC0992AB177FBA51DB92C2A")
+            .try_into().unwrap());
+        let peer = PublicKey::new(hex_decode("DE9EDB7D7B7DC1B4D35B61C2ECE435373F8343C85B78674DADFC7E146F882B4F")
+            .try_into().unwrap());
+        for validation in [Lax, RejectSmallOrder, Full] {
+            assert!(pr_key.ecdh_with_validation(&peer, validation).is_ok());
+        }
+        assert!(x25519::is_torsion_free(&peer.public));
+
+        // a non-canonical small-order point
+        let one = PublicKey::new(hex_decode("EEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7F")
+            .try_into().unwrap());
+        for validation in [RejectSmallOrder, Full] {
+            assert!(one.validate(validation).is_err());
+        }
+        assert!(one.validate(Lax).is_ok());
+        assert!(pr_key.ecdh_with_validation(&one, Lax).is_err());
+
+        // The peer key with a point of order 8 added: the shared secret is the same, since
+        // the clamped scalar is a multiple of 8, and only Full notices.
+        let t8 = Point::from_bytes(&hex_decode("c7176a703d4dd84fba3c0b760d10670f2a2053fa2c39ccc64ec7fd7792ac037a")
+            .try_into().unwrap()).unwrap();
+        let p = Point::from_montgomery(&peer.public, 0).unwrap();
+        let dirty = PublicKey::new(Point::add(&p, &t8).to_montgomery());
+        assert_eq!(pr_key.ecdh_with_validation(&dirty, RejectSmallOrder), pr_key.ecdh(&peer));
+        assert!(pr_key.ecdh_with_validation(&dirty, Full).is_err());
+        assert_eq!(x25519::mul_by_cofactor(&dirty.public), x25519::mul_by_cofactor(&peer.public));
+
+        // u = 2 lies on the twist.
+        let mut two: [u8; 32] = [0; 32];
+        two[0] = 2;
+        let twist = PublicKey::new(two);
+        assert!(twist.validate(RejectSmallOrder).is_ok());
+        assert!(twist.validate(Full).is_err());
+    }
+
     #[test]
     fn test_mul_001() {
         {
@@ -0,0 +1,192 @@
+#![allow(dead_code)]
+
+use crate::field448::Element;
+use crate::subtle;
+
+// X448, the Diffie-Hellman function on Curve448, v^2 = u^3 + 156326*u^2 + u over
+// GF(2^448 - 2^224 - 1), as specified in RFC 7748, Section 5. It offers about 224 bits of
+// security. Scalars and u-coordinates are 56 octets long, and the base point is u = 5.
+
+pub const KEY_SIZE: usize = 56;
+
+// (A - 2) / 4, for the doubling formula of RFC 7748
+const A24: u32 = 39081;
+
+// RFC 7748 decodes the scalar by clearing the two low bits, so that it is a multiple of the
+// cofactor 4, and by setting the top bit, so that the ladder always runs over 448 bits.
+fn scalar_clamp(scalar: [u8; KEY_SIZE]) -> [u8; KEY_SIZE] {
+    let mut clamped: [u8; KEY_SIZE] = scalar;
+    clamped[0] &= 0xFC;
+    clamped[55] |= 0x80;
+    clamped
+}
+
+// scalar: little endian sequence of bytes
+// point: little endian sequence of bytes
+// The Montgomery ladder of RFC 7748, Section 5, with (X2:Z2) = [k]P and (X3:Z3) = [k+1]P
+// conditionally swapped in constant time. Non-canonical u-coordinates are reduced.
+pub fn scalar_mul(scalar: [u8; KEY_SIZE], point: [u8; KEY_SIZE], dst: &mut [u8; KEY_SIZE]) {
+    let k = scalar_clamp(scalar);
+    let x1 = Element::from_le_bytes(&point);
+    let mut x2 = Element::ONE.clone();
+    let mut z2 = Element::ZERO.clone();
+    let mut x3 = x1.clone();
+    let mut z3 = Element::ONE.clone();
+    let mut swap: u32 = 0;
+
+    for pos in (0..448).rev() {
+        let bit: u32 = ((k[pos / 8] >> (pos & 7)) & 1) as u32;
+        swap ^= bit;
+        Element::swap(&mut x2, &mut x3, swap);
+        Element::swap(&mut z2, &mut z3, swap);
+        swap = bit;
+
+        let a = Element::add(&x2, &z2);
+        let aa = Element::square(&a);
+        let b = Element::subtract(&x2, &z2);
+        let bb = Element::square(&b);
+        let e = Element::subtract(&aa, &bb);
+        let c = Element::add(&x3, &z3);
+        let d = Element::subtract(&x3, &z3);
+        let da = Element::multiply(&d, &a);
+        let cb = Element::multiply(&c, &b);
+        x3 = Element::square(&Element::add(&da, &cb));
+        z3 = Element::multiply(&x1, &Element::square(&Element::subtract(&da, &cb)));
+        x2 = Element::multiply(&aa, &bb);
+        z2 = Element::multiply(&e, &Element::add(&aa, &Element::mul32(&e, A24)));
+    }
+    Element::swap(&mut x2, &mut x3, swap);
+    Element::swap(&mut z2, &mut z3, swap);
+
+    *dst = Element::multiply(&x2, &Element::invert(&z2)).to_le_bytes();
+}
+
+// scalar_base_mul computes scalar_mul(scalar, 5).
+pub fn scalar_base_mul(scalar: [u8; KEY_SIZE], dst: &mut [u8; KEY_SIZE]) {
+    let mut five: [u8; KEY_SIZE] = [0; KEY_SIZE];
+    five[0] = 5;
+    scalar_mul(scalar, five, dst);
+}
+
+pub struct PrivateKey {
+    private: [u8; KEY_SIZE],
+}
+
+#[derive(Debug)]
+pub struct PublicKey {
+    pub(crate) public: [u8; KEY_SIZE],
+}
+
+impl PrivateKey {
+    pub fn new(key: [u8; KEY_SIZE]) -> PrivateKey {
+        assert!(!subtle::is_zero(&key));
+        PrivateKey {
+            private: key
+        }
+    }
+
+    pub fn public_key(&self) -> PublicKey {
+        let mut pub_key_bytes: [u8; KEY_SIZE] = [0; KEY_SIZE];
+        scalar_base_mul(self.private, &mut pub_key_bytes);
+        assert!(!subtle::is_zero(&pub_key_bytes));
+        PublicKey::new(pub_key_bytes)
+    }
+
+    // ecdh returns the shared secret, or an error if it is all zero, which happens exactly
+    // when peer is a point of small order [RFC 7748, 6.2].
+    pub fn ecdh(&self, peer: &PublicKey) -> Result<[u8; KEY_SIZE], ()> {
+        let mut ss: [u8; KEY_SIZE] = [0; KEY_SIZE];
+        assert!(!subtle::is_zero(&self.private));
+        scalar_mul(self.private, peer.public, &mut ss);
+        if subtle::is_zero(&ss) {
+            Err(())
+        } else {
+            Ok(ss)
+        }
+    }
+}
+
+impl PublicKey {
+    pub fn new(key: [u8; KEY_SIZE]) -> PublicKey {
+        PublicKey {
+            public: key
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_x448 {
+    use crate::test_util::hex_decode;
+    use crate::x448::{scalar_mul, PrivateKey, PublicKey, KEY_SIZE};
+
+    fn key(s: &str) -> [u8; KEY_SIZE] {
+        hex_decode(s).try_into().unwrap()
+    }
+
+    // RFC 7748, Section 5.2: the two single-shot X448 vectors.
+    #[test]
+    fn test_x448_rfc7748() {
+        let vectors = [
+            ("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3",
+             "06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086",
+             "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f"),
+            ("203d494428b8399352665ddca42f9de8fef600908e0d461cb021f8c538345dd77c3e4806e25f46d3315c44e0a5b4371282dd2c8d5be3095f",
+             "0fbcc2f993cd56d3305b0b7d9e55d4c1a8fb5dbb52f8e9a1e9b6201b165d015894e56c4d3570bee52fe205e28a78b91cdfbde71ce8d157db",
+             "884a02576239ff7a2f2f63b2db6a9ff37047ac13568e1e30fe63c4a7ad1b3ee3a5700df34321d62077e63633c575c1c954514e99da7c179d"),
+        ];
+        for (k, u, expected) in vectors {
+            let mut dst: [u8; KEY_SIZE] = [0; KEY_SIZE];
+            scalar_mul(key(k), key(u), &mut dst);
+            assert_eq!(dst, key(expected));
+        }
+    }
+
+    // RFC 7748, Section 5.2: k = u = 5, then k, u = X448(k, u), k, 1 and 1000 times.
+    #[test]
+    fn test_x448_iterated() {
+        let mut k: [u8; KEY_SIZE] = [0; KEY_SIZE];
+        k[0] = 5;
+        let mut u = k;
+        for i in 1..=1000 {
+            let mut dst: [u8; KEY_SIZE] = [0; KEY_SIZE];
+            scalar_mul(k, u, &mut dst);
+            u = k;
+            k = dst;
+            if i == 1 {
+                assert_eq!(k, key("3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"));
+            }
+        }
+        assert_eq!(k, key("aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"));
+    }
+
+    // RFC 7748, Section 6.2: the Diffie-Hellman exchange between Alice and Bob.
+    #[test]
+    fn test_x448_ecdh() {
+        let alice = PrivateKey::new(key("9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b"));
+        let bob = PrivateKey::new(key("1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d"));
+        assert_eq!(alice.public_key().public, key("9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0"));
+        assert_eq!(bob.public_key().public, key("3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609"));
+
+        let shared = key("07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d");
+        assert_eq!(alice.ecdh(&bob.public_key()), Ok(shared));
+        assert_eq!(bob.ecdh(&alice.public_key()), Ok(shared));
+    }
+
+    // The points of small order, u = 0, u = 1 and u = p - 1, give an all-zero shared secret,
+    // and so do their non-canonical encodings p and p + 1.
+    #[test]
+    fn test_x448_small_order() {
+        let alice = PrivateKey::new(key("9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b"));
+        let mut p: [u8; KEY_SIZE] = [0xFF; KEY_SIZE];
+        p[28] = 0xFE;
+        let mut p_minus_1 = p;
+        p_minus_1[0] = 0xFE;
+        let mut p_plus_1: [u8; KEY_SIZE] = [0xFF; KEY_SIZE];
+        p_plus_1[..28].fill(0);
+        let mut one: [u8; KEY_SIZE] = [0; KEY_SIZE];
+        one[0] = 1;
+        for u in [[0; KEY_SIZE], one, p_minus_1, p, p_plus_1] {
+            assert!(alice.ecdh(&PublicKey::new(u)).is_err());
+        }
+    }
+}